@@ -0,0 +1,179 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::Line,
+};
+
+use crate::sanitize;
+use crate::theme::{self, Theme};
+
+/// Maps one of the 16 standard ANSI colours onto the active theme, so tool
+/// output uses the same reds and greens as the rest of the UI.
+fn palette(theme: &Theme, index: u8) -> Color {
+    let color = match index {
+        0 => Color::Black,
+        1 => theme.error,
+        2 => theme.success,
+        3 => theme.warning,
+        4 => theme.user,
+        5 => Color::Magenta,
+        6 => theme.accent,
+        7 => theme.text,
+        8 => theme.dim,
+        9 => Color::LightRed,
+        10 => theme.assistant,
+        11 => Color::LightYellow,
+        12 => Color::LightBlue,
+        13 => Color::LightMagenta,
+        14 => Color::LightCyan,
+        _ => Color::White,
+    };
+    theme::fit(color, theme.depth)
+}
+
+/// Reads a 256-colour (`5;n`) or truecolour (`2;r;g;b`) argument list.
+fn extended_color(theme: &Theme, params: &mut impl Iterator<Item = u16>) -> Option<Color> {
+    match params.next()? {
+        5 => {
+            let index = u8::try_from(params.next()?).ok()?;
+            Some(if index < 16 {
+                palette(theme, index)
+            } else {
+                theme::fit(Color::Indexed(index), theme.depth)
+            })
+        }
+        2 => {
+            let mut channel = || params.next().and_then(|v| u8::try_from(v).ok());
+            let rgb = Color::Rgb(channel()?, channel()?, channel()?);
+            Some(theme::fit(rgb, theme.depth))
+        }
+        _ => None,
+    }
+}
+
+/// Reads the colon form of an extended colour: `5:n`, or `2:cs:r:g:b` with
+/// its (usually empty) colour space id. `2:r:g:b`, which some programs
+/// write, is accepted too.
+fn colon_color(theme: &Theme, sub: &[&str]) -> Option<Color> {
+    let value = |p: &str| p.parse::<u16>().ok();
+    let args = match sub {
+        ["2", _, r, g, b, ..] => [2, value(r)?, value(g)?, value(b)?].to_vec(),
+        ["2", r, g, b] => [2, value(r)?, value(g)?, value(b)?].to_vec(),
+        ["5", n, ..] => [5, value(n)?].to_vec(),
+        _ => return None,
+    };
+    extended_color(theme, &mut args.into_iter())
+}
+
+/// Applies one SGR parameter string to `style`. Anything unrecognised is
+/// ignored; concealed text and blinking are deliberately not honoured.
+fn apply_sgr(style: Style, params: &str, base: Style, theme: &Theme) -> Style {
+    if params.is_empty() {
+        return base;
+    }
+    if params.starts_with(['<', '=', '>', '?']) {
+        return style;
+    }
+    let mut fields = params.split(';');
+
+    let mut style = style;
+    while let Some(field) = fields.next() {
+        // Colon sub-parameters belong to the field they follow.
+        let mut parts = field.split(':');
+        let code = parts
+            .next()
+            .and_then(|p| p.parse::<u16>().ok())
+            .unwrap_or(0);
+        let sub: Vec<&str> = parts.collect();
+        style = match code {
+            0 => base,
+            1 => style.add_modifier(Modifier::BOLD),
+            2 => style.add_modifier(Modifier::DIM),
+            3 => style.add_modifier(Modifier::ITALIC),
+            4 if sub.first() == Some(&"0") => style.remove_modifier(Modifier::UNDERLINED),
+            4 => style.add_modifier(Modifier::UNDERLINED),
+            7 => style.add_modifier(Modifier::REVERSED),
+            9 => style.add_modifier(Modifier::CROSSED_OUT),
+            22 => style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+            23 => style.remove_modifier(Modifier::ITALIC),
+            24 => style.remove_modifier(Modifier::UNDERLINED),
+            27 => style.remove_modifier(Modifier::REVERSED),
+            29 => style.remove_modifier(Modifier::CROSSED_OUT),
+            30..=37 => style.fg(palette(theme, (code - 30) as u8)),
+            90..=97 => style.fg(palette(theme, (code - 90 + 8) as u8)),
+            40..=47 => style.bg(palette(theme, (code - 40) as u8)),
+            100..=107 => style.bg(palette(theme, (code - 100 + 8) as u8)),
+            38 | 48 => {
+                let color = if sub.is_empty() {
+                    let mut rest = fields.by_ref().map(|p| p.parse::<u16>().unwrap_or(0));
+                    extended_color(theme, &mut rest)
+                } else {
+                    colon_color(theme, &sub)
+                };
+                match color {
+                    Some(color) if code == 38 => style.fg(color),
+                    Some(color) => style.bg(color),
+                    None => style,
+                }
+            }
+            39 => match base.fg {
+                Some(fg) => style.fg(fg),
+                None => style.fg(Color::Reset),
+            },
+            49 => match base.bg {
+                Some(bg) => style.bg(bg),
+                None => style.bg(Color::Reset),
+            },
+            _ => style,
+        };
+    }
+    style
+}
+
+/// Renders untrusted tool output, turning SGR colour codes into styles and
+/// dropping every other escape sequence. Controls and bidi characters are
+/// escaped exactly as `sanitize::styled_lines` does.
+pub fn styled_lines(text: &str, base: Style, marker: Style, theme: &Theme) -> Vec<Line<'static>> {
+    sanitize::styled_lines_with(text, base, marker, |style, params, final_byte| {
+        if final_byte == 'm' {
+            apply_sgr(style, params, base, theme)
+        } else {
+            style
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sgr(params: &str) -> Style {
+        let theme = Theme::default();
+        apply_sgr(Style::default(), params, Style::default(), &theme)
+    }
+
+    #[test]
+    fn reads_semicolon_truecolour() {
+        assert_eq!(sgr("38;2;10;20;30").fg, Some(Color::Rgb(10, 20, 30)));
+        assert_eq!(sgr("1;48;2;10;20;30;3").bg, Some(Color::Rgb(10, 20, 30)));
+        assert!(sgr("1;48;2;10;20;30;3")
+            .add_modifier
+            .contains(Modifier::ITALIC));
+    }
+
+    #[test]
+    fn reads_colon_truecolour_as_a_group() {
+        assert_eq!(sgr("38:2::10:20:30").fg, Some(Color::Rgb(10, 20, 30)));
+        assert_eq!(sgr("38:2:0:10:20:30").fg, Some(Color::Rgb(10, 20, 30)));
+        assert_eq!(sgr("38:2:10:20:30").fg, Some(Color::Rgb(10, 20, 30)));
+        // Fields after the group are codes again.
+        let style = sgr("48:2::1:2:3;1");
+        assert_eq!(style.bg, Some(Color::Rgb(1, 2, 3)));
+        assert!(style.add_modifier.contains(Modifier::BOLD));
+    }
+
+    #[test]
+    fn reads_colon_underline_off() {
+        assert!(!sgr("4:0").add_modifier.contains(Modifier::UNDERLINED));
+        assert!(sgr("4:3").add_modifier.contains(Modifier::UNDERLINED));
+    }
+}
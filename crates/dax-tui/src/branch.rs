@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use crate::Message;
+
+/// Alternative continuations from one point in the transcript.
+struct BranchSet {
+    /// Each branch is the transcript from the fork point on. The one in
+    /// view lives in `AppState.messages`; its slot here is stale.
+    branches: Vec<Vec<Message>>,
+    current: usize,
+}
+
+/// Branches created by editing and resending a user message. The first
+/// message of every branch carries the key of its set in `Message.fork`.
+#[derive(Default)]
+pub struct Branches {
+    sets: HashMap<String, BranchSet>,
+    /// Local id of a resent message the host hasn't echoed yet.
+    pending: Option<String>,
+}
+
+impl Branches {
+    /// Moves `messages[index..]` aside as a branch and starts a new one
+    /// with the edited message.
+    pub fn fork(&mut self, messages: &mut Vec<Message>, index: usize, mut edited: Message) {
+        let mut tail = messages.split_off(index);
+        let Some(first) = tail.first_mut() else {
+            return;
+        };
+        let key = first.fork.clone().unwrap_or_else(|| first.id.clone());
+        first.fork = Some(key.clone());
+        let set = self.sets.entry(key.clone()).or_insert(BranchSet {
+            branches: vec![Vec::new()],
+            current: 0,
+        });
+        set.branches[set.current] = tail;
+        set.branches.push(Vec::new());
+        set.current = set.branches.len() - 1;
+        edited.fork = Some(key);
+        self.pending = Some(edited.id.clone());
+        messages.push(edited);
+    }
+
+    /// Id of the resent message still waiting for the host's echo.
+    pub fn take_pending(&mut self) -> Option<String> {
+        self.pending.take()
+    }
+
+    /// Position and count of siblings, e.g. `(2, 3)` for "2/3".
+    pub fn indicator(&self, key: &str) -> Option<(usize, usize)> {
+        self.sets
+            .get(key)
+            .filter(|set| set.branches.len() > 1)
+            .map(|set| (set.current + 1, set.branches.len()))
+    }
+
+    /// Swaps the branch starting at `index` for its next or previous
+    /// sibling. Returns the id of the message now at the fork point.
+    pub fn flip(
+        &mut self,
+        messages: &mut Vec<Message>,
+        index: usize,
+        forward: bool,
+    ) -> Option<String> {
+        let key = messages.get(index)?.fork.clone()?;
+        let set = self.sets.get_mut(&key)?;
+        let count = set.branches.len();
+        let next = if forward {
+            (set.current + 1) % count
+        } else {
+            (set.current + count - 1) % count
+        };
+        set.branches[set.current] = messages.split_off(index);
+        messages.append(&mut set.branches[next]);
+        set.current = next;
+        self.pending = None;
+        messages.get(index).map(|m| m.id.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Role;
+
+    fn message(id: &str) -> Message {
+        Message {
+            id: id.to_string(),
+            role: if id.starts_with('u') {
+                Role::User
+            } else {
+                Role::Assistant
+            },
+            content: id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn ids(messages: &[Message]) -> Vec<&str> {
+        messages.iter().map(|m| m.id.as_str()).collect()
+    }
+
+    fn transcript(ids: &[&str]) -> Vec<Message> {
+        ids.iter().map(|id| message(id)).collect()
+    }
+
+    #[test]
+    fn fork_moves_the_tail_aside() {
+        let mut branches = Branches::default();
+        let mut messages = transcript(&["u1", "a1", "u2", "a2"]);
+        branches.fork(&mut messages, 2, message("u3"));
+        assert_eq!(ids(&messages), ["u1", "a1", "u3"]);
+        assert_eq!(messages[2].fork.as_deref(), Some("u2"));
+        assert_eq!(branches.indicator("u2"), Some((2, 2)));
+        assert_eq!(branches.take_pending().as_deref(), Some("u3"));
+        assert_eq!(branches.take_pending(), None);
+    }
+
+    #[test]
+    fn fork_then_flip_twice_restores_both_transcripts() {
+        let mut branches = Branches::default();
+        let mut messages = transcript(&["u1", "a1", "u2", "a2"]);
+        branches.fork(&mut messages, 2, message("u3"));
+        messages.push(message("a3"));
+
+        assert_eq!(branches.flip(&mut messages, 2, true).as_deref(), Some("u2"));
+        assert_eq!(ids(&messages), ["u1", "a1", "u2", "a2"]);
+        assert_eq!(branches.indicator("u2"), Some((1, 2)));
+
+        assert_eq!(branches.flip(&mut messages, 2, true).as_deref(), Some("u3"));
+        assert_eq!(ids(&messages), ["u1", "a1", "u3", "a3"]);
+        assert_eq!(branches.indicator("u2"), Some((2, 2)));
+    }
+
+    #[test]
+    fn flip_clears_the_pending_echo() {
+        let mut branches = Branches::default();
+        let mut messages = transcript(&["u1", "a1"]);
+        branches.fork(&mut messages, 0, message("u2"));
+        branches.flip(&mut messages, 0, false);
+        assert_eq!(branches.take_pending(), None);
+    }
+
+    #[test]
+    fn reforking_a_fork_point_adds_a_sibling() {
+        let mut branches = Branches::default();
+        let mut messages = transcript(&["u1", "a1", "u2", "a2"]);
+        branches.fork(&mut messages, 2, message("u3"));
+        messages.push(message("a3"));
+        branches.fork(&mut messages, 2, message("u4"));
+
+        assert_eq!(ids(&messages), ["u1", "a1", "u4"]);
+        assert_eq!(messages[2].fork.as_deref(), Some("u2"));
+        assert_eq!(branches.indicator("u2"), Some((3, 3)));
+        assert_eq!(branches.indicator("u3"), None);
+
+        branches.flip(&mut messages, 2, true);
+        assert_eq!(ids(&messages), ["u1", "a1", "u2", "a2"]);
+        assert_eq!(branches.indicator("u2"), Some((1, 3)));
+        branches.flip(&mut messages, 2, false);
+        assert_eq!(ids(&messages), ["u1", "a1", "u4"]);
+        branches.flip(&mut messages, 2, false);
+        assert_eq!(ids(&messages), ["u1", "a1", "u3", "a3"]);
+        assert_eq!(branches.indicator("u2"), Some((2, 3)));
+    }
+
+    #[test]
+    fn nested_forks_travel_with_their_branch() {
+        let mut branches = Branches::default();
+        let mut messages = transcript(&["u1", "a1", "u2", "a2"]);
+        branches.fork(&mut messages, 2, message("u3"));
+        messages.extend(transcript(&["a3", "u5", "a5"]));
+        branches.fork(&mut messages, 4, message("u6"));
+        assert_eq!(ids(&messages), ["u1", "a1", "u3", "a3", "u6"]);
+        assert_eq!(branches.indicator("u5"), Some((2, 2)));
+
+        branches.flip(&mut messages, 2, false);
+        assert_eq!(ids(&messages), ["u1", "a1", "u2", "a2"]);
+        branches.flip(&mut messages, 2, true);
+        assert_eq!(ids(&messages), ["u1", "a1", "u3", "a3", "u6"]);
+
+        branches.flip(&mut messages, 4, true);
+        assert_eq!(ids(&messages), ["u1", "a1", "u3", "a3", "u5", "a5"]);
+        assert_eq!(branches.indicator("u5"), Some((1, 2)));
+        assert_eq!(branches.indicator("u2"), Some((2, 2)));
+    }
+
+    #[test]
+    fn flip_without_a_fork_does_nothing() {
+        let mut branches = Branches::default();
+        let mut messages = transcript(&["u1", "a1"]);
+        assert_eq!(branches.flip(&mut messages, 0, true), None);
+        assert_eq!(branches.flip(&mut messages, 5, true), None);
+        assert_eq!(ids(&messages), ["u1", "a1"]);
+        branches.fork(&mut messages, 2, message("u2"));
+        assert_eq!(ids(&messages), ["u1", "a1"]);
+        assert_eq!(branches.take_pending(), None);
+    }
+}
@@ -0,0 +1,379 @@
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+use std::path::PathBuf;
+
+use crate::export;
+use crate::keymap::{self, Action, Context, Keymap};
+use crate::sanitize;
+use crate::thinking;
+use crate::Theme;
+
+/// `$XDG_CONFIG_HOME/dax`, falling back to `~/.config/dax`.
+pub fn config_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("XDG_CONFIG_HOME").filter(|d| !d.is_empty()) {
+        return Some(PathBuf::from(dir).join("dax"));
+    }
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config").join("dax"))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SidebarPosition {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone)]
+pub struct LayoutConfig {
+    /// Sidebar width as a percentage of the terminal.
+    pub sidebar_width: u16,
+    pub sidebar_position: SidebarPosition,
+    /// Sidebar panes in display order; empty hides the sidebar.
+    pub panes: Vec<String>,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self {
+            sidebar_width: 30,
+            sidebar_position: SidebarPosition::Right,
+            panes: vec![
+                "timeline".to_string(),
+                "plan".to_string(),
+                "files".to_string(),
+                "scope".to_string(),
+            ],
+        }
+    }
+}
+
+pub const PANES: &[&str] = &["timeline", "plan", "files", "scope"];
+
+#[derive(Debug, Clone)]
+pub struct BehaviorConfig {
+    /// Keep the latest message selected as new ones arrive.
+    pub auto_follow: bool,
+    pub timestamps: bool,
+    pub confirm_quit: bool,
+    /// Modal normal/insert navigation instead of typing straight into the input.
+    pub vim_mode: bool,
+    /// Reasoning display at startup; switchable at runtime.
+    pub thinking: thinking::Mode,
+    /// Keep each session's transcript and view on disk, per working
+    /// directory, and reopen them at startup. Files are private to the user
+    /// and have secrets masked; set to false to keep nothing on disk.
+    pub restore_sessions: bool,
+}
+
+impl Default for BehaviorConfig {
+    fn default() -> Self {
+        Self {
+            auto_follow: true,
+            timestamps: false,
+            confirm_quit: false,
+            vim_mode: false,
+            thinking: thinking::Mode::default(),
+            restore_sessions: true,
+        }
+    }
+}
+
+/// Defaults for `/export`; the command's arguments override them.
+#[derive(Debug, Clone)]
+pub struct ExportConfig {
+    pub format: export::Format,
+    pub redact_outputs: bool,
+    /// Mask API keys, tokens and passwords with the built-in patterns and
+    /// `mask_patterns`.
+    pub mask_secrets: bool,
+    pub mask_patterns: Vec<regex::Regex>,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            format: export::Format::default(),
+            redact_outputs: false,
+            mask_secrets: true,
+            mask_patterns: Vec::new(),
+        }
+    }
+}
+
+/// Settings from `<config dir>/tui.toml`. Every entry that cannot be used is
+/// recorded in `diagnostics` so it can be shown at startup.
+#[derive(Default)]
+pub struct Config {
+    pub path: Option<PathBuf>,
+    pub layout: LayoutConfig,
+    pub behavior: BehaviorConfig,
+    pub export: ExportConfig,
+    pub theme: Option<String>,
+    pub keymap: Keymap,
+    pub diagnostics: Vec<String>,
+}
+
+impl Config {
+    pub fn load() -> Self {
+        let mut config = Config {
+            path: config_dir().map(|d| d.join("tui.toml")),
+            ..Default::default()
+        };
+        let Some(path) = config.path.clone().filter(|p| p.is_file()) else {
+            return config;
+        };
+        let raw = match std::fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(e) => {
+                config
+                    .diagnostics
+                    .push(format!("{}: {}", path.display(), e));
+                return config;
+            }
+        };
+        match raw.parse::<toml::Table>() {
+            Ok(table) => config.apply(&table),
+            Err(e) => config
+                .diagnostics
+                .push(format!("{}: {}", path.display(), e.message())),
+        }
+        let conflicts = config.keymap.conflicts();
+        config.diagnostics.extend(conflicts);
+        config
+    }
+
+    fn apply(&mut self, table: &toml::Table) {
+        for (key, value) in table {
+            match (key.as_str(), value) {
+                ("keybindings", toml::Value::Table(t)) => self.apply_keybindings(t),
+                ("layout", toml::Value::Table(t)) => self.apply_layout(t),
+                ("behavior" | "behaviour", toml::Value::Table(t)) => self.apply_behavior(t),
+                ("export", toml::Value::Table(t)) => self.apply_export(t),
+                ("theme", toml::Value::String(name)) => self.theme = Some(name.clone()),
+                ("theme", toml::Value::Table(t)) => match t.get("name") {
+                    Some(toml::Value::String(name)) => self.theme = Some(name.clone()),
+                    _ => self.warn("theme.name", "expected a theme name"),
+                },
+                _ => self.warn(key, "unknown section"),
+            }
+        }
+    }
+
+    fn warn(&mut self, key: &str, problem: &str) {
+        self.diagnostics.push(format!("{}: {}", key, problem));
+    }
+
+    fn apply_keybindings(&mut self, table: &toml::Table) {
+        for (key, value) in table {
+            // `[keybindings.diff]` tables scope their entries to one context;
+            // bare entries refer to global actions or any unambiguous name.
+            if let toml::Value::Table(inner) = value {
+                let Some(context) = Context::ALL.iter().find(|c| c.name() == key) else {
+                    self.warn(&format!("keybindings.{}", key), "unknown context");
+                    continue;
+                };
+                for (name, value) in inner {
+                    let full = format!("keybindings.{}.{}", key, name);
+                    match Action::lookup(*context, name) {
+                        Some(action) => self.bind(&full, action, value),
+                        None => self.warn(&full, "unknown action"),
+                    }
+                }
+                continue;
+            }
+            let full = format!("keybindings.{}", key);
+            let matches: Vec<Action> = Action::ALL
+                .iter()
+                .copied()
+                .filter(|a| a.name() == key)
+                .collect();
+            let action = Action::lookup(Context::Global, key).or(match matches.as_slice() {
+                [only] => Some(*only),
+                _ => None,
+            });
+            match action {
+                Some(action) => self.bind(&full, action, value),
+                None if matches.len() > 1 => self.warn(
+                    &full,
+                    "ambiguous action; put it under a context table such as [keybindings.diff]",
+                ),
+                None => self.warn(&full, "unknown action"),
+            }
+        }
+    }
+
+    fn bind(&mut self, key: &str, action: Action, value: &toml::Value) {
+        let specs: Vec<&str> = match value {
+            toml::Value::String(s) => vec![s.as_str()],
+            toml::Value::Array(items) => {
+                let specs: Vec<&str> = items.iter().filter_map(|v| v.as_str()).collect();
+                if specs.len() != items.len() {
+                    self.warn(key, "expected a key or a list of keys");
+                    return;
+                }
+                specs
+            }
+            _ => {
+                self.warn(key, "expected a key or a list of keys");
+                return;
+            }
+        };
+        let unbind = specs.is_empty();
+        let mut sequences = Vec::new();
+        for spec in specs {
+            match keymap::parse_sequence(spec) {
+                Ok(seq) => sequences.push(seq),
+                Err(e) => self.warn(key, &format!("\"{}\": {}", spec, e)),
+            }
+        }
+        // `[]` unbinds on purpose; otherwise keep the defaults rather than
+        // leave the action unreachable because of a typo.
+        if unbind || !sequences.is_empty() {
+            self.keymap.bind(action, sequences);
+        }
+    }
+
+    fn apply_layout(&mut self, table: &toml::Table) {
+        for (key, value) in table {
+            let full = format!("layout.{}", key);
+            match (key.as_str(), value) {
+                ("sidebar_width", toml::Value::Integer(n)) if (10..=80).contains(n) => {
+                    self.layout.sidebar_width = *n as u16;
+                }
+                ("sidebar_width", _) => self.warn(&full, "expected a percentage from 10 to 80"),
+                ("sidebar_position", toml::Value::String(s)) if s == "left" => {
+                    self.layout.sidebar_position = SidebarPosition::Left;
+                }
+                ("sidebar_position", toml::Value::String(s)) if s == "right" => {
+                    self.layout.sidebar_position = SidebarPosition::Right;
+                }
+                ("sidebar_position", _) => self.warn(&full, "expected \"left\" or \"right\""),
+                ("panes", toml::Value::Array(items)) => {
+                    let mut panes = Vec::new();
+                    for item in items {
+                        match item.as_str() {
+                            Some(p) if PANES.contains(&p) => panes.push(p.to_string()),
+                            _ => self.warn(
+                                &full,
+                                &format!("unknown pane {} (known: {})", item, PANES.join(", ")),
+                            ),
+                        }
+                    }
+                    self.layout.panes = panes;
+                }
+                ("panes", _) => self.warn(&full, "expected a list of pane names"),
+                _ => self.warn(&full, "unknown setting"),
+            }
+        }
+    }
+
+    fn apply_behavior(&mut self, table: &toml::Table) {
+        for (key, value) in table {
+            let full = format!("behavior.{}", key);
+            if key == "thinking" {
+                match value.as_str().and_then(thinking::Mode::parse) {
+                    Some(mode) => self.behavior.thinking = mode,
+                    None => self.warn(&full, "expected \"off\", \"minimal\" or \"verbose\""),
+                }
+                continue;
+            }
+            let Some(flag) = value.as_bool() else {
+                self.warn(&full, "expected true or false");
+                continue;
+            };
+            match key.as_str() {
+                "auto_follow" => self.behavior.auto_follow = flag,
+                "timestamps" => self.behavior.timestamps = flag,
+                "confirm_quit" => self.behavior.confirm_quit = flag,
+                "vim_mode" => self.behavior.vim_mode = flag,
+                "restore_sessions" => self.behavior.restore_sessions = flag,
+                _ => self.warn(&full, "unknown setting"),
+            }
+        }
+    }
+
+    fn apply_export(&mut self, table: &toml::Table) {
+        for (key, value) in table {
+            let full = format!("export.{}", key);
+            match (key.as_str(), value) {
+                ("format", toml::Value::String(name)) if export::Format::parse(name).is_some() => {
+                    self.export.format = export::Format::parse(name).unwrap_or_default();
+                }
+                ("format", _) => self.warn(&full, "expected \"markdown\", \"html\" or \"json\""),
+                ("redact_outputs", toml::Value::Boolean(flag)) => {
+                    self.export.redact_outputs = *flag
+                }
+                ("mask_secrets", toml::Value::Boolean(flag)) => self.export.mask_secrets = *flag,
+                ("redact_outputs" | "mask_secrets", _) => {
+                    self.warn(&full, "expected true or false")
+                }
+                ("mask_patterns", toml::Value::Array(items)) => {
+                    let mut patterns = Vec::new();
+                    for item in items {
+                        match item.as_str().map(export::compile_pattern) {
+                            Some(Ok(re)) => patterns.push(re),
+                            Some(Err(e)) => self.warn(&full, e.as_str()),
+                            None => self.warn(&full, &format!("expected a regex, got {}", item)),
+                        }
+                    }
+                    self.export.mask_patterns = patterns;
+                }
+                ("mask_patterns", _) => self.warn(&full, "expected a list of regexes"),
+                _ => self.warn(&full, "unknown setting"),
+            }
+        }
+    }
+}
+
+impl Config {
+    /// Popup listing the problems found while loading the config.
+    pub fn render_diagnostics(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let path = self
+            .path
+            .as_ref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| "tui.toml".to_string());
+        let mut lines = Vec::new();
+        if self.diagnostics.is_empty() {
+            lines.push(Line::from(Span::styled(
+                format!("No problems found in {}", sanitize::inline(&path)),
+                Style::default().fg(theme.text),
+            )));
+        } else {
+            lines.push(Line::from(Span::styled(
+                format!("Ignored entries in {}:", sanitize::inline(&path)),
+                Style::default().fg(theme.text),
+            )));
+            for problem in &self.diagnostics {
+                lines.push(Line::from(vec![
+                    Span::styled("  • ", Style::default().fg(theme.warning)),
+                    Span::styled(sanitize::inline(problem), Style::default().fg(theme.text)),
+                ]));
+            }
+        }
+
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let popup = crate::centered_rect(area, 90, height);
+        crate::clear_area(frame, popup, theme);
+        frame.render_widget(
+            Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.warning))
+                    .title(Span::styled(
+                        " Config diagnostics ",
+                        Style::default().fg(theme.warning).bold(),
+                    ))
+                    .title_bottom(Span::styled(
+                        " any key to close ",
+                        Style::default().fg(theme.dim),
+                    )),
+            ),
+            popup,
+        );
+    }
+}
@@ -0,0 +1,433 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Read;
+use std::path::Path;
+
+use crate::keymap::{Action, Keymap};
+use crate::sanitize;
+use crate::text::truncate;
+use crate::Theme;
+
+/// Rough bytes-per-token ratio for source text.
+const BYTES_PER_TOKEN: u64 = 4;
+/// Largest file read for a preview.
+const PREVIEW_BYTES: usize = 256 * 1024;
+
+enum Row {
+    Dir {
+        path: String,
+        bytes: u64,
+        files: usize,
+    },
+    File {
+        path: String,
+    },
+}
+
+/// What the user asked for from the files pane.
+pub enum ContextCommand {
+    Remove(String),
+    Preview(String),
+}
+
+/// Files pane of the sidebar: the host's context files as a tree grouped
+/// by directory, with sizes read from disk when the list changes.
+#[derive(Default)]
+pub struct ContextPane {
+    sizes: HashMap<String, Option<u64>>,
+    pinned: HashSet<String>,
+    filter: String,
+    filtering: bool,
+    /// Index into the visible file rows.
+    selected: usize,
+    scroll: usize,
+}
+
+impl ContextPane {
+    pub fn set_files(&mut self, files: &[String]) {
+        self.sizes = files
+            .iter()
+            .map(|f| {
+                let size = std::fs::metadata(f).ok().map(|m| m.len());
+                (f.clone(), size)
+            })
+            .collect();
+        self.pinned.retain(|p| self.sizes.contains_key(p));
+    }
+
+    pub fn is_filtering(&self) -> bool {
+        self.filtering
+    }
+
+    /// Text entry for the filter; bypasses the keymap.
+    pub fn filter_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter => self.filtering = false,
+            KeyCode::Esc => {
+                self.filtering = false;
+                self.filter.clear();
+            }
+            KeyCode::Backspace => {
+                self.filter.pop();
+            }
+            KeyCode::Char(c) => self.filter.push(c),
+            _ => {}
+        }
+        self.selected = 0;
+    }
+
+    fn bytes(&self, path: &str) -> u64 {
+        self.sizes.get(path).copied().flatten().unwrap_or(0)
+    }
+
+    /// Visible files: pinned first, then by path, narrowed by the filter.
+    fn visible<'a>(&self, files: &'a [String]) -> Vec<&'a String> {
+        let filter = self.filter.to_lowercase();
+        let mut visible: Vec<&String> = files
+            .iter()
+            .filter(|f| f.to_lowercase().contains(&filter))
+            .collect();
+        visible.sort_by_key(|f| (!self.pinned.contains(*f), f.as_str()));
+        visible
+    }
+
+    fn rows(&self, files: &[String]) -> Vec<Row> {
+        let visible = self.visible(files);
+        let mut rows = Vec::new();
+        let (pinned, rest): (Vec<&String>, Vec<&String>) =
+            visible.into_iter().partition(|f| self.pinned.contains(*f));
+        if !pinned.is_empty() {
+            rows.push(Row::Dir {
+                path: "pinned".to_string(),
+                bytes: pinned.iter().map(|f| self.bytes(f)).sum(),
+                files: pinned.len(),
+            });
+            rows.extend(pinned.into_iter().map(|f| Row::File { path: f.clone() }));
+        }
+        let mut dirs: BTreeMap<String, Vec<&String>> = BTreeMap::new();
+        for file in rest {
+            let dir = Path::new(file)
+                .parent()
+                .map(|p| p.display().to_string())
+                .filter(|p| !p.is_empty())
+                .unwrap_or_else(|| ".".to_string());
+            dirs.entry(dir).or_default().push(file);
+        }
+        for (dir, files) in dirs {
+            rows.push(Row::Dir {
+                path: format!("{}/", dir),
+                bytes: files.iter().map(|f| self.bytes(f)).sum(),
+                files: files.len(),
+            });
+            rows.extend(files.into_iter().map(|f| Row::File { path: f.clone() }));
+        }
+        rows
+    }
+
+    fn selected_path(&self, files: &[String]) -> Option<String> {
+        self.rows(files)
+            .into_iter()
+            .filter_map(|r| match r {
+                Row::File { path } => Some(path),
+                Row::Dir { .. } => None,
+            })
+            .nth(self.selected)
+    }
+
+    pub fn handle_action(&mut self, action: Action, files: &[String]) -> Option<ContextCommand> {
+        let count = self.visible(files).len();
+        match action {
+            Action::SidebarDown => self.selected = (self.selected + 1).min(count.saturating_sub(1)),
+            Action::SidebarUp => self.selected = self.selected.saturating_sub(1),
+            Action::SidebarTop => self.selected = 0,
+            Action::SidebarBottom => self.selected = count.saturating_sub(1),
+            Action::SidebarFilter => self.filtering = true,
+            Action::SidebarPin => {
+                if let Some(path) = self.selected_path(files) {
+                    if !self.pinned.remove(&path) {
+                        self.pinned.insert(path.clone());
+                    }
+                    // Follow the file as it moves in or out of the pinned group.
+                    self.selected = self
+                        .rows(files)
+                        .iter()
+                        .filter_map(|r| match r {
+                            Row::File { path } => Some(path),
+                            Row::Dir { .. } => None,
+                        })
+                        .position(|p| *p == path)
+                        .unwrap_or(0);
+                }
+            }
+            Action::SidebarRemove => {
+                let path = self.selected_path(files)?;
+                // Pinned files have to be unpinned before they can go.
+                if self.pinned.contains(&path) {
+                    return None;
+                }
+                self.selected = self.selected.min(count.saturating_sub(2));
+                return Some(ContextCommand::Remove(path));
+            }
+            Action::SidebarPreview => {
+                return self.selected_path(files).map(ContextCommand::Preview)
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Title for the pane: file count and totals.
+    pub fn title(&self, files: &[String]) -> String {
+        if files.is_empty() {
+            return " Files ".to_string();
+        }
+        let bytes: u64 = files.iter().map(|f| self.bytes(f)).sum();
+        format!(
+            " Files • {} • {} • {} tok ",
+            files.len(),
+            size_label(bytes),
+            token_label(bytes)
+        )
+    }
+
+    pub fn render(
+        &mut self,
+        frame: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        files: &[String],
+        focused: bool,
+    ) {
+        if files.is_empty() {
+            frame.render_widget(
+                Paragraph::new("No files loaded").style(Style::default().fg(theme.dim)),
+                area,
+            );
+            return;
+        }
+        let mut lines = Vec::new();
+        if self.filtering || !self.filter.is_empty() {
+            let cursor = if self.filtering { "▊" } else { "" };
+            lines.push(Line::from(Span::styled(
+                format!("/{}{}", sanitize::inline(&self.filter), cursor),
+                Style::default().fg(theme.warning),
+            )));
+        }
+        let header = lines.len();
+
+        let width = area.width as usize;
+        let mut file_index = 0;
+        let mut focus_line = 0;
+        for row in self.rows(files) {
+            match row {
+                Row::Dir { path, bytes, files } => {
+                    let left = format!("▾ {} ({})", sanitize::inline(&path), files);
+                    let right = token_label(bytes);
+                    lines.push(Line::from(vec![
+                        Span::styled(left.clone(), Style::default().fg(theme.accent)),
+                        Span::styled(pad(&left, &right, width), Style::default().fg(theme.dim)),
+                    ]));
+                }
+                Row::File { path } => {
+                    let name = Path::new(&path)
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_else(|| path.clone());
+                    let pin = if self.pinned.contains(&path) {
+                        "★"
+                    } else {
+                        " "
+                    };
+                    let left = format!("  {}{}", pin, sanitize::inline(&name));
+                    let right = match self.sizes.get(&path).copied().flatten() {
+                        Some(bytes) => format!("{} {}", size_label(bytes), token_label(bytes)),
+                        None => "missing".to_string(),
+                    };
+                    let selected = focused && file_index == self.selected;
+                    let style = if selected {
+                        Style::default().fg(theme.accent).reversed()
+                    } else {
+                        Style::default().fg(theme.text)
+                    };
+                    if file_index == self.selected {
+                        focus_line = lines.len();
+                    }
+                    let left = truncate(&left, width.saturating_sub(right.chars().count() + 1));
+                    lines.push(Line::from(vec![
+                        Span::styled(left.clone(), style),
+                        Span::styled(pad(&left, &right, width), Style::default().fg(theme.dim)),
+                    ]));
+                    file_index += 1;
+                }
+            }
+        }
+        if file_index == 0 {
+            lines.push(Line::from(Span::styled(
+                "No matching files",
+                Style::default().fg(theme.dim),
+            )));
+        }
+
+        // Keep the selection, and the directory above it, in view below
+        // the filter line.
+        let height = (area.height as usize).saturating_sub(header).max(1);
+        let focus = focus_line.saturating_sub(header);
+        if focus <= self.scroll {
+            self.scroll = focus.saturating_sub(1);
+        } else if focus >= self.scroll + height {
+            self.scroll = focus + 1 - height;
+        }
+        let mut body = lines.split_off(header);
+        self.scroll = self.scroll.min(body.len().saturating_sub(height));
+        lines.extend(body.drain(self.scroll..));
+        frame.render_widget(Paragraph::new(lines), area);
+    }
+}
+
+/// Read-only view of a context file, read from disk when opened.
+pub struct Preview {
+    path: String,
+    lines: Vec<String>,
+    note: Option<String>,
+    scroll: usize,
+    last_height: u16,
+}
+
+impl Preview {
+    pub fn open(path: &str) -> Self {
+        let (lines, note) = match read_head(path) {
+            Ok(bytes) if bytes.contains(&0) => (Vec::new(), Some("Binary file".to_string())),
+            Ok(bytes) => {
+                let truncated = bytes.len() > PREVIEW_BYTES;
+                let text = String::from_utf8_lossy(&bytes[..bytes.len().min(PREVIEW_BYTES)]);
+                let lines = text.lines().map(sanitize::inline).collect();
+                let note = truncated
+                    .then(|| format!("Showing the first {}", size_label(PREVIEW_BYTES as u64)));
+                (lines, note)
+            }
+            Err(e) => (Vec::new(), Some(format!("Cannot read file: {}", e))),
+        };
+        Self {
+            path: path.to_string(),
+            lines,
+            note,
+            scroll: 0,
+            last_height: 0,
+        }
+    }
+
+    /// Returns false when the preview should be closed.
+    pub fn handle_action(&mut self, action: Action) -> bool {
+        let page = self.last_height.saturating_sub(3).max(1) as usize;
+        match action {
+            Action::PreviewClose => return false,
+            Action::PreviewDown => self.scroll += 1,
+            Action::PreviewUp => self.scroll = self.scroll.saturating_sub(1),
+            Action::PreviewPageDown => self.scroll += page,
+            Action::PreviewPageUp => self.scroll = self.scroll.saturating_sub(page),
+            _ => {}
+        }
+        true
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme, keys: &Keymap) {
+        crate::clear_area(frame, area, theme);
+        self.last_height = area.height;
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(Span::styled(
+                format!(" {} ", sanitize::inline(&self.path)),
+                Style::default().fg(theme.accent).bold(),
+            ))
+            .title_bottom(Span::styled(
+                keys.hints(&[
+                    (Action::PreviewDown, "scroll"),
+                    (Action::PreviewClose, "close"),
+                ]),
+                Style::default().fg(theme.dim),
+            ));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let number_width = self.lines.len().to_string().len();
+        let mut lines: Vec<Line> = Vec::new();
+        if let Some(note) = &self.note {
+            lines.push(Line::from(Span::styled(
+                note.clone(),
+                Style::default().fg(theme.warning),
+            )));
+        }
+        lines.extend(self.lines.iter().enumerate().map(|(i, text)| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{:>width$} ", i + 1, width = number_width),
+                    Style::default().fg(theme.dim),
+                ),
+                Span::styled(text.clone(), Style::default().fg(theme.text)),
+            ])
+        }));
+        self.scroll = self
+            .scroll
+            .min(lines.len().saturating_sub(inner.height as usize));
+        frame.render_widget(Paragraph::new(lines).scroll((self.scroll as u16, 0)), inner);
+    }
+}
+
+/// Right-aligns `right` after `left` within `width` columns.
+fn pad(left: &str, right: &str, width: usize) -> String {
+    let used = unicode_width::UnicodeWidthStr::width(left) + right.chars().count();
+    format!("{}{}", " ".repeat(width.saturating_sub(used).max(1)), right)
+}
+
+/// Up to one byte past `PREVIEW_BYTES` of the file, so a preview never
+/// loads more than it shows.
+fn read_head(path: &str) -> std::io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    std::fs::File::open(path)?
+        .take(PREVIEW_BYTES as u64 + 1)
+        .read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn size_label(bytes: u64) -> String {
+    match bytes {
+        0..=1023 => format!("{}B", bytes),
+        1024..=1_048_575 => format!("{:.1}KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1}MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+/// `~1.2k` estimated tokens.
+fn token_label(bytes: u64) -> String {
+    let tokens = bytes.div_ceil(BYTES_PER_TOKEN);
+    match tokens {
+        0..=999 => format!("~{}", tokens),
+        1_000..=999_999 => format!("~{:.1}k", tokens as f64 / 1_000.0),
+        _ => format!("~{:.1}M", tokens as f64 / 1_000_000.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preview_reads_only_the_head() {
+        let path = std::env::temp_dir().join(format!("dax-tui-preview-{}", std::process::id()));
+        std::fs::write(&path, "line\n".repeat(PREVIEW_BYTES)).unwrap();
+        let preview = Preview::open(&path.to_string_lossy());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(preview.lines.len(), PREVIEW_BYTES / 5 + 1);
+        assert!(preview
+            .note
+            .is_some_and(|n| n.starts_with("Showing the first")));
+    }
+}
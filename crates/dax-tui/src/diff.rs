@@ -0,0 +1,746 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+use unicode_width::UnicodeWidthChar;
+
+use crate::keymap::{Action, Keymap};
+use crate::sanitize;
+use crate::Theme;
+
+/// Terminals at least this wide show diffs side by side unless the user
+/// has toggled the mode explicitly.
+const SPLIT_MIN_WIDTH: u16 = 140;
+const FILE_LIST_WIDTH: u16 = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    Context,
+    Added,
+    Removed,
+    /// `\ No newline at end of file`
+    Note,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiffLine {
+    pub kind: LineKind,
+    pub text: String,
+    pub old_no: Option<u32>,
+    pub new_no: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Hunk {
+    pub header: String,
+    pub old_start: u32,
+    pub old_len: u32,
+    pub new_start: u32,
+    pub new_len: u32,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FileDiff {
+    pub old_path: String,
+    pub new_path: String,
+    pub hunks: Vec<Hunk>,
+}
+
+impl FileDiff {
+    pub fn path(&self) -> &str {
+        if self.new_path == "/dev/null" {
+            &self.old_path
+        } else {
+            &self.new_path
+        }
+    }
+
+    pub fn stats(&self) -> (usize, usize) {
+        let mut added = 0;
+        let mut removed = 0;
+        for line in self.hunks.iter().flat_map(|h| &h.lines) {
+            match line.kind {
+                LineKind::Added => added += 1,
+                LineKind::Removed => removed += 1,
+                _ => {}
+            }
+        }
+        (added, removed)
+    }
+}
+
+fn strip_prefix(path: &str) -> String {
+    let path = path.split('\t').next().unwrap_or(path).trim();
+    path.strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path)
+        .to_string()
+}
+
+/// Parses `@@ -12,3 +12,4 @@ fn main()` into its ranges. Headers come from
+/// tool output, so a range whose end doesn't fit in a `u32` is refused.
+fn parse_hunk_header(line: &str) -> Option<(u32, u32, u32, u32)> {
+    let rest = line.strip_prefix("@@ -")?;
+    let (ranges, _) = rest.split_once(" @@")?;
+    let (old, new) = ranges.split_once(" +")?;
+    let range = |r: &str| -> Option<(u32, u32)> {
+        let (start, len): (u32, u32) = match r.split_once(',') {
+            Some((start, len)) => (start.parse().ok()?, len.parse().ok()?),
+            None => (r.parse().ok()?, 1),
+        };
+        start.max(1).checked_add(len)?;
+        Some((start, len))
+    };
+    let (old_start, old_len) = range(old)?;
+    let (new_start, new_len) = range(new)?;
+    Some((old_start, old_len, new_start, new_len))
+}
+
+/// Extracts every file diff from unified-diff text. Anything that is not
+/// part of a file header or hunk (commit messages, tool chatter) is
+/// skipped, and text without hunks yields an empty list.
+pub fn parse(text: &str) -> Vec<FileDiff> {
+    let text = sanitize::sanitize(text);
+    let mut files: Vec<FileDiff> = Vec::new();
+    let mut current: Option<FileDiff> = None;
+    let mut old_no: u32 = 0;
+    let mut new_no: u32 = 0;
+    let mut lines = text.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        let in_hunk = current
+            .as_ref()
+            .and_then(|f| f.hunks.last())
+            .is_some_and(|h| {
+                let seen_old = old_no.saturating_sub(h.old_start);
+                let seen_new = new_no.saturating_sub(h.new_start);
+                seen_old < h.old_len || seen_new < h.new_len
+            });
+
+        let body_kind = match line.chars().next() {
+            Some('+') => Some(LineKind::Added),
+            Some('-') => Some(LineKind::Removed),
+            Some(' ') | None => Some(LineKind::Context),
+            _ => None,
+        };
+
+        if let (true, Some(kind)) = (in_hunk, body_kind) {
+            let hunk = current
+                .as_mut()
+                .and_then(|f| f.hunks.last_mut())
+                .expect("in_hunk implies a hunk");
+            let body = line.get(1..).unwrap_or("");
+            let (old, new) = match kind {
+                LineKind::Added => (None, Some(new_no)),
+                LineKind::Removed => (Some(old_no), None),
+                _ => (Some(old_no), Some(new_no)),
+            };
+            if old.is_some() {
+                old_no = old_no.saturating_add(1);
+            }
+            if new.is_some() {
+                new_no = new_no.saturating_add(1);
+            }
+            hunk.lines.push(DiffLine {
+                kind,
+                text: body.to_string(),
+                old_no: old,
+                new_no: new,
+            });
+            continue;
+        }
+
+        if in_hunk && !line.starts_with('\\') {
+            // The hunk was cut short; close it at what we have seen. A
+            // no-newline marker can sit between the two sides of a change.
+            if let Some(hunk) = current.as_mut().and_then(|f| f.hunks.last_mut()) {
+                hunk.old_len = old_no.saturating_sub(hunk.old_start);
+                hunk.new_len = new_no.saturating_sub(hunk.new_start);
+            }
+        }
+
+        if line.starts_with('\\') {
+            if let Some(hunk) = current.as_mut().and_then(|f| f.hunks.last_mut()) {
+                hunk.lines.push(DiffLine {
+                    kind: LineKind::Note,
+                    text: line.trim_start_matches('\\').trim().to_string(),
+                    old_no: None,
+                    new_no: None,
+                });
+            }
+        } else if let Some(rest) = line.strip_prefix("diff --git ") {
+            files.extend(current.take().filter(|f| !f.hunks.is_empty()));
+            let (old, new) = rest.split_once(" b/").unwrap_or((rest, rest));
+            current = Some(FileDiff {
+                old_path: strip_prefix(old),
+                new_path: strip_prefix(new),
+                hunks: Vec::new(),
+            });
+        } else if let (Some(old), Some(next)) = (line.strip_prefix("--- "), lines.peek()) {
+            if let Some(new) = next.strip_prefix("+++ ") {
+                let new = strip_prefix(new);
+                lines.next();
+                files.extend(current.take().filter(|f| !f.hunks.is_empty()));
+                current = Some(FileDiff {
+                    old_path: strip_prefix(old),
+                    new_path: new,
+                    hunks: Vec::new(),
+                });
+            }
+        } else if let Some((old_start, old_len, new_start, new_len)) = parse_hunk_header(line) {
+            let Some(file) = current.as_mut() else {
+                continue;
+            };
+            old_no = old_start.max(1);
+            new_no = new_start.max(1);
+            file.hunks.push(Hunk {
+                header: line.to_string(),
+                old_start: old_no,
+                old_len,
+                new_start: new_no,
+                new_len,
+                lines: Vec::new(),
+            });
+        }
+    }
+    files.extend(current.filter(|f| !f.hunks.is_empty()));
+    files
+}
+
+fn fit(text: &str, width: usize) -> String {
+    let mut out = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w > width {
+            break;
+        }
+        used += w;
+        out.push(c);
+    }
+    out.push_str(&" ".repeat(width - used));
+    out
+}
+
+fn number(n: Option<u32>, width: usize) -> String {
+    match n {
+        Some(n) => format!("{:>width$}", n),
+        None => " ".repeat(width),
+    }
+}
+
+fn line_style(kind: LineKind, theme: &Theme) -> Style {
+    match kind {
+        LineKind::Added => Style::default().fg(theme.success),
+        LineKind::Removed => Style::default().fg(theme.error),
+        LineKind::Context => Style::default().fg(theme.text),
+        LineKind::Note => Style::default().fg(theme.dim),
+    }
+}
+
+fn sign(kind: LineKind) -> &'static str {
+    match kind {
+        LineKind::Added => "+",
+        LineKind::Removed => "-",
+        _ => " ",
+    }
+}
+
+/// Rendered lines for one file, plus the line offset each hunk starts at so
+/// callers can scroll to or highlight a hunk.
+pub struct FileView {
+    pub lines: Vec<Line<'static>>,
+    pub hunk_offsets: Vec<usize>,
+}
+
+pub fn file_view(file: &FileDiff, split: bool, width: u16, theme: &Theme) -> FileView {
+    let max_no = file
+        .hunks
+        .iter()
+        .map(|h| {
+            h.old_start
+                .saturating_add(h.old_len)
+                .max(h.new_start.saturating_add(h.new_len))
+        })
+        .max()
+        .unwrap_or(0);
+    let num_width = max_no.to_string().len();
+    let gutter = Style::default().fg(theme.dim);
+    let (added, removed) = file.stats();
+
+    let mut lines = vec![Line::from(vec![
+        Span::styled(
+            sanitize::inline(file.path()),
+            Style::default().fg(theme.accent).bold(),
+        ),
+        Span::styled(format!("  +{}", added), Style::default().fg(theme.success)),
+        Span::styled(format!(" -{}", removed), Style::default().fg(theme.error)),
+    ])];
+    if file.old_path != file.new_path
+        && file.old_path != "/dev/null"
+        && file.new_path != "/dev/null"
+    {
+        lines.push(Line::from(Span::styled(
+            format!("renamed from {}", sanitize::inline(&file.old_path)),
+            gutter,
+        )));
+    }
+
+    let mut hunk_offsets = Vec::new();
+    for hunk in &file.hunks {
+        lines.push(Line::from(""));
+        hunk_offsets.push(lines.len());
+        lines.push(Line::from(Span::styled(
+            hunk.header.clone(),
+            Style::default().fg(theme.accent),
+        )));
+
+        if !split {
+            for line in &hunk.lines {
+                let style = line_style(line.kind, theme);
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!(
+                            "{} {} │",
+                            number(line.old_no, num_width),
+                            number(line.new_no, num_width)
+                        ),
+                        gutter,
+                    ),
+                    Span::styled(format!("{}{}", sign(line.kind), line.text), style),
+                ]));
+            }
+            continue;
+        }
+
+        // Side by side: each run of removals is paired row by row with the
+        // additions that follow it.
+        let half = (width as usize).saturating_sub(1) / 2;
+        let text_width = half.saturating_sub(num_width + 3);
+        let mut i = 0;
+        while i < hunk.lines.len() {
+            let line = &hunk.lines[i];
+            if line.kind != LineKind::Removed && line.kind != LineKind::Added {
+                let style = line_style(line.kind, theme);
+                let text = fit(&line.text, text_width);
+                lines.push(Line::from(vec![
+                    Span::styled(format!("{} │", number(line.old_no, num_width)), gutter),
+                    Span::styled(format!(" {}", text), style),
+                    Span::styled(format!("{} │", number(line.new_no, num_width)), gutter),
+                    Span::styled(format!(" {}", text), style),
+                ]));
+                i += 1;
+                continue;
+            }
+            let removed: Vec<&DiffLine> = hunk.lines[i..]
+                .iter()
+                .take_while(|l| l.kind == LineKind::Removed)
+                .collect();
+            i += removed.len();
+            let added: Vec<&DiffLine> = hunk.lines[i..]
+                .iter()
+                .take_while(|l| l.kind == LineKind::Added)
+                .collect();
+            i += added.len();
+            for row in 0..removed.len().max(added.len()) {
+                let mut spans = Vec::new();
+                for (side, kind) in [
+                    (removed.get(row), LineKind::Removed),
+                    (added.get(row), LineKind::Added),
+                ] {
+                    let no = side.and_then(|l| l.old_no.or(l.new_no));
+                    let text = side.map(|l| l.text.as_str()).unwrap_or("");
+                    spans.push(Span::styled(format!("{} │", number(no, num_width)), gutter));
+                    let marker = if side.is_some() { sign(kind) } else { " " };
+                    spans.push(Span::styled(
+                        format!("{}{}", marker, fit(text, text_width)),
+                        line_style(kind, theme),
+                    ));
+                }
+                lines.push(Line::from(spans));
+            }
+        }
+    }
+
+    FileView {
+        lines,
+        hunk_offsets,
+    }
+}
+
+/// Full-screen viewer over one or more file diffs.
+pub struct DiffView {
+    files: Vec<FileDiff>,
+    selected: usize,
+    scroll: usize,
+    /// `None` follows the terminal width; `Some` is an explicit toggle.
+    split: Option<bool>,
+    last_width: u16,
+    last_height: u16,
+    hunk_offsets: Vec<usize>,
+}
+
+impl DiffView {
+    pub fn new(files: Vec<FileDiff>) -> Self {
+        Self {
+            files,
+            selected: 0,
+            scroll: 0,
+            split: None,
+            last_width: 0,
+            last_height: 0,
+            hunk_offsets: Vec::new(),
+        }
+    }
+
+    fn is_split(&self) -> bool {
+        self.split.unwrap_or(self.last_width >= SPLIT_MIN_WIDTH)
+    }
+
+    fn select_file(&mut self, index: usize) {
+        self.selected = index.min(self.files.len().saturating_sub(1));
+        self.scroll = 0;
+    }
+
+    fn jump_hunk(&mut self, forward: bool) {
+        let target = if forward {
+            self.hunk_offsets.iter().find(|&&o| o > self.scroll)
+        } else {
+            self.hunk_offsets.iter().rev().find(|&&o| o < self.scroll)
+        };
+        if let Some(&offset) = target {
+            self.scroll = offset;
+        }
+    }
+
+    /// Returns false when the view should be closed.
+    pub fn handle_action(&mut self, action: Action) -> bool {
+        let page = self.last_height.saturating_sub(3).max(1) as usize;
+        let count = self.files.len().max(1);
+        match action {
+            Action::DiffClose => return false,
+            Action::DiffDown => self.scroll += 1,
+            Action::DiffUp => self.scroll = self.scroll.saturating_sub(1),
+            Action::DiffPageDown => self.scroll += page,
+            Action::DiffPageUp => self.scroll = self.scroll.saturating_sub(page),
+            Action::DiffTop => self.scroll = 0,
+            Action::DiffBottom => self.scroll = usize::MAX,
+            Action::DiffNextFile => self.select_file((self.selected + 1) % count),
+            Action::DiffPrevFile => self.select_file((self.selected + count - 1) % count),
+            Action::DiffNextHunk => self.jump_hunk(true),
+            Action::DiffPrevHunk => self.jump_hunk(false),
+            Action::DiffToggleSplit => self.split = Some(!self.is_split()),
+            _ => {}
+        }
+        true
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme, keys: &Keymap) {
+        self.last_width = area.width;
+        self.last_height = area.height;
+        crate::clear_area(frame, area, theme);
+
+        let mode = if self.is_split() { "split" } else { "unified" };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(Span::styled(
+                format!(" Diff • {} files • {} ", self.files.len(), mode),
+                Style::default().fg(theme.accent).bold(),
+            ))
+            .title_bottom(Span::styled(
+                keys.hints(&[
+                    (Action::DiffNextFile, "file"),
+                    (Action::DiffNextHunk, "hunk"),
+                    (Action::DiffToggleSplit, "split"),
+                    (Action::DiffDown, "scroll"),
+                    (Action::DiffClose, "close"),
+                ]),
+                Style::default().fg(theme.dim),
+            ));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let content = if self.files.len() > 1 {
+            let panes = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Length(FILE_LIST_WIDTH), Constraint::Min(0)])
+                .split(inner);
+            let items: Vec<ListItem> = self
+                .files
+                .iter()
+                .map(|f| {
+                    let (added, removed) = f.stats();
+                    ListItem::new(Line::from(vec![
+                        Span::styled(format!("+{:<4}", added), Style::default().fg(theme.success)),
+                        Span::styled(format!("-{:<4}", removed), Style::default().fg(theme.error)),
+                        Span::styled(sanitize::inline(f.path()), Style::default().fg(theme.text)),
+                    ]))
+                })
+                .collect();
+            let list = List::new(items)
+                .block(
+                    Block::default()
+                        .borders(Borders::RIGHT)
+                        .border_style(Style::default().fg(theme.border)),
+                )
+                .highlight_style(Style::default().fg(theme.accent).reversed());
+            let mut list_state = ListState::default().with_selected(Some(self.selected));
+            frame.render_stateful_widget(list, panes[0], &mut list_state);
+            panes[1]
+        } else {
+            inner
+        };
+
+        let Some(file) = self.files.get(self.selected) else {
+            frame.render_widget(
+                Paragraph::new("No changes").style(Style::default().fg(theme.dim)),
+                content,
+            );
+            return;
+        };
+        let view = file_view(file, self.is_split(), content.width, theme);
+        self.hunk_offsets = view.hunk_offsets;
+        let max_scroll = view.lines.len().saturating_sub(content.height as usize);
+        self.scroll = self.scroll.min(max_scroll);
+        let visible: Vec<Line> = view
+            .lines
+            .into_iter()
+            .skip(self.scroll)
+            .take(content.height as usize)
+            .collect();
+        frame.render_widget(Paragraph::new(visible), content);
+    }
+}
+
+/// One-line summary shown under a tool call that produced a diff.
+pub fn summary(files: &[FileDiff]) -> String {
+    let (added, removed) = files.iter().fold((0, 0), |(a, r), f| {
+        let (fa, fr) = f.stats();
+        (a + fa, r + fr)
+    });
+    let noun = if files.len() == 1 { "file" } else { "files" };
+    format!("{} {} changed, +{} -{}", files.len(), noun, added, removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    const EDIT: &str = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -10,4 +10,5 @@ fn main() {
+ let a = 1;
+-let b = 2;
++let b = 3;
++let c = 4;
+ let d = 5;
+ let e = 6;
+";
+
+    #[test]
+    fn parses_header_ranges() {
+        assert_eq!(
+            parse_hunk_header("@@ -12,3 +12,4 @@ fn main()"),
+            Some((12, 3, 12, 4))
+        );
+        assert_eq!(parse_hunk_header("@@ -5 +7 @@"), Some((5, 1, 7, 1)));
+        assert_eq!(parse_hunk_header("@@ -0,0 +1,2 @@"), Some((0, 0, 1, 2)));
+        assert_eq!(parse_hunk_header("@@ -x,1 +1 @@"), None);
+        assert_eq!(parse_hunk_header("@@ garbage"), None);
+    }
+
+    #[test]
+    fn numbers_lines_on_both_sides() {
+        let files = parse(EDIT);
+        assert_eq!(files.len(), 1);
+        let file = &files[0];
+        assert_eq!(file.path(), "src/lib.rs");
+        assert_eq!(file.stats(), (2, 1));
+        let hunk = &file.hunks[0];
+        assert_eq!(hunk.header, "@@ -10,4 +10,5 @@ fn main() {");
+        let numbers: Vec<_> = hunk
+            .lines
+            .iter()
+            .map(|l| (l.kind, l.old_no, l.new_no))
+            .collect();
+        assert_eq!(
+            numbers,
+            [
+                (LineKind::Context, Some(10), Some(10)),
+                (LineKind::Removed, Some(11), None),
+                (LineKind::Added, None, Some(11)),
+                (LineKind::Added, None, Some(12)),
+                (LineKind::Context, Some(12), Some(13)),
+                (LineKind::Context, Some(13), Some(14)),
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_multi_file_patches_and_skips_chatter() {
+        let patch = "\
+commit abc
+Author: someone
+
+    message with --- dashes
+diff --git a/one.rs b/one.rs
+--- a/one.rs
++++ b/one.rs
+@@ -1 +1 @@
+-old
++new
+Applied patch.
+diff --git a/two.rs b/two.rs
+--- a/two.rs
++++ b/two.rs
+@@ -3,2 +3,1 @@
+ keep
+-gone
+";
+        let files = parse(patch);
+        let paths: Vec<_> = files.iter().map(|f| f.path()).collect();
+        assert_eq!(paths, ["one.rs", "two.rs"]);
+        assert_eq!(files[0].stats(), (1, 1));
+        assert_eq!(files[1].stats(), (0, 1));
+        assert_eq!(files[0].hunks[0].lines.len(), 2);
+    }
+
+    #[test]
+    fn handles_added_and_deleted_files() {
+        let patch = "\
+--- /dev/null
++++ b/new.txt
+@@ -0,0 +1,2 @@
++first
++second
+--- a/old.txt
++++ /dev/null
+@@ -1,1 +0,0 @@
+-only
+";
+        let files = parse(patch);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].old_path, "/dev/null");
+        assert_eq!(files[0].path(), "new.txt");
+        let new_numbers: Vec<_> = files[0].hunks[0].lines.iter().map(|l| l.new_no).collect();
+        assert_eq!(new_numbers, [Some(1), Some(2)]);
+        assert_eq!(files[1].new_path, "/dev/null");
+        assert_eq!(files[1].path(), "old.txt");
+        assert_eq!(files[1].stats(), (0, 1));
+    }
+
+    #[test]
+    fn keeps_no_newline_markers_as_notes() {
+        let patch = "\
+--- a/f
++++ b/f
+@@ -1 +1 @@
+-a
+\\ No newline at end of file
++a
+\\ No newline at end of file
+";
+        let lines = &parse(patch)[0].hunks[0].lines;
+        let kinds: Vec<_> = lines.iter().map(|l| l.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                LineKind::Removed,
+                LineKind::Note,
+                LineKind::Added,
+                LineKind::Note
+            ]
+        );
+        assert_eq!(lines[1].text, "No newline at end of file");
+        assert_eq!(lines[2].new_no, Some(1));
+    }
+
+    #[test]
+    fn closes_a_hunk_that_was_cut_short() {
+        let patch = "\
+--- a/f
++++ b/f
+@@ -1,5 +1,5 @@
+ one
+-two
+Error: tool output truncated
+";
+        let hunk = &parse(patch)[0].hunks[0];
+        assert_eq!(hunk.lines.len(), 2);
+        assert_eq!((hunk.old_len, hunk.new_len), (2, 1));
+    }
+
+    #[test]
+    fn refuses_ranges_that_overflow() {
+        assert_eq!(parse_hunk_header("@@ -4294967295,5 +1 @@"), None);
+        assert_eq!(parse_hunk_header("@@ -1 +0,4294967295 @@"), None);
+        assert_eq!(
+            parse_hunk_header("@@ -4294967290,5 +1 @@"),
+            Some((4294967290, 5, 1, 1))
+        );
+        let patch = "--- a/f\n+++ b/f\n@@ -4294967295,5 +1 @@\n-a\n+b\n";
+        assert!(parse(patch).is_empty());
+
+        let patch = "--- a/f\n+++ b/f\n@@ -4294967294,1 +4294967294,1 @@\n-a\n+b\n x\n";
+        let files = parse(patch);
+        let hunk = &files[0].hunks[0];
+        assert_eq!(hunk.lines[1].new_no, Some(4294967294));
+        let view = file_view(&files[0], false, 80, &Theme::default());
+        assert_eq!(view.hunk_offsets, [2]);
+    }
+
+    #[test]
+    fn text_without_hunks_is_not_a_diff() {
+        assert!(parse("hello\n--- not a header\nworld").is_empty());
+        assert!(parse("@@ -1 +1 @@\n-a\n+b").is_empty());
+    }
+
+    #[test]
+    fn split_pairs_removals_with_additions() {
+        let file = &parse(EDIT)[0];
+        let theme = Theme::default();
+        let unified = file_view(file, false, 100, &theme);
+        let split = file_view(file, true, SPLIT_MIN_WIDTH, &theme);
+        // Header, blank, hunk header, then one row per line or pair.
+        assert_eq!(unified.lines.len(), 3 + 6);
+        assert_eq!(split.lines.len(), 3 + 5);
+        assert_eq!(unified.hunk_offsets, [2]);
+        assert_eq!(split.hunk_offsets, [2]);
+
+        let paired = text(&split.lines[4]);
+        assert!(paired.contains("-let b = 2;"), "{}", paired);
+        assert!(paired.contains("+let b = 3;"), "{}", paired);
+        let lone = text(&split.lines[5]);
+        assert!(lone.contains("+let c = 4;"), "{}", lone);
+        assert!(!lone.contains('-'), "{}", lone);
+        for line in &split.lines[3..] {
+            let width: usize = text(line).chars().count();
+            assert!(width <= SPLIT_MIN_WIDTH as usize, "{}", width);
+        }
+    }
+
+    #[test]
+    fn split_follows_width_until_toggled() {
+        let mut view = DiffView::new(parse(EDIT));
+        view.last_width = SPLIT_MIN_WIDTH - 1;
+        assert!(!view.is_split());
+        view.last_width = SPLIT_MIN_WIDTH;
+        assert!(view.is_split());
+        view.handle_action(Action::DiffToggleSplit);
+        assert!(!view.is_split());
+        view.last_width = SPLIT_MIN_WIDTH + 40;
+        assert!(!view.is_split());
+    }
+}
@@ -0,0 +1,174 @@
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::keymap::{Action, Keymap};
+use crate::sanitize;
+use crate::Theme;
+
+/// Rows the banner takes at the bottom of the chat.
+pub const HEIGHT: u16 = 4;
+
+/// Error reported by the host, shown as a banner until acted on.
+pub struct ErrorBanner {
+    pub code: Option<String>,
+    pub message: String,
+    /// The turn can be retried or moved to another provider.
+    pub recoverable: bool,
+}
+
+/// What the user chose to do about an error, sent back to the host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorChoice {
+    Retry,
+    Fallback,
+    Dismiss,
+}
+
+impl ErrorChoice {
+    fn name(self) -> &'static str {
+        match self {
+            ErrorChoice::Retry => "retry",
+            ErrorChoice::Fallback => "fallback",
+            ErrorChoice::Dismiss => "dismiss",
+        }
+    }
+}
+
+impl ErrorBanner {
+    /// Maps a banner key to a choice; retry and fallback only apply to
+    /// recoverable errors.
+    pub fn handle_action(&self, action: Action) -> Option<ErrorChoice> {
+        match action {
+            Action::ErrorRetry if self.recoverable => Some(ErrorChoice::Retry),
+            Action::ErrorFallback if self.recoverable => Some(ErrorChoice::Fallback),
+            Action::ErrorDismiss => Some(ErrorChoice::Dismiss),
+            _ => None,
+        }
+    }
+
+    pub fn command(&self, choice: ErrorChoice) -> serde_json::Value {
+        serde_json::json!({
+            "type": "error_action",
+            "action": choice.name(),
+            "code": self.code,
+            "message": self.message,
+        })
+    }
+
+    /// Suggestion based on the same error classes the host's provider
+    /// fallback uses (src/cli/fallback.ts).
+    pub fn suggestion(&self) -> &'static str {
+        let text =
+            format!("{} {}", self.code.as_deref().unwrap_or(""), self.message).to_lowercase();
+        let has = |words: &[&str]| words.iter().any(|w| text.contains(w));
+        if has(&[
+            "quota",
+            "rate limit",
+            "rate_limit",
+            "429",
+            "exhausted",
+            "capacity",
+        ]) {
+            "Switch to a fallback provider or wait for the limit to reset"
+        } else if has(&["auth", "401", "403", "api key", "unauthorized", "forbidden"]) {
+            "Check the provider's credentials, or switch to a fallback provider"
+        } else if has(&["context", "too long", "token limit", "max_tokens"]) {
+            "Remove files from context or start a new session"
+        } else if has(&[
+            "timeout",
+            "timed out",
+            "network",
+            "connection",
+            "econn",
+            "503",
+            "502",
+        ]) {
+            "Retry the turn; switch provider if it keeps failing"
+        } else if self.recoverable {
+            "Retry the turn"
+        } else {
+            "Check the host output for details"
+        }
+    }
+
+    /// One line for the transcript, so the error stays visible after the
+    /// banner is dismissed.
+    pub fn summary(&self) -> String {
+        match &self.code {
+            Some(code) => format!("Error [{}]: {}", code, self.message),
+            None => format!("Error: {}", self.message),
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme, keys: &Keymap) {
+        crate::clear_area(frame, area, theme);
+        let hints = if self.recoverable {
+            keys.hints(&[
+                (Action::ErrorRetry, "retry"),
+                (Action::ErrorFallback, "fallback provider"),
+                (Action::ErrorDismiss, "dismiss"),
+            ])
+        } else {
+            keys.hints(&[(Action::ErrorDismiss, "dismiss")])
+        };
+        let title = match &self.code {
+            Some(code) => format!(" Error • {} ", sanitize::inline(code)),
+            None => " Error ".to_string(),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.error))
+            .title(Span::styled(title, Style::default().fg(theme.error).bold()))
+            .title_bottom(Span::styled(hints, Style::default().fg(theme.dim)));
+        let lines = vec![
+            Line::from(Span::styled(
+                sanitize::inline(&self.message),
+                Style::default().fg(theme.text),
+            )),
+            Line::from(vec![
+                Span::styled("→ ", Style::default().fg(theme.warning)),
+                Span::styled(self.suggestion(), Style::default().fg(theme.dim)),
+            ]),
+        ];
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn banner(recoverable: bool) -> ErrorBanner {
+        ErrorBanner {
+            code: Some("stream_error".to_string()),
+            message: "429 quota exceeded".to_string(),
+            recoverable,
+        }
+    }
+
+    #[test]
+    fn offers_retry_only_when_recoverable() {
+        assert_eq!(
+            banner(true).handle_action(Action::ErrorFallback),
+            Some(ErrorChoice::Fallback)
+        );
+        assert_eq!(banner(false).handle_action(Action::ErrorRetry), None);
+        assert_eq!(
+            banner(false).handle_action(Action::ErrorDismiss),
+            Some(ErrorChoice::Dismiss)
+        );
+    }
+
+    #[test]
+    fn sends_the_message_for_fallback_selection() {
+        let command = banner(true).command(ErrorChoice::Fallback);
+        assert_eq!(command["type"], "error_action");
+        assert_eq!(command["action"], "fallback");
+        assert_eq!(command["message"], "429 quota exceeded");
+    }
+}
@@ -0,0 +1,809 @@
+use std::fmt::Write as _;
+use std::io::{ErrorKind, Write as _};
+use std::path::{Path, PathBuf};
+
+use ratatui::style::Color;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::ExportConfig;
+use crate::text::duration_label;
+use crate::{sanitize, theme, AppState, Message, Role, Theme};
+
+const MASK: &str = "[masked]";
+
+/// Secrets masked by default. A `secret` group, when present, is the only
+/// part replaced so the key name stays readable.
+const SECRET_PATTERNS: &[&str] = &[
+    r"sk-[A-Za-z0-9_-]{16,}",
+    r"AKIA[0-9A-Z]{16}",
+    r"gh[pousr]_[A-Za-z0-9]{20,}",
+    r"xox[abprs]-[A-Za-z0-9-]{10,}",
+    r"(?i)bearer\s+(?P<secret>[A-Za-z0-9._~+/=-]{16,})",
+    r#"(?i)(?:password|passwd|secret|api[_-]?key|access[_-]?token|auth[_-]?token)["']?\s*[:=]\s*["']?(?P<secret>[^\s"',;]+)"#,
+    r"-----BEGIN [A-Z ]*PRIVATE KEY-----[\s\S]*?-----END [A-Z ]*PRIVATE KEY-----",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Markdown,
+    Html,
+    Json,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "md" | "markdown" => Some(Self::Markdown),
+            "html" | "htm" => Some(Self::Html),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// A gate prompt and how it was answered, for exports and the session
+/// store.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GateDecision {
+    pub id: String,
+    /// `approve_once`, `always_allow_tool`, `always_allow_path`, `reject`,
+    /// or whatever the host reported.
+    pub action: String,
+    pub blocked: bool,
+    pub warnings: Vec<String>,
+    pub timestamp: u64,
+}
+
+/// What `/export` writes, from the config and the command's arguments.
+pub struct Options {
+    pub format: Format,
+    pub redact_outputs: bool,
+    pub mask_secrets: bool,
+    pub path: Option<PathBuf>,
+}
+
+impl Options {
+    /// `[markdown|html|json] [--redact-outputs] [--mask|--no-mask] [file]`,
+    /// over the `[export]` defaults. A file name's extension picks the
+    /// format when none is given.
+    pub fn parse(args: &str, config: &ExportConfig) -> Result<Self, String> {
+        let mut options = Self {
+            format: config.format,
+            redact_outputs: config.redact_outputs,
+            mask_secrets: config.mask_secrets,
+            path: None,
+        };
+        let mut format = None;
+        for arg in args.split_whitespace() {
+            match arg {
+                "--redact-outputs" | "--redact" => options.redact_outputs = true,
+                "--mask" => options.mask_secrets = true,
+                "--no-mask" => options.mask_secrets = false,
+                flag if flag.starts_with("--") => return Err(format!("unknown option {}", flag)),
+                name if format.is_none()
+                    && options.path.is_none()
+                    && Format::parse(name).is_some() =>
+                {
+                    format = Format::parse(name);
+                }
+                path if options.path.is_none() => options.path = Some(PathBuf::from(path)),
+                extra => return Err(format!("unexpected argument {}", extra)),
+            }
+        }
+        let from_path = options
+            .path
+            .as_ref()
+            .and_then(|p| p.extension())
+            .and_then(|e| Format::parse(&e.to_string_lossy()));
+        if let Some(format) = format.or(from_path) {
+            options.format = format;
+        }
+        Ok(options)
+    }
+}
+
+/// Checks `[export] mask_patterns` entries as they are loaded.
+pub fn compile_pattern(pattern: &str) -> Result<Regex, String> {
+    Regex::new(pattern).map_err(|e| e.to_string())
+}
+
+/// Replaces secrets in text written to disk, by `/export` and the
+/// session store.
+pub struct Masker {
+    patterns: Vec<Regex>,
+}
+
+impl Masker {
+    pub fn new(enabled: bool, extra: &[Regex]) -> Self {
+        let patterns = if enabled {
+            SECRET_PATTERNS
+                .iter()
+                .filter_map(|p| Regex::new(p).ok())
+                .chain(extra.iter().cloned())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Self { patterns }
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for re in &self.patterns {
+            text = re
+                .replace_all(&text, |caps: &Captures| {
+                    let whole = &caps[0];
+                    match caps.name("secret") {
+                        Some(secret) => {
+                            let start = secret.start() - caps.get(0).map_or(0, |m| m.start());
+                            let end = start + secret.len();
+                            format!("{}{}{}", &whole[..start], MASK, &whole[end..])
+                        }
+                        None => MASK.to_string(),
+                    }
+                })
+                .into_owned();
+        }
+        text
+    }
+}
+
+#[derive(Serialize)]
+struct Tool {
+    id: String,
+    name: String,
+    status: String,
+    /// The call's arguments, as JSON when they parse.
+    arguments: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    output_redacted: bool,
+    elapsed_ms: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct Entry {
+    id: String,
+    role: Role,
+    content: String,
+    timestamp: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    elapsed_ms: Option<u64>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    thinking: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<Tool>,
+    /// The turn was still streaming when the export was taken.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    in_progress: bool,
+}
+
+#[derive(Serialize)]
+struct Document {
+    exported_at: String,
+    session: Option<String>,
+    model: Option<String>,
+    redacted_outputs: bool,
+    masked_secrets: bool,
+    messages: Vec<Entry>,
+    gates: Vec<GateDecision>,
+}
+
+/// Text as written to a file: escape sequences stripped, other controls
+/// made visible the way the transcript shows them, and secrets masked.
+fn clean(text: &str, masker: &Masker) -> String {
+    masker.apply(&sanitize::sanitize(text))
+}
+
+fn entry(message: &Message, options: &Options, masker: &Masker) -> Entry {
+    let tools = message
+        .tools
+        .iter()
+        .map(|tool| {
+            let arguments = tool.arguments.as_deref().map(|a| clean(a, masker));
+            Tool {
+                id: sanitize::inline(&tool.id),
+                name: sanitize::inline(&tool.name),
+                status: sanitize::inline(&tool.status),
+                arguments: match arguments {
+                    Some(text) => serde_json::from_str(&text).unwrap_or(Value::String(text)),
+                    None => Value::Null,
+                },
+                output: tool
+                    .output
+                    .as_deref()
+                    .filter(|_| !options.redact_outputs)
+                    .map(|o| clean(o, masker)),
+                output_redacted: options.redact_outputs && tool.output.is_some(),
+                elapsed_ms: tool.elapsed_ms,
+            }
+        })
+        .collect();
+    Entry {
+        id: message.id.clone(),
+        role: message.role,
+        content: clean(&message.content, masker),
+        timestamp: message.timestamp,
+        elapsed_ms: message.elapsed_ms,
+        thinking: clean(&message.thinking, masker),
+        tools,
+        in_progress: false,
+    }
+}
+
+fn document(
+    state: &AppState,
+    session: Option<String>,
+    options: &Options,
+    config: &ExportConfig,
+) -> Document {
+    let masker = Masker::new(options.mask_secrets, &config.mask_patterns);
+    let mut messages: Vec<Entry> = state
+        .messages
+        .iter()
+        .map(|m| entry(m, options, &masker))
+        .collect();
+    if !state.current_stream.is_empty() || !state.tools.is_empty() {
+        let partial = Message {
+            id: "in-progress".to_string(),
+            role: Role::Assistant,
+            content: state.current_stream.clone(),
+            timestamp: crate::waterfall::now_ms(),
+            tools: state.tools.clone(),
+            thinking: state.current_thinking.clone(),
+            ..Default::default()
+        };
+        messages.push(Entry {
+            in_progress: true,
+            ..entry(&partial, options, &masker)
+        });
+    }
+    let gates = state
+        .gate_log
+        .iter()
+        .map(|gate| GateDecision {
+            warnings: gate.warnings.iter().map(|w| clean(w, &masker)).collect(),
+            ..gate.clone()
+        })
+        .collect();
+    Document {
+        exported_at: chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+        session: session.map(|s| sanitize::inline(&s)),
+        model: crate::model_label(&state.provider, &state.model).map(|m| sanitize::inline(&m)),
+        redacted_outputs: options.redact_outputs,
+        masked_secrets: options.mask_secrets,
+        messages,
+        gates,
+    }
+}
+
+/// Writes the session to `options.path`, or to
+/// `dax-session-YYYYmmdd-HHMMSS.<ext>` in the working directory. An
+/// existing file is never replaced: a named one is an error, and the
+/// default name gets a `-2`, `-3`… suffix. Returns the path and the number
+/// of messages written.
+pub fn export(
+    state: &AppState,
+    session: Option<String>,
+    options: &Options,
+    config: &ExportConfig,
+    theme: &Theme,
+) -> std::io::Result<(PathBuf, usize)> {
+    let doc = document(state, session, options, config);
+    let body = match options.format {
+        Format::Json => serde_json::to_string_pretty(&doc)?,
+        Format::Markdown => markdown(&doc),
+        Format::Html => html(&doc, theme),
+    };
+    let path = match &options.path {
+        Some(path) => {
+            write_new(path, &body).map_err(|e| match e.kind() {
+                ErrorKind::AlreadyExists => {
+                    std::io::Error::new(e.kind(), format!("{} already exists", path.display()))
+                }
+                _ => e,
+            })?;
+            path.clone()
+        }
+        None => write_unique(
+            &format!(
+                "dax-session-{}",
+                chrono::Local::now().format("%Y%m%d-%H%M%S")
+            ),
+            options.format.extension(),
+            &body,
+        )?,
+    };
+    Ok((path, doc.messages.len()))
+}
+
+/// Writes `contents` to `<stem>.<extension>` in the working directory, or
+/// to `<stem>-2.<extension>`, `-3`… when that name is taken.
+pub fn write_unique(stem: &str, extension: &str, contents: &str) -> std::io::Result<PathBuf> {
+    let mut attempt = 1;
+    loop {
+        let name = match attempt {
+            1 => format!("{}.{}", stem, extension),
+            n => format!("{}-{}.{}", stem, n, extension),
+        };
+        let path = PathBuf::from(name);
+        match write_new(&path, contents) {
+            Err(e) if e.kind() == ErrorKind::AlreadyExists && attempt < 100 => attempt += 1,
+            result => return result.map(|()| path),
+        }
+    }
+}
+
+/// Creates `path` with `contents`, failing if it already exists.
+fn write_new(path: &Path, contents: &str) -> std::io::Result<()> {
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)?;
+    file.write_all(contents.as_bytes())
+}
+
+fn time(ms: u64) -> String {
+    chrono::DateTime::from_timestamp_millis(ms as i64)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+fn status_glyph(status: &str) -> &'static str {
+    match status {
+        "success" => "✓",
+        "error" => "✕",
+        _ => "…",
+    }
+}
+
+fn summary(doc: &Document) -> String {
+    let mut parts = vec![format!("Exported {}", doc.exported_at)];
+    if let Some(model) = &doc.model {
+        parts.push(model.clone());
+    }
+    parts.push(format!("{} messages", doc.messages.len()));
+    if doc.redacted_outputs {
+        parts.push("tool outputs redacted".to_string());
+    }
+    if doc.masked_secrets {
+        parts.push("secrets masked".to_string());
+    }
+    parts.join(" • ")
+}
+
+fn heading(doc: &Document) -> String {
+    match &doc.session {
+        Some(id) => format!("DAX session {}", id),
+        None => "DAX session".to_string(),
+    }
+}
+
+/// Time, turn duration and whether the turn was still running.
+fn entry_meta(entry: &Entry) -> String {
+    let mut meta = time(entry.timestamp);
+    if let Some(ms) = entry.elapsed_ms {
+        let _ = write!(meta, " • {}", duration_label(ms));
+    }
+    if entry.in_progress {
+        meta.push_str(" • in progress");
+    }
+    meta
+}
+
+fn arguments_text(arguments: &Value) -> Option<String> {
+    match arguments {
+        Value::Null => None,
+        Value::String(text) => Some(text.clone()),
+        other => serde_json::to_string_pretty(other).ok(),
+    }
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    let mut longest = 0;
+    let mut run = 0;
+    for c in text.chars() {
+        run = if c == '`' { run + 1 } else { 0 };
+        longest = longest.max(run);
+    }
+    longest
+}
+
+/// A code fence longer than any backtick run in `text`.
+fn fence(text: &str) -> String {
+    "`".repeat((longest_backtick_run(text) + 1).max(3))
+}
+
+/// Untrusted text as a one-line markdown code span whose delimiters are
+/// longer than any backtick run inside it.
+pub fn code_span(text: &str) -> String {
+    let text = sanitize::inline(text);
+    let ticks = "`".repeat(longest_backtick_run(&text) + 1);
+    let pad = if text.starts_with('`') || text.ends_with('`') {
+        " "
+    } else {
+        ""
+    };
+    format!("{}{}{}{}{}", ticks, pad, text, pad, ticks)
+}
+
+fn code_block(out: &mut String, lang: &str, text: &str) {
+    let fence = fence(text);
+    let _ = writeln!(out, "{}{}\n{}\n{}\n", fence, lang, text.trim_end(), fence);
+}
+
+fn markdown(doc: &Document) -> String {
+    let mut out = format!("# {}\n\n{}\n\n", heading(doc), summary(doc));
+    for entry in &doc.messages {
+        if entry.role.is_note() {
+            let text = format!("{} {}", entry.role.label(), entry.content.trim_end());
+            for line in text.lines() {
+                let _ = writeln!(out, ">{}{}", if line.is_empty() { "" } else { " " }, line);
+            }
+            out.push('\n');
+            continue;
+        }
+        let _ = writeln!(out, "## {} • {}\n", entry.role.label(), entry_meta(entry));
+        if !entry.thinking.is_empty() {
+            out.push_str("<details><summary>Reasoning</summary>\n\n");
+            code_block(&mut out, "", &entry.thinking);
+            out.push_str("</details>\n\n");
+        }
+        if !entry.content.trim().is_empty() {
+            let _ = writeln!(out, "{}\n", entry.content.trim_end());
+        }
+        for tool in &entry.tools {
+            let _ = write!(
+                out,
+                "### {} {} {}",
+                status_glyph(&tool.status),
+                code_span(&tool.name),
+                code_span(&tool.id)
+            );
+            if let Some(ms) = tool.elapsed_ms {
+                let _ = write!(out, " • {}", duration_label(ms));
+            }
+            out.push_str("\n\n");
+            if let Some(args) = arguments_text(&tool.arguments) {
+                code_block(&mut out, "json", &args);
+            }
+            if tool.output_redacted {
+                out.push_str("_Output redacted._\n\n");
+            } else if let Some(output) = &tool.output {
+                code_block(&mut out, "", output);
+            }
+        }
+    }
+    if !doc.gates.is_empty() {
+        out.push_str(
+            "## Gate decisions\n\n| Time | Gate | Decision | Warnings |\n|---|---|---|---|\n",
+        );
+        for gate in &doc.gates {
+            let cell = |text: &str| text.replace('|', "\\|").replace('\n', " ");
+            let _ = writeln!(
+                out,
+                "| {} | {} | {}{} | {} |",
+                time(gate.timestamp),
+                cell(&code_span(&gate.id)),
+                cell(&gate.action),
+                if gate.blocked { " (blocked)" } else { "" },
+                cell(&gate.warnings.join("; "))
+            );
+        }
+    }
+    out
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// CSS for a theme colour, with `fallback` for the terminal default.
+fn css(color: Color, fallback: &str) -> String {
+    theme::rgb(color)
+        .map(|(r, g, b)| format!("#{:02x}{:02x}{:02x}", r, g, b))
+        .unwrap_or_else(|| fallback.to_string())
+}
+
+/// One file with the theme's colours inlined, so it can be attached or
+/// opened anywhere.
+fn html(doc: &Document, theme: &Theme) -> String {
+    // A theme on the terminal background still needs one on the page;
+    // pick it from how light the text is.
+    let light_text =
+        theme::rgb(theme.text).is_none_or(|(r, g, b)| r as u32 + g as u32 + b as u32 > 384);
+    let (bg_fallback, text_fallback) = if light_text {
+        ("#1e1e1e", "#e6e6e6")
+    } else {
+        ("#ffffff", "#1e1e1e")
+    };
+    let c = |color: Color| css(color, text_fallback);
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ background: {bg}; color: {text}; font-family: ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; font-size: 14px; line-height: 1.5; max-width: 960px; margin: 2em auto; padding: 0 1em; }}
+h1 {{ color: {accent}; font-size: 1.4em; }}
+.summary, .time, .meta {{ color: {dim}; }}
+.message {{ border-left: 3px solid {border}; margin: 1.2em 0; padding: 0.2em 0 0.2em 1em; }}
+.role {{ font-weight: bold; }}
+.user .role {{ color: {user}; }} .user {{ border-color: {user}; }}
+.assistant .role {{ color: {assistant}; }} .assistant {{ border-color: {assistant}; }}
+.note {{ color: {dim}; margin: 0.4em 0; }}
+.note.warning {{ color: {warning}; }} .note.error {{ color: {error}; }} .note.notice {{ color: {accent}; }}
+pre {{ white-space: pre-wrap; word-break: break-word; margin: 0.4em 0; }}
+.tool {{ border: 1px solid {border}; border-radius: 4px; margin: 0.6em 0; padding: 0.4em 0.8em; }}
+.tool .name {{ color: {accent}; font-weight: bold; }}
+.success {{ color: {success}; }} .failed {{ color: {error}; }}
+details summary {{ color: {dim}; cursor: pointer; }}
+table {{ border-collapse: collapse; width: 100%; }}
+th, td {{ border: 1px solid {border}; padding: 0.3em 0.6em; text-align: left; vertical-align: top; }}
+.reject {{ color: {warning}; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p class="summary">{summary}</p>
+"#,
+        title = escape(&heading(doc)),
+        summary = escape(&summary(doc)),
+        bg = css(theme.bg, bg_fallback),
+        text = c(theme.text),
+        dim = c(theme.dim),
+        border = c(theme.border),
+        accent = c(theme.accent),
+        success = c(theme.success),
+        warning = c(theme.warning),
+        error = c(theme.error),
+        user = c(theme.user),
+        assistant = c(theme.assistant),
+    );
+    for entry in &doc.messages {
+        if entry.role.is_note() {
+            let _ = writeln!(
+                out,
+                r#"<div class="note {}">{} {}</div>"#,
+                role_class(entry.role),
+                escape(entry.role.label()),
+                escape(&entry.content)
+            );
+            continue;
+        }
+        let _ = writeln!(
+            out,
+            r#"<div class="message {}"><div><span class="role">{}</span> <span class="time">{}</span></div>"#,
+            role_class(entry.role),
+            escape(entry.role.label()),
+            escape(&entry_meta(entry))
+        );
+        if !entry.thinking.is_empty() {
+            let _ = writeln!(
+                out,
+                "<details><summary>Reasoning</summary><pre>{}</pre></details>",
+                escape(&entry.thinking)
+            );
+        }
+        if !entry.content.trim().is_empty() {
+            let _ = writeln!(out, "<pre>{}</pre>", escape(entry.content.trim_end()));
+        }
+        for tool in &entry.tools {
+            let class = match tool.status.as_str() {
+                "success" => "success",
+                "error" => "failed",
+                _ => "",
+            };
+            let _ = write!(
+                out,
+                r#"<div class="tool"><span class="{}">{}</span> <span class="name">{}</span> <span class="meta">{}"#,
+                class,
+                status_glyph(&tool.status),
+                escape(&tool.name),
+                escape(&tool.id)
+            );
+            if let Some(ms) = tool.elapsed_ms {
+                let _ = write!(out, " • {}", duration_label(ms));
+            }
+            out.push_str("</span>\n");
+            if let Some(args) = arguments_text(&tool.arguments) {
+                let _ = writeln!(
+                    out,
+                    "<details><summary>Arguments</summary><pre>{}</pre></details>",
+                    escape(&args)
+                );
+            }
+            if tool.output_redacted {
+                out.push_str("<p class=\"meta\">Output redacted.</p>\n");
+            } else if let Some(output) = &tool.output {
+                let _ = writeln!(
+                    out,
+                    "<details open><summary>Output</summary><pre>{}</pre></details>",
+                    escape(output.trim_end())
+                );
+            }
+            out.push_str("</div>\n");
+        }
+        out.push_str("</div>\n");
+    }
+    if !doc.gates.is_empty() {
+        out.push_str("<h2>Gate decisions</h2>\n<table>\n<tr><th>Time</th><th>Gate</th><th>Decision</th><th>Warnings</th></tr>\n");
+        for gate in &doc.gates {
+            let _ = writeln!(
+                out,
+                r#"<tr><td class="meta">{}</td><td>{}</td><td class="{}">{}{}</td><td>{}</td></tr>"#,
+                escape(&time(gate.timestamp)),
+                escape(&gate.id),
+                if gate.action == "reject" {
+                    "reject"
+                } else {
+                    ""
+                },
+                escape(&gate.action),
+                if gate.blocked { " (blocked)" } else { "" },
+                escape(&gate.warnings.join("; "))
+            );
+        }
+        out.push_str("</table>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn role_class(role: Role) -> &'static str {
+    match role {
+        Role::User => "user",
+        Role::Assistant => "assistant",
+        Role::System => "system",
+        Role::Notice => "notice",
+        Role::Warning => "warning",
+        Role::Error => "error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ToolState;
+
+    fn state() -> AppState {
+        let mut reply = Message {
+            id: "a1".to_string(),
+            role: Role::Assistant,
+            content: "\x1b]0;pwned\x07done\x1b[2J".to_string(),
+            ..Default::default()
+        };
+        reply.tools.push(ToolState {
+            name: "bash".to_string(),
+            status: "success".to_string(),
+            output: Some("\x1b[31mred\x1b[0m api_key=abc123".to_string()),
+            ..Default::default()
+        });
+        AppState {
+            messages: vec![reply],
+            ..Default::default()
+        }
+    }
+
+    fn options(format: Format, path: Option<PathBuf>) -> Options {
+        Options {
+            format,
+            redact_outputs: false,
+            mask_secrets: true,
+            path,
+        }
+    }
+
+    #[test]
+    fn parses_options() {
+        let config = ExportConfig::default();
+        let options = Options::parse("--no-mask out.html", &config).unwrap();
+        assert_eq!(options.format, Format::Html);
+        assert!(!options.mask_secrets);
+        assert_eq!(options.path, Some(PathBuf::from("out.html")));
+        let options = Options::parse("json --redact notes.md", &config).unwrap();
+        assert_eq!(options.format, Format::Json);
+        assert!(options.redact_outputs);
+        assert!(Options::parse("--bogus", &config).is_err());
+    }
+
+    #[test]
+    fn strips_escapes_and_masks_secrets() {
+        let config = ExportConfig::default();
+        for format in [Format::Markdown, Format::Html, Format::Json] {
+            let doc = document(&state(), None, &options(format, None), &config);
+            let body = match format {
+                Format::Json => serde_json::to_string(&doc).unwrap(),
+                Format::Markdown => markdown(&doc),
+                Format::Html => html(&doc, &Theme::default()),
+            };
+            assert!(!body.contains('\x1b'), "{:?} kept an escape", format);
+            assert!(!body.contains("\\u001b"), "{:?} kept an escape", format);
+            assert!(!body.contains("abc123"), "{:?} leaked a secret", format);
+            assert!(body.contains("done") && body.contains("red"));
+        }
+    }
+
+    #[test]
+    fn quotes_every_line_of_a_note() {
+        let state = AppState {
+            messages: vec![Message {
+                role: Role::Notice,
+                content: "first\n\nthird".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let config = ExportConfig::default();
+        let doc = document(&state, None, &options(Format::Markdown, None), &config);
+        let body = markdown(&doc);
+        assert!(body.contains("> ℹ first\n>\n> third\n"), "{}", body);
+    }
+
+    #[test]
+    fn backticks_in_ids_stay_inside_code_spans() {
+        let mut state = state();
+        state.messages[0].tools[0].name = "a`b".to_string();
+        state.messages[0].tools[0].id = "`id`".to_string();
+        state.gate_log.push(GateDecision {
+            id: "g`1|x".to_string(),
+            action: "reject".to_string(),
+            blocked: false,
+            warnings: Vec::new(),
+            timestamp: 0,
+        });
+        let config = ExportConfig::default();
+        let doc = document(&state, None, &options(Format::Markdown, None), &config);
+        let body = markdown(&doc);
+        assert!(body.contains("### ✓ ``a`b`` `` `id` ``"), "{}", body);
+        assert!(body.contains("| ``g`1\\|x`` | reject |"), "{}", body);
+    }
+
+    #[test]
+    fn never_overwrites_a_file() {
+        let path = std::env::temp_dir().join(format!("dax-tui-export-{}.md", std::process::id()));
+        std::fs::write(&path, "keep").unwrap();
+        let options = options(Format::Markdown, Some(path.clone()));
+        let result = export(
+            &state(),
+            None,
+            &options,
+            &ExportConfig::default(),
+            &Theme::default(),
+        );
+        let kept = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::AlreadyExists);
+        assert_eq!(kept, "keep");
+    }
+}
@@ -0,0 +1,138 @@
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::keymap::{Action, Keymap};
+use crate::sanitize;
+use crate::{Theme, Warning};
+
+/// A pending approval gate raised by the host.
+#[derive(Debug, Clone, Default)]
+pub struct GatePrompt {
+    pub id: String,
+    pub blocked: bool,
+    pub warnings: Vec<Warning>,
+    /// Whether the pending tool calls carry a diff that can be reviewed hunk by hunk.
+    pub reviewable: bool,
+    /// What a submitted review said; the gate stays open until the host answers it.
+    pub submitted: Option<String>,
+}
+
+pub enum GateChoice {
+    Resolve(&'static str),
+    Review,
+    Dismiss,
+}
+
+impl GatePrompt {
+    pub fn handle_action(&self, action: Action) -> Option<GateChoice> {
+        if self.submitted.is_some() {
+            return (action == Action::GateDismiss).then_some(GateChoice::Dismiss);
+        }
+        match action {
+            Action::GateApprove if !self.blocked => Some(GateChoice::Resolve("approve_once")),
+            Action::GateAllowTool if !self.blocked => {
+                Some(GateChoice::Resolve("always_allow_tool"))
+            }
+            Action::GateAllowPath if !self.blocked => {
+                Some(GateChoice::Resolve("always_allow_path"))
+            }
+            Action::GateReview if !self.blocked && self.reviewable => Some(GateChoice::Review),
+            Action::GateReject => Some(GateChoice::Resolve("reject")),
+            Action::GateDismiss => Some(GateChoice::Dismiss),
+            _ => None,
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme, keys: &Keymap) {
+        let color = if self.blocked {
+            theme.error
+        } else {
+            theme.warning
+        };
+        let title = if self.blocked {
+            " Gate: blocked by policy "
+        } else {
+            " Gate: approval required "
+        };
+
+        let mut lines: Vec<Line> = Vec::new();
+        for warning in &self.warnings {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("[{}] ", sanitize::inline(&warning.code)),
+                    Style::default().fg(color).bold(),
+                ),
+                Span::styled(
+                    sanitize::inline(&warning.subject),
+                    Style::default().fg(theme.text),
+                ),
+            ]));
+            if let Some(message) = &warning.message {
+                lines.push(Line::from(Span::styled(
+                    format!("  {}", sanitize::inline(message)),
+                    Style::default().fg(theme.dim),
+                )));
+            }
+        }
+        if let Some(summary) = &self.submitted {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                format!("Review submitted ({}); waiting for the host.", summary),
+                Style::default().fg(theme.accent).bold(),
+            )));
+        } else if self.warnings.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "The host is waiting for a decision.",
+                Style::default().fg(theme.text),
+            )));
+        }
+        lines.push(Line::from(""));
+
+        let mut options = Vec::new();
+        if self.submitted.is_some() {
+            options.push((Action::GateDismiss, "hide"));
+        } else {
+            if !self.blocked {
+                options.push((Action::GateApprove, "approve once"));
+                options.push((Action::GateAllowTool, "always allow tool"));
+                options.push((Action::GateAllowPath, "always allow path"));
+                if self.reviewable {
+                    options.push((Action::GateReview, "review hunks"));
+                }
+            }
+            options.push((Action::GateReject, "reject"));
+            options.push((Action::GateDismiss, "hide"));
+        }
+        let mut spans = Vec::new();
+        for (action, label) in options {
+            let key = keys.hint(action);
+            spans.push(Span::styled(
+                format!(" {} ", key),
+                Style::default().fg(theme.accent).bold(),
+            ));
+            spans.push(Span::styled(
+                format!("{}  ", label),
+                Style::default().fg(theme.dim),
+            ));
+        }
+        lines.push(Line::from(spans));
+
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let popup = crate::centered_rect(area, 72, height);
+        crate::clear_area(frame, popup, theme);
+        frame.render_widget(
+            Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(color))
+                    .title(Span::styled(title, Style::default().fg(color).bold())),
+            ),
+            popup,
+        );
+    }
+}
@@ -0,0 +1,260 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::keymap::{Action, Context, Keymap};
+use crate::sanitize;
+use crate::vim;
+use crate::Theme;
+
+const KEY_COLUMN: usize = 18;
+
+/// A slash command registered by the host.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlashCommand {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// Commands handled by the TUI itself rather than the host.
+const LOCAL_COMMANDS: &[(&str, &str)] = &[
+    ("/theme [name]", "List or switch colour themes"),
+    (
+        "/thinking <mode>",
+        "Set reasoning display: off, minimal, verbose",
+    ),
+    (
+        "/export [md|html|json] [file]",
+        "Save the session; --redact-outputs, --mask, --no-mask",
+    ),
+];
+
+struct Section {
+    title: String,
+    rows: Vec<(String, String)>,
+}
+
+/// Keybinding and command reference, built from the live keymap on every
+/// draw so remaps show up as configured.
+#[derive(Default)]
+pub struct HelpView {
+    query: String,
+    searching: bool,
+    scroll: usize,
+    last_height: u16,
+}
+
+impl HelpView {
+    pub fn is_searching(&self) -> bool {
+        self.searching
+    }
+
+    /// Text entry for the filter; bypasses the keymap.
+    pub fn search_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter => self.searching = false,
+            KeyCode::Esc => {
+                self.searching = false;
+                self.query.clear();
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+            }
+            KeyCode::Char(c) => self.query.push(c),
+            _ => {}
+        }
+        self.scroll = 0;
+    }
+
+    /// Returns false when the overlay should be closed.
+    pub fn handle_action(&mut self, action: Action) -> bool {
+        let page = self.last_height.saturating_sub(3).max(1) as usize;
+        match action {
+            Action::HelpClose => return false,
+            Action::HelpDown => self.scroll += 1,
+            Action::HelpUp => self.scroll = self.scroll.saturating_sub(1),
+            Action::HelpPageDown => self.scroll += page,
+            Action::HelpPageUp => self.scroll = self.scroll.saturating_sub(page),
+            Action::HelpTop => self.scroll = 0,
+            Action::HelpBottom => self.scroll = usize::MAX,
+            Action::HelpSearch => self.searching = true,
+            _ => {}
+        }
+        true
+    }
+
+    fn sections(keys: &Keymap, commands: &[SlashCommand], vim_mode: bool) -> Vec<Section> {
+        let mut sections = Vec::new();
+        for context in Context::ALL {
+            if context == Context::Normal && !vim_mode {
+                continue;
+            }
+            let rows = Action::ALL
+                .iter()
+                .filter(|a| a.context() == context)
+                .filter(|a| vim_mode || **a != Action::NormalMode)
+                .map(|&a| {
+                    let label = keys.label(a);
+                    let label = if label.is_empty() {
+                        "unbound".to_string()
+                    } else {
+                        label
+                    };
+                    (label, a.description().to_string())
+                })
+                .collect();
+            sections.push(Section {
+                title: context.title().to_string(),
+                rows,
+            });
+        }
+
+        let mut rows: Vec<(String, String)> = commands
+            .iter()
+            .map(|c| {
+                (
+                    sanitize::inline(&c.name),
+                    sanitize::inline(c.description.as_deref().unwrap_or("")),
+                )
+            })
+            .collect();
+        rows.extend(
+            LOCAL_COMMANDS
+                .iter()
+                .map(|(name, desc)| (name.to_string(), desc.to_string())),
+        );
+        sections.push(Section {
+            title: "Slash commands".to_string(),
+            rows,
+        });
+
+        if vim_mode {
+            let mut rows = vec![(":<n>".to_string(), "Jump to message n".to_string())];
+            rows.extend(
+                vim::COMMANDS
+                    .iter()
+                    .map(|c| (c.usage(), c.description().to_string())),
+            );
+            rows.push((
+                ":<other>".to_string(),
+                "Sent to the host as /<other>".to_string(),
+            ));
+            sections.push(Section {
+                title: "Commands (:)".to_string(),
+                rows,
+            });
+        }
+        sections
+    }
+
+    pub fn render(
+        &mut self,
+        frame: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        keys: &Keymap,
+        commands: &[SlashCommand],
+        vim_mode: bool,
+    ) {
+        crate::clear_area(frame, area, theme);
+        self.last_height = area.height;
+
+        let query = self.query.to_lowercase();
+        let mut lines: Vec<Line> = Vec::new();
+        for section in Self::sections(keys, commands, vim_mode) {
+            let title_match = section.title.to_lowercase().contains(&query);
+            let rows: Vec<&(String, String)> = section
+                .rows
+                .iter()
+                .filter(|(key, desc)| {
+                    title_match
+                        || key.to_lowercase().contains(&query)
+                        || desc.to_lowercase().contains(&query)
+                })
+                .collect();
+            if rows.is_empty() {
+                continue;
+            }
+            if !lines.is_empty() {
+                lines.push(Line::from(""));
+            }
+            lines.push(Line::from(Span::styled(
+                section.title.clone(),
+                Style::default().fg(theme.accent).bold(),
+            )));
+            for (key, desc) in rows {
+                let width = unicode_width::UnicodeWidthStr::width(key.as_str());
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("  {}{}", key, " ".repeat(KEY_COLUMN.saturating_sub(width))),
+                        Style::default().fg(theme.warning),
+                    ),
+                    Span::styled(format!(" {}", desc), Style::default().fg(theme.text)),
+                ]));
+            }
+        }
+        if lines.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "No matching keys or commands",
+                Style::default().fg(theme.dim),
+            )));
+        }
+
+        let title = if self.searching || !self.query.is_empty() {
+            let cursor = if self.searching { "▊" } else { "" };
+            format!(" Help • /{}{} ", sanitize::inline(&self.query), cursor)
+        } else {
+            " Help ".to_string()
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(Span::styled(
+                title,
+                Style::default().fg(theme.accent).bold(),
+            ))
+            .title_bottom(Span::styled(
+                keys.hints(&[
+                    (Action::HelpSearch, "search"),
+                    (Action::HelpDown, "scroll"),
+                    (Action::HelpClose, "close"),
+                ]),
+                Style::default().fg(theme.dim),
+            ));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        self.scroll = self
+            .scroll
+            .min(lines.len().saturating_sub(inner.height as usize));
+        frame.render_widget(Paragraph::new(lines).scroll((self.scroll as u16, 0)), inner);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_every_named_command_in_vim_mode() {
+        let keys = Keymap::default();
+        let sections = HelpView::sections(&keys, &[], true);
+        let rows = &sections.last().unwrap().rows;
+        for named in vim::COMMANDS {
+            assert!(
+                rows.iter().any(|(usage, _)| *usage == named.usage()),
+                "{}",
+                named.usage()
+            );
+        }
+        assert!(rows.iter().any(|(usage, _)| usage == ":tabn"));
+        let sections = HelpView::sections(&keys, &[], false);
+        assert!(sections.iter().all(|s| s.title != "Commands (:)"));
+    }
+}
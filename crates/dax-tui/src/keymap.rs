@@ -0,0 +1,653 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// How long a partial multi-key sequence waits for its next key.
+const SEQUENCE_TIMEOUT: Duration = Duration::from_millis(1000);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Context {
+    Global,
+    Chat,
+    Normal,
+    Input,
+    Gate,
+    Diff,
+    Review,
+    Help,
+    Waterfall,
+    Error,
+    Sidebar,
+    Preview,
+    Policy,
+    Rao,
+    Plan,
+}
+
+impl Context {
+    pub const ALL: [Context; 15] = [
+        Context::Global,
+        Context::Chat,
+        Context::Normal,
+        Context::Input,
+        Context::Gate,
+        Context::Diff,
+        Context::Review,
+        Context::Help,
+        Context::Waterfall,
+        Context::Error,
+        Context::Sidebar,
+        Context::Preview,
+        Context::Policy,
+        Context::Rao,
+        Context::Plan,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Context::Global => "global",
+            Context::Chat => "chat",
+            Context::Normal => "normal",
+            Context::Input => "input",
+            Context::Gate => "gate",
+            Context::Diff => "diff",
+            Context::Review => "review",
+            Context::Help => "help",
+            Context::Waterfall => "waterfall",
+            Context::Error => "error",
+            Context::Sidebar => "sidebar",
+            Context::Preview => "preview",
+            Context::Policy => "policy",
+            Context::Rao => "rao",
+            Context::Plan => "plan",
+        }
+    }
+
+    /// Section heading in the help overlay.
+    pub fn title(self) -> &'static str {
+        match self {
+            Context::Global => "Global",
+            Context::Chat => "Chat",
+            Context::Normal => "Normal mode",
+            Context::Input => "Input",
+            Context::Gate => "Gate prompt",
+            Context::Diff => "Diff viewer",
+            Context::Review => "Hunk review",
+            Context::Help => "Help",
+            Context::Waterfall => "Timing waterfall",
+            Context::Error => "Error banner",
+            Context::Sidebar => "Context sidebar",
+            Context::Preview => "File preview",
+            Context::Policy => "PM policy",
+            Context::Rao => "RAO history",
+            Context::Plan => "Plan checklist",
+        }
+    }
+}
+
+macro_rules! actions {
+    ($($variant:ident => $ctx:ident, $name:literal, $desc:literal, [$($key:literal),*];)*) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Action {
+            $($variant,)*
+        }
+
+        impl Action {
+            pub const ALL: &'static [Action] = &[$(Action::$variant,)*];
+
+            pub fn context(self) -> Context {
+                match self {
+                    $(Action::$variant => Context::$ctx,)*
+                }
+            }
+
+            /// The name used in the `[keybindings]` config section.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Action::$variant => $name,)*
+                }
+            }
+
+            pub fn description(self) -> &'static str {
+                match self {
+                    $(Action::$variant => $desc,)*
+                }
+            }
+
+            fn default_keys(self) -> &'static [&'static str] {
+                match self {
+                    $(Action::$variant => &[$($key),*],)*
+                }
+            }
+        }
+    };
+}
+
+actions! {
+    Quit => Global, "quit", "Quit dax-tui", ["ctrl+c"];
+    OpenDiff => Global, "open_diff", "Open the diff viewer", ["ctrl+d", "alt+d"];
+    OpenReview => Global, "open_review", "Review proposed hunks", ["ctrl+r"];
+    ShowGate => Global, "show_gate", "Show the pending gate", ["ctrl+g"];
+    ShowDiagnostics => Global, "show_diagnostics", "Show config diagnostics", ["ctrl+e"];
+    ShowHelp => Global, "help", "Show keybindings and commands", ["f1"];
+    ToggleSidebar => Global, "toggle_sidebar", "Open or close the context drawer", ["ctrl+b"];
+    FocusContext => Global, "focus_context", "Browse the context files in the sidebar", ["ctrl+f"];
+    CycleThinking => Global, "thinking_mode", "Cycle reasoning display: off, minimal, verbose", ["f2"];
+    OpenWaterfall => Global, "waterfall", "Show the timing waterfall for recent turns", ["ctrl+t"];
+    OpenPolicy => Global, "policy", "Show the PM constraints and preferences", ["alt+m"];
+    OpenRaoHistory => Global, "rao_history", "Browse RAO run, audit and override history", ["ctrl+y"];
+    OpenPlan => Global, "plan", "Show the plan checklist to comment on steps", ["ctrl+l"];
+    NextSession => Global, "next_session", "Switch to the next session tab", ["alt+n", "ctrl+pagedown"];
+    PrevSession => Global, "prev_session", "Switch to the previous session tab", ["alt+p", "ctrl+pageup"];
+    NewSession => Global, "new_session", "Start a new session in its own tab", ["alt+t"];
+    CloseSession => Global, "close_session", "Close the session tab in view (press twice)", ["alt+w"];
+
+    ScrollUp => Chat, "scroll_up", "Select previous message", ["up"];
+    ScrollDown => Chat, "scroll_down", "Select next message", ["down"];
+    PageUp => Chat, "page_up", "Scroll up a page", ["pageup"];
+    PageDown => Chat, "page_down", "Scroll down a page", ["pagedown"];
+    ScrollTop => Chat, "scroll_top", "Jump to the first message", ["home"];
+    ScrollBottom => Chat, "scroll_bottom", "Jump to the latest message", ["end"];
+    ToggleThinking => Chat, "toggle_thinking", "Expand or collapse the selected reasoning block", ["ctrl+o"];
+    EditMessage => Chat, "edit_message", "Edit the selected user message and resend it as a new branch", ["alt+e"];
+    PrevBranch => Chat, "prev_branch", "Show the previous branch from this point", ["alt+left"];
+    NextBranch => Chat, "next_branch", "Show the next branch from this point", ["alt+right"];
+
+    NormalDown => Normal, "down", "Select next message", ["j"];
+    NormalUp => Normal, "up", "Select previous message", ["k"];
+    NormalTop => Normal, "top", "Jump to the first message", ["g g"];
+    NormalBottom => Normal, "bottom", "Jump to the latest message", ["G"];
+    HalfPageDown => Normal, "half_page_down", "Scroll down half a page", ["ctrl+d"];
+    HalfPageUp => Normal, "half_page_up", "Scroll up half a page", ["ctrl+u"];
+    NextTool => Normal, "next_tool", "Next message with tool calls", ["] t"];
+    PrevTool => Normal, "prev_tool", "Previous message with tool calls", ["[ t"];
+    NextError => Normal, "next_error", "Next failed tool call", ["] e"];
+    PrevError => Normal, "prev_error", "Previous failed tool call", ["[ e"];
+    Yank => Normal, "yank", "Copy the selected message", ["y"];
+    Search => Normal, "search", "Search messages", ["/"];
+    SearchNext => Normal, "search_next", "Next search match", ["n"];
+    SearchPrev => Normal, "search_prev", "Previous search match", ["N"];
+    InsertMode => Normal, "insert", "Enter insert mode", ["i"];
+    CommandMode => Normal, "command", "Enter a : command", [":"];
+    NormalHelp => Normal, "help", "Show keybindings and commands", ["?"];
+
+    Submit => Input, "submit", "Send the input", ["enter"];
+    DeleteBack => Input, "delete_back", "Delete the previous character", ["backspace"];
+    NormalMode => Input, "normal_mode", "Leave insert mode (vim mode)", ["esc"];
+
+    GateApprove => Gate, "approve_once", "Approve once", ["a"];
+    GateAllowTool => Gate, "always_allow_tool", "Always allow this tool", ["t"];
+    GateAllowPath => Gate, "always_allow_path", "Always allow this path", ["p"];
+    GateReject => Gate, "reject", "Reject", ["r"];
+    GateReview => Gate, "review", "Review hunks", ["v"];
+    GateDismiss => Gate, "dismiss", "Hide the prompt", ["esc"];
+
+    DiffClose => Diff, "close", "Close the diff", ["esc", "q"];
+    DiffDown => Diff, "down", "Scroll down", ["down", "j"];
+    DiffUp => Diff, "up", "Scroll up", ["up", "k"];
+    DiffPageDown => Diff, "page_down", "Page down", ["pagedown", "space"];
+    DiffPageUp => Diff, "page_up", "Page up", ["pageup"];
+    DiffTop => Diff, "top", "Jump to top", ["home", "g"];
+    DiffBottom => Diff, "bottom", "Jump to bottom", ["end", "G"];
+    DiffNextFile => Diff, "next_file", "Next file", ["tab"];
+    DiffPrevFile => Diff, "prev_file", "Previous file", ["backtab"];
+    DiffNextHunk => Diff, "next_hunk", "Next hunk", ["n"];
+    DiffPrevHunk => Diff, "prev_hunk", "Previous hunk", ["N"];
+    DiffToggleSplit => Diff, "toggle_split", "Toggle side-by-side", ["s"];
+
+    ReviewClose => Review, "close", "Cancel the review", ["esc", "q"];
+    ReviewNext => Review, "next", "Next hunk", ["down", "j", "tab"];
+    ReviewPrev => Review, "prev", "Previous hunk", ["up", "k", "backtab"];
+    ReviewPageDown => Review, "page_down", "Scroll hunk down", ["pagedown", "space"];
+    ReviewPageUp => Review, "page_up", "Scroll hunk up", ["pageup"];
+    ReviewAccept => Review, "accept", "Accept hunk", ["a", "y"];
+    ReviewReject => Review, "reject", "Reject hunk", ["r", "n"];
+    ReviewUndo => Review, "undo", "Reset hunk to pending", ["u"];
+    ReviewComment => Review, "comment", "Comment on hunk", ["c"];
+    ReviewAcceptAll => Review, "accept_all", "Accept all hunks", ["A"];
+    ReviewRejectAll => Review, "reject_all", "Reject all hunks", ["R"];
+    ReviewSubmit => Review, "submit", "Send the review", ["enter", "s"];
+
+    HelpClose => Help, "close", "Close help", ["esc", "q", "f1"];
+    HelpDown => Help, "down", "Scroll down", ["down", "j"];
+    HelpUp => Help, "up", "Scroll up", ["up", "k"];
+    HelpPageDown => Help, "page_down", "Page down", ["pagedown", "space"];
+    HelpPageUp => Help, "page_up", "Page up", ["pageup"];
+    HelpTop => Help, "top", "Jump to top", ["home", "g"];
+    HelpBottom => Help, "bottom", "Jump to bottom", ["end", "G"];
+    HelpSearch => Help, "search", "Filter entries", ["/"];
+    WaterfallClose => Waterfall, "close", "Close the waterfall", ["esc", "q"];
+    WaterfallDown => Waterfall, "down", "Select the next bar", ["down", "j"];
+    WaterfallUp => Waterfall, "up", "Select the previous bar", ["up", "k"];
+    WaterfallPrevTurn => Waterfall, "prev_turn", "Show the previous turn", ["left", "h"];
+    WaterfallNextTurn => Waterfall, "next_turn", "Show the next turn", ["right", "l"];
+    ErrorRetry => Error, "retry", "Retry the failed turn", ["alt+r"];
+    ErrorFallback => Error, "fallback", "Retry with a fallback provider", ["alt+f"];
+    ErrorDismiss => Error, "dismiss", "Dismiss the error", ["esc"];
+    SidebarClose => Sidebar, "close", "Return to the chat", ["esc", "q"];
+    SidebarDown => Sidebar, "down", "Select the next file", ["down", "j"];
+    SidebarUp => Sidebar, "up", "Select the previous file", ["up", "k"];
+    SidebarTop => Sidebar, "top", "Select the first file", ["home", "g"];
+    SidebarBottom => Sidebar, "bottom", "Select the last file", ["end", "G"];
+    SidebarFilter => Sidebar, "filter", "Filter files by path", ["/"];
+    SidebarRemove => Sidebar, "remove", "Remove the file from context", ["x", "delete"];
+    SidebarPin => Sidebar, "pin", "Pin or unpin the file", ["p"];
+    SidebarPreview => Sidebar, "preview", "Preview the file", ["enter", "space"];
+    PreviewClose => Preview, "close", "Close the preview", ["esc", "q"];
+    PreviewDown => Preview, "down", "Scroll down", ["down", "j"];
+    PreviewUp => Preview, "up", "Scroll up", ["up", "k"];
+    PreviewPageDown => Preview, "page_down", "Page down", ["pagedown", "space"];
+    PreviewPageUp => Preview, "page_up", "Page up", ["pageup"];
+    PolicyClose => Policy, "close", "Close the panel or cancel a change", ["esc", "q"];
+    PolicyDown => Policy, "down", "Select the next rule", ["down", "j"];
+    PolicyUp => Policy, "up", "Select the previous rule", ["up", "k"];
+    PolicyAddAllow => Policy, "add_allow", "Add an always_allow tool or path rule", ["a"];
+    PolicyAddNeverTouch => Policy, "add_never_touch", "Add a never_touch glob", ["n"];
+    PolicyEdit => Policy, "edit", "Edit the selected never_touch glob", ["e", "enter"];
+    PolicyRemove => Policy, "remove", "Remove the selected glob or revoke the rule", ["x", "delete"];
+    PolicyConfirm => Policy, "confirm", "Send the pending change to the host", ["y"];
+    RaoClose => Rao, "close", "Close the history", ["esc", "q"];
+    RaoDown => Rao, "down", "Select the next snapshot", ["down", "j"];
+    RaoUp => Rao, "up", "Select the previous snapshot", ["up", "k"];
+    RaoTop => Rao, "top", "Select the oldest snapshot", ["home", "g"];
+    RaoBottom => Rao, "bottom", "Select the newest snapshot", ["end", "G"];
+    RaoFilter => Rao, "filter", "Cycle the kind filter: all, run, audit, override, clear", ["f", "tab"];
+    RaoExportJson => Rao, "export_json", "Export the listed snapshots as JSON", ["e"];
+    RaoExportMarkdown => Rao, "export_markdown", "Export the listed snapshots as markdown", ["m"];
+    PlanClose => Plan, "close", "Close the checklist", ["esc", "q"];
+    PlanDown => Plan, "down", "Select the next step", ["down", "j"];
+    PlanUp => Plan, "up", "Select the previous step", ["up", "k"];
+    PlanComment => Plan, "comment", "Send feedback on the selected step", ["c", "enter"];
+}
+
+impl Action {
+    pub fn lookup(context: Context, name: &str) -> Option<Action> {
+        Action::ALL
+            .iter()
+            .copied()
+            .find(|a| a.context() == context && a.name() == name)
+    }
+}
+
+/// A single key press with the modifiers that matter for matching. Shift
+/// is folded into the character for printable keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        if matches!(code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Self { code, modifiers }
+    }
+
+    pub fn from_event(key: KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+
+    /// Parses `ctrl+d`, `alt+enter`, `shift+tab`, `G`, `?`, `f1`, `space`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut parts: Vec<&str> = text.split('+').collect();
+        // A trailing "+" means the plus key itself, e.g. "ctrl++".
+        if text.ends_with("++") || text == "+" {
+            parts.retain(|p| !p.is_empty());
+            parts.push("+");
+        }
+        let key = parts.pop().filter(|k| !k.is_empty()).ok_or("empty key")?;
+        for part in parts {
+            match part.to_lowercase().as_str() {
+                "ctrl" | "control" => modifiers |= KeyModifiers::CONTROL,
+                "alt" | "meta" => modifiers |= KeyModifiers::ALT,
+                "shift" => modifiers |= KeyModifiers::SHIFT,
+                other => return Err(format!("unknown modifier '{}'", other)),
+            }
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                f if f.starts_with('f') => match f[1..].parse::<u8>() {
+                    Ok(n @ 1..=24) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key '{}'", key)),
+                },
+                _ => return Err(format!("unknown key '{}'", key)),
+            },
+        };
+        // Ctrl+letter arrives lowercase regardless of shift.
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::CONTROL) => {
+                KeyCode::Char(c.to_ascii_lowercase())
+            }
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            other => other,
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) if self.modifiers.contains(KeyModifiers::CONTROL) => {
+                write!(f, "{}", c.to_ascii_uppercase())
+            }
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::BackTab => write!(f, "Shift+Tab"),
+            KeyCode::Backspace => write!(f, "Backspace"),
+            KeyCode::Delete => write!(f, "Del"),
+            KeyCode::Insert => write!(f, "Ins"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::Home => write!(f, "Home"),
+            KeyCode::End => write!(f, "End"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+pub type Sequence = Vec<KeyChord>;
+
+/// Parses a space-separated chord sequence such as `g g` or `ctrl+x ctrl+s`.
+pub fn parse_sequence(text: &str) -> Result<Sequence, String> {
+    let chords: Result<Sequence, String> = text.split_whitespace().map(KeyChord::parse).collect();
+    let chords = chords?;
+    if chords.is_empty() {
+        return Err("empty key sequence".to_string());
+    }
+    Ok(chords)
+}
+
+pub fn sequence_label(seq: &[KeyChord]) -> String {
+    seq.iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub enum Resolved {
+    Action(Action),
+    /// The key started or continued a multi-key sequence.
+    Pending,
+    /// Nothing bound; the key may still be text input.
+    Unbound(KeyEvent),
+}
+
+pub struct Keymap {
+    bindings: HashMap<Action, Vec<Sequence>>,
+    pending: Vec<KeyChord>,
+    pending_since: Instant,
+    /// An exact match held back because a longer sequence shares its prefix.
+    deferred: Option<Action>,
+    /// A key that broke a sequence after a deferred action fired; it is
+    /// handled again on its own.
+    replay: Option<KeyEvent>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .iter()
+            .map(|&action| {
+                let keys = action
+                    .default_keys()
+                    .iter()
+                    .filter_map(|k| parse_sequence(k).ok())
+                    .collect();
+                (action, keys)
+            })
+            .collect();
+        Self {
+            bindings,
+            pending: Vec::new(),
+            pending_since: Instant::now(),
+            deferred: None,
+            replay: None,
+        }
+    }
+}
+
+impl Keymap {
+    /// Replaces the keys for `action`, as a user remap does.
+    pub fn bind(&mut self, action: Action, keys: Vec<Sequence>) {
+        self.bindings.insert(action, keys);
+    }
+
+    pub fn keys(&self, action: Action) -> &[Sequence] {
+        self.bindings.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// All keys bound to `action`, e.g. `Esc, q`.
+    pub fn label(&self, action: Action) -> String {
+        self.keys(action)
+            .iter()
+            .map(|seq| sequence_label(seq))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Short label of the first key bound to `action`, for hint lines.
+    pub fn hint(&self, action: Action) -> String {
+        self.keys(action)
+            .first()
+            .map(|seq| sequence_label(seq))
+            .unwrap_or_else(|| "unbound".to_string())
+    }
+
+    /// A hint line such as ` Tab file • n hunk • Esc close `.
+    pub fn hints(&self, items: &[(Action, &str)]) -> String {
+        let parts: Vec<String> = items
+            .iter()
+            .map(|(action, label)| format!("{} {}", self.hint(*action), label))
+            .collect();
+        format!(" {} ", parts.join(" • "))
+    }
+
+    /// Reports pairs of actions in the same context bound to the same keys,
+    /// and global actions whose every key is taken in the chat, where
+    /// chat, input and normal-mode keys are looked up first.
+    pub fn conflicts(&self) -> Vec<String> {
+        let mut seen: HashMap<(Context, &Sequence), Action> = HashMap::new();
+        let mut out = Vec::new();
+        for &action in Action::ALL {
+            for seq in self.keys(action) {
+                match seen.get(&(action.context(), seq)) {
+                    Some(other) => out.push(format!(
+                        "{} is bound to both {}.{} and {}.{}",
+                        sequence_label(seq),
+                        action.context().name(),
+                        other.name(),
+                        action.context().name(),
+                        action.name()
+                    )),
+                    None => {
+                        seen.insert((action.context(), seq), action);
+                    }
+                }
+            }
+        }
+        for stack in [
+            [Context::Input, Context::Chat],
+            [Context::Normal, Context::Chat],
+        ] {
+            for &action in Action::ALL
+                .iter()
+                .filter(|a| a.context() == Context::Global)
+            {
+                let keys = self.keys(action);
+                let shadowed: Vec<(&Sequence, Action)> = keys
+                    .iter()
+                    .filter_map(|seq| {
+                        stack
+                            .iter()
+                            .find_map(|&ctx| seen.get(&(ctx, seq)))
+                            .map(|&other| (seq, other))
+                    })
+                    .collect();
+                if keys.is_empty() || shadowed.len() < keys.len() {
+                    continue;
+                }
+                for (seq, other) in shadowed {
+                    out.push(format!(
+                        "{} runs {}.{} there, so global.{} can't be reached in {} mode",
+                        sequence_label(seq),
+                        other.context().name(),
+                        other.name(),
+                        action.name(),
+                        stack[0].name()
+                    ));
+                }
+            }
+        }
+        out
+    }
+
+    fn candidates<'a>(
+        &'a self,
+        contexts: &'a [Context],
+    ) -> impl Iterator<Item = (Action, &'a Sequence)> + 'a {
+        contexts.iter().flat_map(move |&ctx| {
+            Action::ALL
+                .iter()
+                .filter(move |a| a.context() == ctx)
+                .flat_map(move |&a| self.keys(a).iter().map(move |seq| (a, seq)))
+        })
+    }
+
+    pub fn take_replay(&mut self) -> Option<KeyEvent> {
+        self.replay.take()
+    }
+
+    /// Fires a deferred action once its sequence has timed out.
+    pub fn tick(&mut self) -> Option<Action> {
+        if self.pending.is_empty() || self.pending_since.elapsed() < SEQUENCE_TIMEOUT {
+            return None;
+        }
+        self.pending.clear();
+        self.deferred.take()
+    }
+
+    /// Matches `key` against the bindings of `contexts`, earlier contexts
+    /// taking precedence.
+    pub fn resolve(&mut self, contexts: &[Context], key: KeyEvent) -> Resolved {
+        if !self.pending.is_empty() && self.pending_since.elapsed() >= SEQUENCE_TIMEOUT {
+            self.pending.clear();
+            self.deferred = None;
+        }
+        let chord = KeyChord::from_event(key);
+        self.pending.push(chord);
+        self.pending_since = Instant::now();
+
+        let mut exact = None;
+        let mut longer = false;
+        for (action, seq) in self.candidates(contexts) {
+            if seq.as_slice() == self.pending.as_slice() {
+                exact.get_or_insert(action);
+            } else if seq.starts_with(&self.pending) {
+                longer = true;
+            }
+        }
+
+        match (exact, longer) {
+            (Some(action), false) => {
+                self.pending.clear();
+                self.deferred = None;
+                Resolved::Action(action)
+            }
+            (exact, true) => {
+                self.deferred = exact;
+                Resolved::Pending
+            }
+            (None, false) => {
+                let was_sequence = self.pending.len() > 1;
+                let deferred = self.deferred.take();
+                self.pending.clear();
+                match deferred {
+                    Some(action) => {
+                        self.replay = Some(key);
+                        Resolved::Action(action)
+                    }
+                    None if was_sequence => self.resolve(contexts, key),
+                    None => Resolved::Unbound(key),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_have_no_conflicts() {
+        assert_eq!(Keymap::default().conflicts(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn reports_shadowed_global_actions() {
+        let mut keymap = Keymap::default();
+        keymap.bind(Action::OpenDiff, vec![parse_sequence("ctrl+d").unwrap()]);
+        assert_eq!(
+            keymap.conflicts(),
+            ["Ctrl+D runs normal.half_page_down there, so global.open_diff can't be reached in normal mode"]
+        );
+    }
+
+    #[test]
+    fn parses_chords() {
+        let chord = KeyChord::parse("ctrl+D").unwrap();
+        assert_eq!(
+            chord,
+            KeyChord::new(KeyCode::Char('d'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(KeyChord::parse("shift+tab").unwrap().code, KeyCode::BackTab);
+        assert_eq!(KeyChord::parse("ctrl++").unwrap().code, KeyCode::Char('+'));
+        assert!(KeyChord::parse("hyper+x").is_err());
+    }
+}
@@ -0,0 +1,194 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+
+use crate::config::{LayoutConfig, SidebarPosition};
+
+/// Smallest terminal the main screen is drawn in.
+pub const MIN_WIDTH: u16 = 40;
+pub const MIN_HEIGHT: u16 = 12;
+/// Below this width the sidebar no longer fits beside the chat.
+const NARROW_WIDTH: u16 = 100;
+/// Narrow terminals at least this tall stack the sidebar under the chat
+/// instead of hiding it in a drawer.
+const STACK_HEIGHT: u16 = 40;
+/// Below this height the header shrinks to a single status line.
+const COMPACT_HEIGHT: u16 = 30;
+/// Below this height the input box loses its spare rows.
+const SHORT_HEIGHT: u16 = 24;
+const DRAWER_MIN_WIDTH: u16 = 30;
+
+pub struct Areas {
+    /// Session tab bar; empty with a single session.
+    pub tabs: Rect,
+    pub header: Rect,
+    /// One borderless status line instead of the boxed header.
+    pub compact_header: bool,
+    /// Everything between header and input; overlays are drawn here.
+    pub body: Rect,
+    pub chat: Rect,
+    pub sidebar: Option<Rect>,
+    /// The sidebar is drawn as a drawer over the chat.
+    pub drawer: bool,
+    /// The sidebar is collapsed and can be opened as a drawer.
+    pub collapsed: bool,
+    pub input: Rect,
+    /// Usage and timing line under the input.
+    pub status: Rect,
+}
+
+pub fn too_small(area: Rect) -> bool {
+    area.width < MIN_WIDTH || area.height < MIN_HEIGHT
+}
+
+pub fn compute(area: Rect, config: &LayoutConfig, drawer_open: bool, tab_bar: bool) -> Areas {
+    let compact_header = area.height < COMPACT_HEIGHT;
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(u16::from(tab_bar)),
+            Constraint::Length(if compact_header { 1 } else { 3 }),
+            Constraint::Min(0),
+            Constraint::Length(if area.height < SHORT_HEIGHT { 3 } else { 6 }),
+            Constraint::Length(1),
+        ])
+        .split(area);
+    let body = rows[2];
+    let mut areas = Areas {
+        tabs: rows[0],
+        header: rows[1],
+        compact_header,
+        body,
+        chat: body,
+        sidebar: None,
+        drawer: false,
+        collapsed: false,
+        input: rows[3],
+        status: rows[4],
+    };
+    if config.panes.is_empty() {
+        return areas;
+    }
+
+    let sidebar_first = config.sidebar_position == SidebarPosition::Left;
+    if area.width >= NARROW_WIDTH || area.height >= STACK_HEIGHT {
+        let (direction, sidebar) = if area.width >= NARROW_WIDTH {
+            (Direction::Horizontal, config.sidebar_width)
+        } else {
+            (Direction::Vertical, 35)
+        };
+        let constraints = if sidebar_first {
+            [
+                Constraint::Percentage(sidebar),
+                Constraint::Percentage(100 - sidebar),
+            ]
+        } else {
+            [
+                Constraint::Percentage(100 - sidebar),
+                Constraint::Percentage(sidebar),
+            ]
+        };
+        let split = Layout::default()
+            .direction(direction)
+            .constraints(constraints)
+            .split(body);
+        let (chat, sidebar) = if sidebar_first {
+            (split[1], split[0])
+        } else {
+            (split[0], split[1])
+        };
+        areas.chat = chat;
+        areas.sidebar = Some(sidebar);
+    } else if drawer_open {
+        let width = (body.width * config.sidebar_width / 100)
+            .max(DRAWER_MIN_WIDTH)
+            .min(body.width);
+        let x = if sidebar_first {
+            body.x
+        } else {
+            body.x + body.width - width
+        };
+        areas.sidebar = Some(Rect::new(x, body.y, width, body.height));
+        areas.drawer = true;
+    } else {
+        areas.collapsed = true;
+    }
+    areas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(width: u16, height: u16) -> Rect {
+        Rect::new(0, 0, width, height)
+    }
+
+    #[test]
+    fn wide_terminal_puts_the_sidebar_beside_the_chat() {
+        let areas = compute(area(120, 40), &LayoutConfig::default(), false, false);
+        let sidebar = areas.sidebar.expect("sidebar");
+        assert_eq!(areas.tabs.height, 0);
+        assert_eq!(areas.header.height, 3);
+        assert_eq!(sidebar.y, areas.chat.y);
+        assert_eq!(sidebar.x, areas.chat.x + areas.chat.width);
+        assert_eq!(areas.chat.width + sidebar.width, 120);
+        assert!(!areas.drawer && !areas.collapsed);
+    }
+
+    #[test]
+    fn left_sidebar_comes_first() {
+        let config = LayoutConfig {
+            sidebar_position: SidebarPosition::Left,
+            ..LayoutConfig::default()
+        };
+        let areas = compute(area(120, 40), &config, false, false);
+        assert_eq!(areas.sidebar.expect("sidebar").x, 0);
+    }
+
+    #[test]
+    fn narrow_tall_terminal_stacks_the_sidebar() {
+        let areas = compute(area(80, 50), &LayoutConfig::default(), false, false);
+        let sidebar = areas.sidebar.expect("sidebar");
+        assert_eq!(sidebar.x, areas.chat.x);
+        assert_eq!(sidebar.y, areas.chat.y + areas.chat.height);
+    }
+
+    #[test]
+    fn narrow_short_terminal_uses_a_drawer() {
+        let config = LayoutConfig::default();
+        let closed = compute(area(80, 24), &config, false, false);
+        assert!(closed.collapsed && closed.sidebar.is_none());
+        assert_eq!(closed.chat, closed.body);
+        assert!(closed.compact_header);
+
+        let open = compute(area(80, 24), &config, true, false);
+        let drawer = open.sidebar.expect("drawer");
+        assert!(open.drawer);
+        assert_eq!(drawer.width, DRAWER_MIN_WIDTH);
+        assert_eq!(drawer.x + drawer.width, 80);
+    }
+
+    #[test]
+    fn no_panes_means_no_sidebar() {
+        let config = LayoutConfig {
+            panes: Vec::new(),
+            ..LayoutConfig::default()
+        };
+        let areas = compute(area(120, 40), &config, true, true);
+        assert!(areas.sidebar.is_none());
+        assert_eq!(areas.tabs.height, 1);
+        assert_eq!(areas.chat, areas.body);
+    }
+
+    #[test]
+    fn short_terminal_shrinks_the_input() {
+        let config = LayoutConfig::default();
+        assert_eq!(
+            compute(area(120, 20), &config, false, false).input.height,
+            3
+        );
+        assert_eq!(
+            compute(area(120, 40), &config, false, false).input.height,
+            6
+        );
+    }
+}
@@ -1,14 +1,17 @@
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+        KeyModifiers,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, Paragraph, Scrollbar, ScrollbarState},
+    widgets::{Block, Borders, Clear, List, Paragraph, Scrollbar, ScrollbarState},
     Frame, Terminal,
 };
 use serde::{Deserialize, Serialize};
@@ -17,43 +20,56 @@ use std::sync::mpsc;
 use std::thread;
 use std::time::Duration;
 
-struct Theme {
-    bg: Color,
-    text: Color,
-    dim: Color,
-    border: Color,
-    accent: Color,
-    success: Color,
-    warning: Color,
-    error: Color,
-    user: Color,
-    assistant: Color,
-}
+mod ansi;
+mod branch;
+mod config;
+mod context;
+mod diff;
+mod errors;
+mod export;
+mod gate;
+mod help;
+mod keymap;
+mod layout;
+mod plan;
+mod pm;
+mod policy_sim;
+mod rao;
+mod review;
+mod sanitize;
+mod scope;
+mod store;
+mod tabs;
+mod text;
+mod theme;
+mod thinking;
+mod timeline;
+mod usage;
+mod vim;
+mod waterfall;
 
-impl Theme {
-    fn default() -> Self {
-        Self {
-            bg: Color::Reset,
-            text: Color::White,
-            dim: Color::DarkGray,
-            border: Color::DarkGray,
-            accent: Color::Cyan,
-            success: Color::Green,
-            warning: Color::Yellow,
-            error: Color::Red,
-            user: Color::LightBlue,
-            assistant: Color::LightGreen,
-        }
-    }
-}
+use config::Config;
+use keymap::{Action, Context, Resolved};
+use theme::Theme;
+
+const TOOL_OUTPUT_LINES: usize = 6;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum TuiMessage {
     #[serde(rename = "dispatch")]
-    Dispatch { event: StreamEvent },
+    Dispatch {
+        event: StreamEvent,
+        /// Host clock, epoch milliseconds, when the event was created.
+        timestamp: Option<u64>,
+    },
     #[serde(rename = "addUserMessage")]
-    AddUserMessage { content: String },
+    AddUserMessage {
+        content: String,
+        /// Stable id from the host; the TUI makes one up if it's missing.
+        #[serde(default)]
+        id: Option<String>,
+    },
     #[serde(rename = "setContext")]
     SetContext {
         files: Vec<String>,
@@ -61,10 +77,43 @@ pub enum TuiMessage {
     },
     #[serde(rename = "updateState")]
     UpdateState { state: String },
+    /// A line for the transcript from the host rather than the model.
+    #[serde(rename = "addSystemMessage")]
+    AddSystemMessage {
+        content: String,
+        #[serde(default)]
+        role: Role,
+    },
+    #[serde(rename = "scopeStatus")]
+    ScopeStatus(scope::ScopeStatus),
+    #[serde(rename = "pmState")]
+    PmState(pm::PmState),
+    #[serde(rename = "raoHistory")]
+    RaoHistory { history: Vec<rao::Snapshot> },
+    #[serde(rename = "setCommands")]
+    SetCommands { commands: Vec<help::SlashCommand> },
+    /// The host's copy of the conversation, sent on (re)attach so a
+    /// transcript restored from disk can be brought up to date.
+    #[serde(rename = "snapshot")]
+    Snapshot {
+        messages: Vec<store::SnapshotMessage>,
+        #[serde(default)]
+        state: Option<String>,
+    },
     #[serde(rename = "destroy")]
     Destroy,
 }
 
+/// A host message and the session it belongs to. Messages without one
+/// go to the first tab.
+#[derive(Debug, Deserialize)]
+struct Envelope {
+    #[serde(default)]
+    session: Option<String>,
+    #[serde(flatten)]
+    message: TuiMessage,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "data")]
 pub enum StreamEvent {
@@ -77,10 +126,14 @@ pub enum StreamEvent {
     State { state: String },
     #[serde(rename = "text_delta")]
     TextDelta { text: String },
+    #[serde(rename = "thinking_delta")]
+    ThinkingDelta { text: String },
     #[serde(rename = "tool_call")]
     ToolCall {
         name: Option<String>,
         id: Option<String>,
+        arguments: Option<String>,
+        diff: Option<String>,
     },
     #[serde(rename = "tool_result")]
     ToolResult {
@@ -88,6 +141,7 @@ pub enum StreamEvent {
         success: Option<bool>,
         output: Option<String>,
         elapsed_ms: Option<u64>,
+        diff: Option<String>,
     },
     #[serde(rename = "gate")]
     Gate {
@@ -95,58 +149,1427 @@ pub enum StreamEvent {
         blocked: Option<bool>,
         warnings: Option<Vec<Warning>>,
     },
+    #[serde(rename = "gate_resolved")]
+    GateResolved { action: Option<String> },
+    #[serde(rename = "phase")]
+    Phase { phase: String },
+    #[serde(rename = "step")]
+    Step { phase: Option<String>, step: String },
+    /// Replaces the plan checklist.
+    #[serde(rename = "plan")]
+    Plan {
+        title: Option<String>,
+        steps: Vec<plan::Step>,
+    },
+    #[serde(rename = "plan_update")]
+    PlanUpdate {
+        id: String,
+        status: Option<plan::Status>,
+        title: Option<String>,
+    },
+    #[serde(rename = "usage")]
+    Usage {
+        input_tokens: Option<u64>,
+        output_tokens: Option<u64>,
+        cached_tokens: Option<u64>,
+        cost_usd: Option<f64>,
+        context_tokens: Option<u64>,
+        context_window: Option<u64>,
+    },
     #[serde(rename = "complete")]
-    Complete,
+    Complete {},
     #[serde(rename = "error")]
-    Error { message: Option<String> },
+    Error {
+        message: Option<String>,
+        code: Option<String>,
+        recoverable: Option<bool>,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Warning {
     pub code: String,
     pub subject: String,
+    pub message: Option<String>,
 }
 
 #[derive(Default)]
 struct AppState {
     messages: Vec<Message>,
     current_stream: String,
+    /// Reasoning streamed so far for the current turn.
+    current_thinking: String,
+    /// Expand or collapse override for the current turn's reasoning.
+    thinking_expanded: Option<bool>,
+    thinking_mode: thinking::Mode,
     stream_state: String,
     current_tool: Option<String>,
     tools: Vec<ToolState>,
     context_files: Vec<String>,
     context_scope: Vec<String>,
+    context: context::ContextPane,
+    /// Latest budget report from the host's scope tracker.
+    scope_status: Option<scope::ScopeStatus>,
+    /// Project manager constraints and preferences from the host.
+    policy: pm::Policy,
+    /// RAO run/audit/override/clear snapshots, oldest first.
+    rao_history: Vec<rao::Snapshot>,
+    plan: Option<plan::Plan>,
+    branches: branch::Branches,
+    /// Id of the user message being edited in the input.
+    editing: Option<String>,
+    /// Counter for locally assigned message ids.
+    next_id: u64,
+    /// Keys go to the files pane instead of the chat.
+    sidebar_focus: bool,
     input: String,
     scroll_state: ScrollbarState,
     chat_scroll: usize,
     provider: Option<String>,
     model: Option<String>,
+    /// Duration of the last completed turn.
     elapsed_ms: Option<u64>,
+    usage: usage::UsageTracker,
+    timeline: timeline::Timeline,
+    waterfall: waterfall::Recorder,
+    overlay: Option<Overlay>,
+    gate: Option<gate::GatePrompt>,
+    /// Every gate answered so far, oldest first.
+    gate_log: Vec<export::GateDecision>,
+    /// Last host error, shown as a banner until acted on.
+    error: Option<errors::ErrorBanner>,
+    /// Slash commands the host has registered, for the help overlay.
+    commands: Vec<help::SlashCommand>,
+    /// Set by the first press of a key that has to be pressed twice: quit
+    /// when `behavior.confirm_quit` is on, and closing a session.
+    armed: Option<Action>,
+    mode: vim::Mode,
+    /// Text typed after `/` or `:`.
+    prompt: String,
+    search: Option<String>,
+    /// One-off status shown in the header until the next key press.
+    notice: Option<String>,
+    /// First chat line shown, and the height it was last drawn at.
+    chat_offset: usize,
+    chat_height: usize,
+    /// Chat line at which each message starts, from the last draw.
+    message_lines: Vec<usize>,
+    /// Keep the end of the transcript in view as it grows.
+    follow_tail: bool,
+    /// Scroll the selected message into view on the next draw.
+    reveal: bool,
+    /// Whether the collapsed sidebar is shown as a drawer on narrow terminals.
+    drawer_open: bool,
+    /// Session tab key pressed here, for the main loop that owns the tabs.
+    tab_action: Option<Action>,
+    /// Host session this tab shows; everything it sends is tagged with it.
+    session: Option<String>,
+}
+
+enum Overlay {
+    Diff(diff::DiffView),
+    Gate,
+    Review(review::ReviewView),
+    Diagnostics,
+    Help(help::HelpView),
+    Waterfall(waterfall::WaterfallView),
+    Preview(context::Preview),
+    Policy(pm::PolicyView),
+    Rao(rao::HistoryView),
+    Plan(plan::PlanView),
+}
+
+/// Who a transcript entry comes from. Everything after `Assistant` is a
+/// one-line note rather than a conversation turn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    User,
+    Assistant,
+    #[default]
+    System,
+    Notice,
+    Warning,
+    Error,
+}
+
+impl Role {
+    fn is_note(self) -> bool {
+        !matches!(self, Role::User | Role::Assistant)
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Role::User => "You",
+            Role::Assistant => "DAX",
+            Role::System => "•",
+            Role::Notice => "ℹ",
+            Role::Warning => "⚠",
+            Role::Error => "✕",
+        }
+    }
+
+    fn color(self, theme: &Theme) -> Color {
+        match self {
+            Role::User => theme.user,
+            Role::Assistant => theme.assistant,
+            Role::System => theme.dim,
+            Role::Notice => theme.accent,
+            Role::Warning => theme.warning,
+            Role::Error => theme.error,
+        }
+    }
 }
 
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
 struct Message {
-    role: String,
+    /// Stable id: the host's for user messages, otherwise local.
+    id: String,
+    /// Set on the first message of a branch: the key of its sibling set.
+    fork: Option<String>,
+    role: Role,
     content: String,
     timestamp: u64,
+    /// How long the turn took, for assistant messages.
+    elapsed_ms: Option<u64>,
     tools: Vec<ToolState>,
+    thinking: String,
+    /// Set when the reasoning block was toggled by hand; otherwise the
+    /// thinking mode decides.
+    thinking_expanded: Option<bool>,
 }
 
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
 struct ToolState {
     name: String,
     id: String,
     status: String,
+    /// Arguments as the model sent them, usually a JSON string.
+    arguments: Option<String>,
     output: Option<String>,
     elapsed_ms: Option<u64>,
+    /// Parsed from the tool's diff; not kept in the session store.
+    #[serde(skip)]
+    diff: Vec<diff::FileDiff>,
+}
+
+impl AppState {
+    /// Adds a note to the transcript so it outlives the header state.
+    fn push_system(&mut self, role: Role, content: String) {
+        let id = self.new_id();
+        self.messages.push(Message {
+            id,
+            role,
+            content,
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            ..Default::default()
+        });
+    }
+
+    /// Moves the streamed reply, its thinking and its tools into the
+    /// transcript.
+    fn finish_reply(&mut self) {
+        let id = self.new_id();
+        self.messages.push(Message {
+            id,
+            fork: None,
+            role: Role::Assistant,
+            content: std::mem::take(&mut self.current_stream),
+            timestamp: waterfall::now_ms(),
+            elapsed_ms: self.elapsed_ms,
+            tools: std::mem::take(&mut self.tools),
+            thinking: std::mem::take(&mut self.current_thinking),
+            thinking_expanded: self.thinking_expanded.take(),
+        });
+    }
+
+    /// Diffs for the selected message, keyed by tool id, falling back to the
+    /// most recent tool call that produced one.
+    fn diff_for_selection(&self) -> Vec<(String, Vec<diff::FileDiff>)> {
+        let collect = |tools: &[ToolState]| -> Vec<(String, Vec<diff::FileDiff>)> {
+            tools
+                .iter()
+                .filter(|t| !t.diff.is_empty())
+                .map(|t| (t.id.clone(), t.diff.clone()))
+                .collect()
+        };
+        if let Some(msg) = self.messages.get(self.chat_scroll) {
+            let files = collect(&msg.tools);
+            if !files.is_empty() {
+                return files;
+            }
+        }
+        let current = collect(&self.tools);
+        if !current.is_empty() {
+            return current;
+        }
+        self.messages
+            .iter()
+            .rev()
+            .map(|m| collect(&m.tools))
+            .find(|files| !files.is_empty())
+            .unwrap_or_default()
+    }
+
+    /// Records how a gate was answered, in the transcript and the gate log.
+    fn log_gate(&mut self, gate: gate::GatePrompt, action: &str) {
+        self.push_system(
+            gate_role(action),
+            format!("Gate {}: {}", gate.id, gate_outcome(action)),
+        );
+        self.gate_log.push(export::GateDecision {
+            id: gate.id,
+            action: action.to_string(),
+            blocked: gate.blocked,
+            warnings: gate
+                .warnings
+                .iter()
+                .map(|w| format!("{}: {}", w.code, w.subject))
+                .collect(),
+            timestamp: waterfall::now_ms(),
+        });
+    }
+
+    fn new_id(&mut self) -> String {
+        self.next_id += 1;
+        format!("local-{}", self.next_id)
+    }
+
+    /// A turn is in flight, so the transcript tail is still changing.
+    fn turn_active(&self) -> bool {
+        matches!(
+            self.stream_state.as_str(),
+            "request_sent"
+                | "thinking"
+                | "awaiting_first_token"
+                | "streaming"
+                | "tool_executing"
+                | "waiting"
+        )
+    }
+
+    fn follow(&mut self, config: &Config) {
+        if config.behavior.auto_follow {
+            self.select(usize::MAX);
+        }
+    }
+
+    fn select(&mut self, index: usize) {
+        let last = self.messages.len().saturating_sub(1);
+        self.chat_scroll = index.min(last);
+        self.follow_tail = self.chat_scroll == last;
+        self.reveal = true;
+    }
+
+    /// Scrolls the chat by lines and selects the message at the top.
+    fn scroll_lines(&mut self, delta: isize) {
+        self.chat_offset = self.chat_offset.saturating_add_signed(delta);
+        self.follow_tail = false;
+        self.reveal = false;
+        let top = self
+            .message_lines
+            .iter()
+            .rposition(|&start| start <= self.chat_offset)
+            .unwrap_or(0);
+        self.chat_scroll = top.min(self.messages.len().saturating_sub(1));
+    }
+
+    /// Diffs proposed by the tool calls of the turn in progress, which is
+    /// what a pending gate is asking about.
+    fn pending_diffs(&self) -> Vec<(String, Vec<diff::FileDiff>)> {
+        self.tools
+            .iter()
+            .filter(|t| !t.diff.is_empty())
+            .map(|t| (t.id.clone(), t.diff.clone()))
+            .collect()
+    }
+}
+
+/// Resends an edited user message. The transcript from that message on is
+/// kept as a branch and the host is asked to fork the conversation there.
+fn resend(state: &mut AppState, id: &str, content: String) {
+    let Some(index) = state.messages.iter().position(|m| m.id == id) else {
+        // The message was on a branch that is no longer shown; sending the
+        // edit as a new turn would drop the fork point, so keep the draft.
+        state.input = content;
+        state.editing = Some(id.to_string());
+        state.notice = Some("message is on a hidden branch".to_string());
+        return;
+    };
+    if state.turn_active() {
+        state.input = content;
+        state.editing = Some(id.to_string());
+        state.notice = Some("wait for the turn to finish".to_string());
+        return;
+    }
+    emit(
+        state.session.as_deref(),
+        serde_json::json!({
+            "type": "fork_from",
+            "message_id": id,
+            "content": content,
+        }),
+    );
+    let edited = Message {
+        id: state.new_id(),
+        role: Role::User,
+        content,
+        timestamp: waterfall::now_ms(),
+        ..Default::default()
+    };
+    state.branches.fork(&mut state.messages, index, edited);
+    state.select(usize::MAX);
+}
+
+/// Shows a sibling of the nearest branch at or above the selection and
+/// tells the host which one the conversation continues from.
+fn flip_branch(state: &mut AppState, forward: bool) {
+    if state.turn_active() {
+        state.notice = Some("wait for the turn to finish".to_string());
+        return;
+    }
+    let end = state
+        .chat_scroll
+        .min(state.messages.len().saturating_sub(1));
+    let fork = state.messages.get(..=end).and_then(|messages| {
+        messages.iter().rposition(|m| {
+            m.fork
+                .as_deref()
+                .is_some_and(|key| state.branches.indicator(key).is_some())
+        })
+    });
+    let Some(index) = fork else {
+        state.notice = Some("no other branches here".to_string());
+        return;
+    };
+    if let Some(id) = state.branches.flip(&mut state.messages, index, forward) {
+        emit(
+            state.session.as_deref(),
+            serde_json::json!({ "type": "select_branch", "message_id": id }),
+        );
+    }
+    state.select(index);
+}
+
+/// Sends a comment on a plan step to the host and keeps it under the step.
+fn send_plan_feedback(state: &mut AppState, outcome: plan::PlanOutcome) {
+    let plan::PlanOutcome::Comment { step_id, text } = outcome else {
+        return;
+    };
+    emit(
+        state.session.as_deref(),
+        serde_json::json!({
+            "type": "plan_feedback",
+            "step_id": step_id,
+            "comment": text,
+        }),
+    );
+    state.push_system(
+        Role::System,
+        format!("Feedback on step {}: {}", step_id, text),
+    );
+    if let Some(plan) = &mut state.plan {
+        plan.comment(&step_id, text);
+    }
+}
+
+/// Value of `--name value` or `--name=value` on the command line.
+fn flag_value(name: &str) -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    let prefix = format!("--{}=", name);
+    while let Some(arg) = args.next() {
+        if let Some(value) = arg.strip_prefix(&prefix) {
+            return Some(value.to_string());
+        }
+        if arg == format!("--{}", name) {
+            return args.next();
+        }
+    }
+    None
+}
+
+/// Handles `/theme`, `/thinking <mode>` and `/export` locally. Returns
+/// false for anything else so it is forwarded to the host.
+fn local_command(
+    input: &str,
+    themes: &mut theme::ThemeManager,
+    state: &mut AppState,
+    config: &Config,
+) -> bool {
+    let mut parts = input.split_whitespace();
+    match parts.next() {
+        Some("/theme") => theme_command(parts.next(), themes, state),
+        Some("/export") => {
+            let args: Vec<&str> = parts.collect();
+            export_command(&args.join(" "), themes.theme(), state, config)
+        }
+        Some("/thinking") => match parts.next().and_then(thinking::Mode::parse) {
+            Some(mode) => set_thinking_mode(state, mode),
+            // `show`, `reset` and the bare command are the host's.
+            None => return false,
+        },
+        _ => return false,
+    }
+    true
+}
+
+/// Switches how reasoning is shown and tells the host, which decides how
+/// much it sends.
+fn set_thinking_mode(state: &mut AppState, mode: thinking::Mode) {
+    state.thinking_mode = mode;
+    state.notice = Some(format!("thinking: {}", mode.name()));
+    emit(
+        state.session.as_deref(),
+        serde_json::json!({
+            "type": "thinking_mode",
+            "mode": mode.name(),
+        }),
+    );
+}
+
+fn export_command(args: &str, theme: &Theme, state: &mut AppState, config: &Config) {
+    let result = export::Options::parse(args, &config.export).and_then(|options| {
+        export::export(
+            state,
+            state.session.clone(),
+            &options,
+            &config.export,
+            theme,
+        )
+        .map_err(|e| e.to_string())
+    });
+    match result {
+        Ok((path, count)) => {
+            let path = path.display().to_string();
+            state.notice = Some(format!("exported to {}", path));
+            state.push_system(
+                Role::System,
+                format!("Exported {} messages to {}", count, path),
+            );
+        }
+        Err(e) => state.push_system(Role::Error, format!("Export failed: {}", e)),
+    }
+    state.follow(config);
+}
+
+fn theme_command(name: Option<&str>, themes: &mut theme::ThemeManager, state: &mut AppState) {
+    match name {
+        Some(name) => {
+            if let Err(e) = themes.select(name) {
+                state.push_system(Role::Error, format!("Theme: {}", e));
+            }
+        }
+        None => state.push_system(
+            Role::System,
+            format!(
+                "Theme: {} ({}). Available: {}",
+                themes.name,
+                themes.depth.label(),
+                themes.available().join(", ")
+            ),
+        ),
+    }
+}
+
+/// Routes a key press through the keymap for the contexts that are active.
+/// Returns true when the app should exit.
+fn handle_key(
+    key: KeyEvent,
+    state: &mut AppState,
+    themes: &mut theme::ThemeManager,
+    config: &mut Config,
+) -> bool {
+    state.notice = None;
+    match &mut state.overlay {
+        Some(Overlay::Review(view)) if view.is_editing() => {
+            view.edit_key(key);
+            return false;
+        }
+        Some(Overlay::Help(view)) if view.is_searching() => {
+            view.search_key(key);
+            return false;
+        }
+        Some(Overlay::Policy(view)) if view.is_editing() => {
+            if let Some(pm) = &state.policy.state {
+                view.edit_key(key, pm);
+            }
+            return false;
+        }
+        Some(Overlay::Plan(view)) if view.is_editing() => {
+            if let Some(plan) = &mut state.plan {
+                let outcome = view.edit_key(key, plan);
+                send_plan_feedback(state, outcome);
+            }
+            return false;
+        }
+        None if state.sidebar_focus && state.context.is_filtering() => {
+            state.context.filter_key(key);
+            return false;
+        }
+        _ => {}
+    }
+    if state.overlay.is_none() && matches!(state.mode, vim::Mode::Search | vim::Mode::Command) {
+        return prompt_key(key, state, themes, config);
+    }
+    let contexts: &[Context] = match state.overlay {
+        Some(Overlay::Diff(_)) => &[Context::Diff, Context::Global],
+        Some(Overlay::Review(_)) => &[Context::Review, Context::Global],
+        Some(Overlay::Gate) => &[Context::Gate, Context::Global],
+        Some(Overlay::Diagnostics) => &[Context::Global],
+        Some(Overlay::Help(_)) => &[Context::Help, Context::Global],
+        Some(Overlay::Waterfall(_)) => &[Context::Waterfall, Context::Global],
+        Some(Overlay::Preview(_)) => &[Context::Preview, Context::Global],
+        Some(Overlay::Policy(_)) => &[Context::Policy, Context::Global],
+        Some(Overlay::Rao(_)) => &[Context::Rao, Context::Global],
+        Some(Overlay::Plan(_)) => &[Context::Plan, Context::Global],
+        None if state.sidebar_focus => &[Context::Sidebar, Context::Global],
+        None if state.error.is_some() && state.mode == vim::Mode::Normal => &[
+            Context::Error,
+            Context::Normal,
+            Context::Chat,
+            Context::Global,
+        ],
+        None if state.error.is_some() => &[
+            Context::Error,
+            Context::Input,
+            Context::Chat,
+            Context::Global,
+        ],
+        None if state.mode == vim::Mode::Normal => {
+            &[Context::Normal, Context::Chat, Context::Global]
+        }
+        None => &[Context::Input, Context::Chat, Context::Global],
+    };
+    match config.keymap.resolve(contexts, key) {
+        Resolved::Action(action) => run_action(action, state, themes, config),
+        Resolved::Pending => false,
+        Resolved::Unbound(key) => {
+            state.armed = None;
+            match (&state.overlay, key.code) {
+                (Some(Overlay::Diagnostics), _) => state.overlay = None,
+                // `?` on an empty prompt opens help rather than starting a message.
+                (None, KeyCode::Char('?'))
+                    if state.mode == vim::Mode::Insert && state.input.is_empty() =>
+                {
+                    state.overlay = Some(Overlay::Help(help::HelpView::default()))
+                }
+                (None, KeyCode::Char(c))
+                    if state.mode == vim::Mode::Insert
+                        && !key.modifiers.contains(KeyModifiers::CONTROL) =>
+                {
+                    state.input.push(c)
+                }
+                _ => {}
+            }
+            false
+        }
+    }
+}
+
+/// Line editing for the `/` search and `:` command prompts.
+fn prompt_key(
+    key: KeyEvent,
+    state: &mut AppState,
+    themes: &mut theme::ThemeManager,
+    config: &Config,
+) -> bool {
+    match key.code {
+        KeyCode::Esc => {
+            state.prompt.clear();
+            state.mode = vim::Mode::Normal;
+        }
+        KeyCode::Backspace if state.prompt.is_empty() => state.mode = vim::Mode::Normal,
+        KeyCode::Backspace => {
+            state.prompt.pop();
+        }
+        KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => state.prompt.push(c),
+        KeyCode::Enter => {
+            let text = std::mem::take(&mut state.prompt);
+            let mode = std::mem::replace(&mut state.mode, vim::Mode::Normal);
+            if mode == vim::Mode::Search {
+                if !text.is_empty() {
+                    state.search = Some(text);
+                }
+                search_step(state, true);
+                return false;
+            }
+            match vim::parse_command(&text) {
+                Some(vim::Command::Quit) => return true,
+                Some(vim::Command::Action(action)) => {
+                    return run_action(action, state, themes, config)
+                }
+                Some(vim::Command::Goto(index)) => state.select(index),
+                Some(vim::Command::Local(input)) => {
+                    local_command(&input, themes, state, config);
+                }
+                Some(vim::Command::Host(content)) => emit(
+                    state.session.as_deref(),
+                    serde_json::json!({
+                        "type": "input",
+                        "content": content
+                    }),
+                ),
+                None => {}
+            }
+        }
+        _ => {}
+    }
+    false
+}
+
+fn search_step(state: &mut AppState, forward: bool) {
+    let Some(query) = state.search.clone() else {
+        return;
+    };
+    match vim::search(&state.messages, state.chat_scroll, &query, forward) {
+        Some(index) => state.select(index),
+        None => state.notice = Some(format!("Pattern not found: {}", query)),
+    }
+}
+
+fn run_action(
+    action: Action,
+    state: &mut AppState,
+    themes: &mut theme::ThemeManager,
+    config: &Config,
+) -> bool {
+    let armed = state.armed.take();
+    match (&mut state.overlay, action.context()) {
+        (Some(Overlay::Diff(view)), Context::Diff) => {
+            if !view.handle_action(action) {
+                state.overlay = None;
+            }
+            return false;
+        }
+        (Some(Overlay::Review(view)), Context::Review) => {
+            match view.handle_action(action) {
+                review::ReviewOutcome::Continue => {}
+                review::ReviewOutcome::Cancel => state.overlay = None,
+                review::ReviewOutcome::Submit(result) => {
+                    let summary = view.summary();
+                    emit(state.session.as_deref(), result);
+                    // The gate stays up, marked submitted, until the host answers.
+                    state.overlay = match &mut state.gate {
+                        Some(gate) => {
+                            gate.submitted = Some(summary.clone());
+                            Some(Overlay::Gate)
+                        }
+                        None => None,
+                    };
+                    state.push_system(Role::Notice, format!("Review sent: {}", summary));
+                }
+            }
+            return false;
+        }
+        (Some(Overlay::Help(view)), Context::Help) => {
+            if !view.handle_action(action) {
+                state.overlay = None;
+            }
+            return false;
+        }
+        (Some(Overlay::Waterfall(view)), Context::Waterfall) => {
+            if !view.handle_action(action, &state.waterfall) {
+                state.overlay = None;
+            }
+            return false;
+        }
+        (Some(Overlay::Gate), Context::Gate) => {
+            match state.gate.as_ref().and_then(|g| g.handle_action(action)) {
+                Some(gate::GateChoice::Resolve(choice)) => {
+                    if let Some(gate) = state.gate.take() {
+                        emit(
+                            state.session.as_deref(),
+                            serde_json::json!({
+                                "type": "gate_resolution",
+                                "gate_id": gate.id,
+                                "action": choice,
+                            }),
+                        );
+                        state.log_gate(gate, choice);
+                    }
+                    state.overlay = None;
+                }
+                Some(gate::GateChoice::Review) => {
+                    let gate_id = state.gate.as_ref().map(|g| g.id.clone());
+                    state.overlay = Some(Overlay::Review(review::ReviewView::new(
+                        state.pending_diffs(),
+                        gate_id,
+                    )));
+                }
+                Some(gate::GateChoice::Dismiss) => state.overlay = None,
+                None => {}
+            }
+            return false;
+        }
+        (Some(Overlay::Preview(view)), Context::Preview) => {
+            if !view.handle_action(action) {
+                state.overlay = None;
+            }
+            return false;
+        }
+        (Some(Overlay::Policy(_)), Context::Policy) if state.policy.state.is_none() => {
+            if action == Action::PolicyClose {
+                state.overlay = None;
+            }
+            return false;
+        }
+        (Some(Overlay::Policy(view)), Context::Policy) => {
+            let Some(pm) = &state.policy.state else {
+                return false;
+            };
+            match view.handle_action(action, pm) {
+                pm::PolicyOutcome::Continue => {}
+                pm::PolicyOutcome::Close => state.overlay = None,
+                pm::PolicyOutcome::Send(cmd) => {
+                    emit(state.session.as_deref(), cmd.payload);
+                    state.notice = Some(format!("sent: {}", cmd.description));
+                }
+            }
+            return false;
+        }
+        (Some(Overlay::Rao(view)), Context::Rao) => {
+            match view.handle_action(action, &state.rao_history) {
+                rao::RaoOutcome::Continue => {}
+                rao::RaoOutcome::Close => state.overlay = None,
+                rao::RaoOutcome::Export(format) => {
+                    let count = view.visible(&state.rao_history).len();
+                    match view.export(&state.rao_history, format) {
+                        Ok(path) => {
+                            let path = path.display().to_string();
+                            state.notice = Some(format!("exported to {}", path));
+                            state.push_system(
+                                Role::System,
+                                format!("Exported {} RAO snapshots to {}", count, path),
+                            );
+                        }
+                        Err(e) => state
+                            .push_system(Role::Error, format!("RAO history export failed: {}", e)),
+                    }
+                }
+            }
+            return false;
+        }
+        (Some(Overlay::Plan(view)), Context::Plan) => {
+            match &state.plan {
+                Some(plan) => match view.handle_action(action, plan) {
+                    plan::PlanOutcome::Close => state.overlay = None,
+                    outcome => send_plan_feedback(state, outcome),
+                },
+                None if action == Action::PlanClose => state.overlay = None,
+                None => {}
+            }
+            return false;
+        }
+        (None, Context::Sidebar) if action == Action::SidebarClose => {
+            state.sidebar_focus = false;
+            return false;
+        }
+        (None, Context::Sidebar) => {
+            match state.context.handle_action(action, &state.context_files) {
+                Some(context::ContextCommand::Remove(path)) => {
+                    emit(
+                        state.session.as_deref(),
+                        serde_json::json!({
+                            "type": "remove_context_file",
+                            "path": path,
+                        }),
+                    );
+                    state.context_files.retain(|f| *f != path);
+                    state.context.set_files(&state.context_files);
+                    state.push_system(Role::System, format!("Removed {} from context", path));
+                }
+                Some(context::ContextCommand::Preview(path)) => {
+                    state.overlay = Some(Overlay::Preview(context::Preview::open(&path)));
+                }
+                None => {}
+            }
+            return false;
+        }
+        (None, Context::Error) => {
+            let Some(error) = &state.error else {
+                return false;
+            };
+            if let Some(choice) = error.handle_action(action) {
+                emit(state.session.as_deref(), error.command(choice));
+                state.notice = match choice {
+                    errors::ErrorChoice::Retry => Some("retrying…".to_string()),
+                    errors::ErrorChoice::Fallback => Some("switching provider…".to_string()),
+                    errors::ErrorChoice::Dismiss => None,
+                };
+                state.error = None;
+            }
+            return false;
+        }
+        _ => {}
+    }
+
+    let last = state.messages.len().saturating_sub(1);
+    match action {
+        Action::Quit if config.behavior.confirm_quit && armed != Some(action) => {
+            state.armed = Some(action)
+        }
+        Action::Quit => return true,
+        Action::OpenDiff => {
+            let files: Vec<diff::FileDiff> = state
+                .diff_for_selection()
+                .into_iter()
+                .flat_map(|(_, files)| files)
+                .collect();
+            if !files.is_empty() {
+                state.overlay = Some(Overlay::Diff(diff::DiffView::new(files)));
+            }
+        }
+        Action::OpenReview if state.gate.as_ref().is_some_and(|g| g.submitted.is_some()) => {
+            state.overlay = Some(Overlay::Gate)
+        }
+        Action::OpenReview => {
+            let gate_id = state.gate.as_ref().map(|g| g.id.clone());
+            let tools = if gate_id.is_some() {
+                state.pending_diffs()
+            } else {
+                state.diff_for_selection()
+            };
+            let view = review::ReviewView::new(tools, gate_id);
+            if !view.is_empty() {
+                state.overlay = Some(Overlay::Review(view));
+            }
+        }
+        // Closing forgets the saved transcript, so it takes a second press.
+        Action::CloseSession if armed != Some(action) => state.armed = Some(action),
+        Action::NextSession | Action::PrevSession | Action::NewSession | Action::CloseSession => {
+            state.tab_action = Some(action)
+        }
+        Action::ShowGate if state.gate.is_some() => state.overlay = Some(Overlay::Gate),
+        Action::ShowDiagnostics => state.overlay = Some(Overlay::Diagnostics),
+        Action::ToggleSidebar => state.drawer_open = !state.drawer_open,
+        Action::FocusContext if config.layout.panes.iter().any(|p| p == "files") => {
+            state.sidebar_focus = !state.sidebar_focus;
+            // On narrow terminals the files are only visible in the drawer.
+            if state.sidebar_focus {
+                state.drawer_open = true;
+            }
+        }
+        Action::CycleThinking => set_thinking_mode(state, state.thinking_mode.next()),
+        Action::ToggleThinking => {
+            let default = state.thinking_mode == thinking::Mode::Verbose;
+            // While reasoning streams at the tail it is the block in view.
+            if !state.current_thinking.is_empty() && state.follow_tail {
+                state.thinking_expanded = Some(!state.thinking_expanded.unwrap_or(default));
+            } else if let Some(msg) = state.messages.get_mut(state.chat_scroll) {
+                msg.thinking_expanded = Some(!msg.thinking_expanded.unwrap_or(default));
+            }
+        }
+        Action::OpenWaterfall => {
+            state.overlay = Some(Overlay::Waterfall(waterfall::WaterfallView::new()))
+        }
+        Action::OpenPolicy => state.overlay = Some(Overlay::Policy(pm::PolicyView::new())),
+        Action::OpenRaoHistory => state.overlay = Some(Overlay::Rao(rao::HistoryView::new())),
+        Action::OpenPlan => {
+            state.overlay = Some(Overlay::Plan(plan::PlanView::new(state.plan.as_ref())))
+        }
+        Action::ShowHelp | Action::NormalHelp => {
+            state.overlay = Some(Overlay::Help(help::HelpView::default()))
+        }
+        Action::ScrollUp | Action::NormalUp => state.select(state.chat_scroll.saturating_sub(1)),
+        Action::ScrollDown | Action::NormalDown => state.select(state.chat_scroll + 1),
+        Action::PageUp => state.select(state.chat_scroll.saturating_sub(10)),
+        Action::PageDown => state.select(state.chat_scroll + 10),
+        Action::ScrollTop | Action::NormalTop => state.select(0),
+        Action::ScrollBottom | Action::NormalBottom => state.select(last),
+        Action::HalfPageDown => state.scroll_lines((state.chat_height / 2).max(1) as isize),
+        Action::HalfPageUp => state.scroll_lines(-((state.chat_height / 2).max(1) as isize)),
+        Action::NextTool | Action::PrevTool | Action::NextError | Action::PrevError => {
+            let forward = matches!(action, Action::NextTool | Action::NextError);
+            let found = if matches!(action, Action::NextTool | Action::PrevTool) {
+                vim::find(&state.messages, state.chat_scroll, forward, vim::has_tools)
+            } else {
+                vim::find(&state.messages, state.chat_scroll, forward, vim::has_error)
+            };
+            match found {
+                Some(index) => state.select(index),
+                None => state.notice = Some("No more matches".to_string()),
+            }
+        }
+        Action::Yank => {
+            if let Some(msg) = state.messages.get(state.chat_scroll) {
+                state.notice = Some(match copy_to_clipboard(&msg.content) {
+                    Ok(()) => format!("Yanked {} chars", msg.content.chars().count()),
+                    Err(e) => format!("Couldn't yank: {}", e),
+                });
+            }
+        }
+        Action::Search => state.mode = vim::Mode::Search,
+        Action::SearchNext => search_step(state, true),
+        Action::SearchPrev => search_step(state, false),
+        Action::InsertMode => state.mode = vim::Mode::Insert,
+        Action::CommandMode => state.mode = vim::Mode::Command,
+        Action::EditMessage => match state.messages.get(state.chat_scroll) {
+            Some(msg) if msg.role == Role::User && !state.turn_active() => {
+                state.input = msg.content.clone();
+                state.editing = Some(msg.id.clone());
+                if config.behavior.vim_mode {
+                    state.mode = vim::Mode::Insert;
+                }
+            }
+            Some(msg) if msg.role == Role::User => {
+                state.notice = Some("wait for the turn to finish".to_string())
+            }
+            _ => state.notice = Some("select one of your messages to edit".to_string()),
+        },
+        Action::PrevBranch => flip_branch(state, false),
+        Action::NextBranch => flip_branch(state, true),
+        Action::NormalMode if state.editing.is_some() => {
+            state.editing = None;
+            state.input.clear();
+            if config.behavior.vim_mode {
+                state.mode = vim::Mode::Normal;
+            }
+        }
+        Action::NormalMode if config.behavior.vim_mode => state.mode = vim::Mode::Normal,
+        Action::Submit if !state.input.is_empty() => {
+            let input = std::mem::take(&mut state.input);
+            if let Some(id) = state.editing.take() {
+                resend(state, &id, input);
+            } else if !local_command(&input, themes, state, config) {
+                emit(
+                    state.session.as_deref(),
+                    serde_json::json!({
+                        "type": "input",
+                        "content": input
+                    }),
+                );
+            }
+        }
+        Action::DeleteBack => {
+            state.input.pop();
+        }
+        _ => {}
+    }
+    false
+}
+
+fn model_label(provider: &Option<String>, model: &Option<String>) -> Option<String> {
+    match (provider, model) {
+        (Some(p), Some(m)) => Some(format!("{}/{}", p, m)),
+        (Some(x), None) | (None, Some(x)) => Some(x.clone()),
+        (None, None) => None,
+    }
+}
+
+fn gate_outcome(action: &str) -> &str {
+    match action {
+        "approve_once" => "approved once",
+        "always_allow_tool" => "tool always allowed",
+        "always_allow_path" => "path always allowed",
+        "reject" => "rejected",
+        other => other,
+    }
+}
+
+fn gate_role(action: &str) -> Role {
+    if action == "reject" {
+        Role::Warning
+    } else {
+        Role::Notice
+    }
+}
+
+/// `+a, +b, -c` for what was added and removed, or `cleared`.
+fn scope_change(before: &[String], after: &[String]) -> Option<String> {
+    if before == after {
+        return None;
+    }
+    if after.is_empty() {
+        return Some("cleared".to_string());
+    }
+    let added = after.iter().filter(|x| !before.contains(x));
+    let removed = before.iter().filter(|x| !after.contains(x));
+    let mut parts: Vec<String> = added
+        .map(|x| format!("+{}", x))
+        .chain(removed.map(|x| format!("-{}", x)))
+        .collect();
+    if parts.is_empty() {
+        return None;
+    }
+    if parts.len() > 6 {
+        let more = parts.len() - 5;
+        parts.truncate(5);
+        parts.push(format!("… {} more", more));
+    }
+    Some(parts.join(", "))
+}
+
+/// Sends `msg` to the host, tagged with the session it came from.
+fn emit(session: Option<&str>, mut msg: serde_json::Value) {
+    if let (Some(fields), Some(session)) = (msg.as_object_mut(), session) {
+        fields.entry("session").or_insert(session.into());
+    }
+    println!("{}", msg);
+}
+
+/// Puts `text` on the system clipboard with an OSC 52 sequence. It goes to
+/// the controlling terminal rather than stdout, which may be the host's
+/// pipe; inside tmux it is wrapped so tmux passes it on.
+fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    use base64::Engine as _;
+    use std::io::Write as _;
+
+    let mut sequence = format!(
+        "\x1b]52;c;{}\x07",
+        base64::engine::general_purpose::STANDARD.encode(text)
+    );
+    if std::env::var_os("TMUX").is_some() {
+        sequence = format!("\x1bPtmux;\x1b{}\x1b\\", sequence);
+    }
+    let mut tty = std::fs::OpenOptions::new().write(true).open("/dev/tty")?;
+    tty.write_all(sequence.as_bytes())?;
+    tty.flush()
+}
+
+/// Blanks `area` for an overlay, keeping the theme background.
+fn clear_area(frame: &mut Frame, area: Rect, theme: &Theme) {
+    frame.render_widget(Clear, area);
+    frame.render_widget(Block::default().style(Style::default().bg(theme.bg)), area);
+}
+
+fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+/// Applies a host message to one session's state.
+fn apply_message(state: &mut AppState, message: TuiMessage, config: &Config) {
+    match message {
+        TuiMessage::Dispatch { event, timestamp } => {
+            state
+                .waterfall
+                .record(timestamp.unwrap_or_else(waterfall::now_ms), &event);
+            match event {
+                StreamEvent::State { state: s } => state.stream_state = s,
+                StreamEvent::TextDelta { text } => {
+                    state.usage.token();
+                    state.current_stream.push_str(&text);
+                }
+                StreamEvent::ThinkingDelta { text } => {
+                    state.usage.token();
+                    state.current_thinking.push_str(&text);
+                }
+                StreamEvent::ToolCall {
+                    name,
+                    id,
+                    arguments,
+                    diff,
+                } => {
+                    state.current_tool = name.clone();
+                    if let (Some(name), Some(id)) = (name, id) {
+                        state.tools.push(ToolState {
+                            name,
+                            id,
+                            status: "running".to_string(),
+                            arguments,
+                            output: None,
+                            elapsed_ms: None,
+                            diff: diff.as_deref().map(diff::parse).unwrap_or_default(),
+                        });
+                    }
+                }
+                StreamEvent::ToolResult {
+                    tool_id,
+                    success,
+                    output,
+                    elapsed_ms,
+                    diff,
+                } => {
+                    state.current_tool = None;
+                    for tool in &mut state.tools {
+                        if tool.id == tool_id.clone().unwrap_or_default() {
+                            tool.status = if success.unwrap_or(false) {
+                                "success".to_string()
+                            } else {
+                                "error".to_string()
+                            };
+                            let result_diff = diff
+                                .as_deref()
+                                .or(output.as_deref())
+                                .map(diff::parse)
+                                .unwrap_or_default();
+                            if !result_diff.is_empty() {
+                                tool.diff = result_diff;
+                            }
+                            tool.output = output;
+                            tool.elapsed_ms = elapsed_ms;
+                            break;
+                        }
+                    }
+                }
+                StreamEvent::Complete {}
+                    if !state.current_stream.is_empty()
+                        || !state.tools.is_empty()
+                        || !state.current_thinking.is_empty() =>
+                {
+                    state.elapsed_ms = state.usage.finish();
+                    state.timeline.finish();
+                    state.finish_reply();
+                    state.stream_state = "idle".to_string();
+                    state.follow(config);
+                }
+                StreamEvent::Complete {} => {
+                    state.elapsed_ms = state.usage.finish();
+                    state.timeline.finish();
+                }
+                StreamEvent::Phase { phase } => state.timeline.enter(&phase),
+                StreamEvent::Step { phase, step } => state.timeline.step(phase.as_deref(), &step),
+                StreamEvent::Plan { title, steps } => {
+                    state.plan = Some(plan::Plan::new(title, steps))
+                }
+                StreamEvent::PlanUpdate { id, status, title } => {
+                    if let Some(plan) = &mut state.plan {
+                        plan.update(&id, status, title);
+                    }
+                }
+                StreamEvent::Usage {
+                    input_tokens,
+                    output_tokens,
+                    cached_tokens,
+                    cost_usd,
+                    context_tokens,
+                    context_window,
+                } => state.usage.record(
+                    usage::Usage {
+                        input: input_tokens.unwrap_or(0),
+                        output: output_tokens.unwrap_or(0),
+                        cached: cached_tokens.unwrap_or(0),
+                        cost: cost_usd.unwrap_or(0.0),
+                    },
+                    context_tokens,
+                    context_window,
+                ),
+                StreamEvent::Meta { provider, model } => {
+                    let before = model_label(&state.provider, &state.model);
+                    let after = model_label(&provider, &model);
+                    if let (Some(before), Some(after)) = (&before, &after) {
+                        if before != after {
+                            state.push_system(
+                                Role::Notice,
+                                format!("Model switched: {} → {}", before, after),
+                            );
+                        }
+                    }
+                    state.provider = provider;
+                    state.model = model;
+                }
+                StreamEvent::Error {
+                    message,
+                    code,
+                    recoverable,
+                } => {
+                    state.elapsed_ms = state.usage.finish();
+                    state.timeline.finish();
+                    state.stream_state = "error".to_string();
+                    // Keep what streamed before the failure; tools still
+                    // running will not report back.
+                    for tool in &mut state.tools {
+                        if tool.status == "running" {
+                            tool.status = "error".to_string();
+                        }
+                    }
+                    if !state.current_stream.is_empty()
+                        || !state.tools.is_empty()
+                        || !state.current_thinking.is_empty()
+                    {
+                        state.finish_reply();
+                    }
+                    let error = errors::ErrorBanner {
+                        code,
+                        message: message.unwrap_or_else(|| "Unknown error".to_string()),
+                        recoverable: recoverable.unwrap_or(false),
+                    };
+                    state.push_system(
+                        Role::Error,
+                        format!("{} — {}", error.summary(), error.suggestion()),
+                    );
+                    state.error = Some(error);
+                    state.follow(config);
+                }
+                StreamEvent::Gate {
+                    id,
+                    blocked,
+                    warnings,
+                } => {
+                    state.stream_state = "waiting".to_string();
+                    state.gate = Some(gate::GatePrompt {
+                        id: id.unwrap_or_default(),
+                        blocked: blocked.unwrap_or(false),
+                        warnings: warnings.unwrap_or_default(),
+                        reviewable: !state.pending_diffs().is_empty(),
+                        submitted: None,
+                    });
+                    state.overlay = Some(Overlay::Gate);
+                }
+                StreamEvent::GateResolved { action } => {
+                    // Gates resolved here were logged when the key was pressed.
+                    if let Some(gate) = state.gate.take() {
+                        state.log_gate(gate, action.as_deref().unwrap_or("resolved"));
+                    }
+                    if matches!(state.overlay, Some(Overlay::Gate | Overlay::Review(_))) {
+                        state.overlay = None;
+                    }
+                }
+            }
+        }
+        TuiMessage::AddUserMessage { content, id } => {
+            // A resent message is already shown; take the host's id.
+            let resent = state.branches.take_pending();
+            match state
+                .messages
+                .last_mut()
+                .filter(|m| Some(&m.id) == resent.as_ref() && m.content == content)
+            {
+                Some(msg) => {
+                    if let Some(id) = id {
+                        msg.id = id;
+                    }
+                }
+                None => {
+                    let id = id.unwrap_or_else(|| state.new_id());
+                    state.messages.push(Message {
+                        id,
+                        role: Role::User,
+                        content,
+                        timestamp: waterfall::now_ms(),
+                        ..Default::default()
+                    });
+                }
+            }
+            state.current_stream.clear();
+            state.current_thinking.clear();
+            state.thinking_expanded = None;
+            state.error = None;
+            state.stream_state = "thinking".to_string();
+            state.usage.start_turn();
+            state.timeline.reset();
+            state.waterfall.begin(waterfall::now_ms());
+            state.follow(config);
+        }
+        TuiMessage::SetContext { files, scope } => {
+            if let Some(change) = scope_change(&state.context_scope, &scope) {
+                state.push_system(Role::Notice, format!("Scope {}", change));
+            }
+            if let Some(change) = scope_change(&state.context_files, &files) {
+                state.push_system(Role::System, format!("Context files {}", change));
+            }
+            state.context.set_files(&files);
+            state.context_files = files;
+            state.context_scope = scope;
+        }
+        TuiMessage::ScopeStatus(status) => {
+            let was_within = state.scope_status.as_ref().is_none_or(|s| s.within_limits);
+            if was_within && !status.within_limits {
+                state.push_system(
+                    Role::Error,
+                    format!("Scope limit exceeded: {}", status.warnings.join("; ")),
+                );
+                state.follow(config);
+            } else if !was_within && status.within_limits {
+                state.push_system(Role::Notice, "Scope back within limits".to_string());
+            }
+            state.scope_status = Some(status);
+        }
+        TuiMessage::RaoHistory { history } => state.rao_history = history,
+        TuiMessage::PmState(pm) => {
+            for change in state.policy.update(pm) {
+                state.push_system(Role::Notice, change);
+            }
+            state.follow(config);
+        }
+        TuiMessage::AddSystemMessage { content, role } => {
+            state.push_system(role, content);
+            state.follow(config);
+        }
+        TuiMessage::UpdateState { state: s } => {
+            state.stream_state = s;
+        }
+        TuiMessage::SetCommands { commands } => state.commands = commands,
+        TuiMessage::Snapshot {
+            messages,
+            state: stream_state,
+        } => {
+            store::reconcile(state, messages);
+            if let Some(s) = stream_state {
+                state.stream_state = s;
+            }
+        }
+        TuiMessage::Destroy => {}
+    }
+}
+
+/// A fresh session tab as the config sets it up.
+fn new_state(config: &Config) -> AppState {
+    AppState {
+        stream_state: "done".to_string(),
+        follow_tail: true,
+        mode: if config.behavior.vim_mode {
+            vim::Mode::Normal
+        } else {
+            vim::Mode::default()
+        },
+        thinking_mode: config.behavior.thinking,
+        ..Default::default()
+    }
+}
+
+/// Switches, opens or closes session tabs. New sessions are started by
+/// the host; their tab appears with the first message for them.
+fn session_action(tabs: &mut tabs::Tabs, store: &mut store::Store, action: Action) {
+    match action {
+        Action::NextSession => tabs.cycle(true),
+        Action::PrevSession => tabs.cycle(false),
+        Action::NewSession if !tabs.multi() => {
+            tabs.current_mut().notice = Some("this host runs a single session".to_string());
+        }
+        Action::NewSession => {
+            emit(
+                tabs.current().session.as_deref(),
+                serde_json::json!({ "type": "new_session" }),
+            );
+            tabs.open();
+            tabs.current_mut().notice = Some("starting a new session…".to_string());
+        }
+        Action::CloseSession => match tabs.close() {
+            Ok(session) => {
+                store.forget(session.as_deref());
+                emit(
+                    session.as_deref(),
+                    serde_json::json!({ "type": "close_session" }),
+                );
+            }
+            Err(e) => tabs.current_mut().notice = Some(e.to_string()),
+        },
+        _ => {}
+    }
 }
 
 fn main() -> io::Result<()> {
+    if std::env::args().nth(1).as_deref() == Some("policy-sim") {
+        std::process::exit(policy_sim::run());
+    }
+
     // Check if we have a TTY, but try anyway if it's a pseudo-TTY (works in most IDEs)
     let allow_pipe = std::env::var("DAX_TUI_ALLOW_PIPE").unwrap_or_default() == "1";
     if !allow_pipe && !atty::is(atty::Stream::Stdin) && !atty::is(atty::Stream::Stdout) {
         eprintln!("Error: TUI requires a real terminal.");
-        eprintln!("");
+        eprintln!();
         eprintln!("To run the TUI:");
         eprintln!("  1. Open a new Terminal window");
         eprintln!("  2. cd to the dax-cli project directory");
@@ -160,8 +1583,30 @@ fn main() -> io::Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut state = AppState::default();
-    state.stream_state = "done".to_string();
+    let mut config = Config::load();
+    let mut themes = theme::ThemeManager::new(theme::ColorDepth::detect());
+    let requested = flag_value("theme")
+        .or_else(|| {
+            std::env::var("DAX_TUI_THEME")
+                .ok()
+                .filter(|t| !t.is_empty())
+        })
+        .or_else(|| config.theme.clone());
+    if let Some(name) = requested {
+        if let Err(e) = themes.select(&name) {
+            config.diagnostics.push(format!("theme: {}", e));
+        }
+    }
+    let mut state = new_state(&config);
+    if !config.diagnostics.is_empty() {
+        state.overlay = Some(Overlay::Diagnostics);
+    }
+    if config.behavior.thinking != thinking::Mode::default() {
+        set_thinking_mode(&mut state, config.behavior.thinking);
+    }
+    let mut tabs = tabs::Tabs::new(state);
+    let mut store = store::Store::open(config.behavior.restore_sessions, &config.export);
+    store.restore(&mut tabs, || new_state(&config));
 
     let (tx, rx) = mpsc::channel::<String>();
 
@@ -185,188 +1630,114 @@ fn main() -> io::Result<()> {
     });
 
     loop {
-        terminal.draw(|f| ui(f, &mut state))?;
-
-        while let Ok(msg) = rx.try_recv() {
-            if let Ok(tui_msg) = serde_json::from_str::<TuiMessage>(&msg) {
-                match tui_msg {
-                    TuiMessage::Dispatch { event } => match event {
-                        StreamEvent::State { state: s } => state.stream_state = s,
-                        StreamEvent::TextDelta { text } => state.current_stream.push_str(&text),
-                        StreamEvent::ToolCall { name, id } => {
-                            state.current_tool = name.clone();
-                            if let (Some(name), Some(id)) = (name, id) {
-                                state.tools.push(ToolState {
-                                    name,
-                                    id,
-                                    status: "running".to_string(),
-                                    output: None,
-                                    elapsed_ms: None,
-                                });
-                            }
-                        }
-                        StreamEvent::ToolResult {
-                            tool_id,
-                            success,
-                            output,
-                            elapsed_ms,
-                        } => {
-                            state.current_tool = None;
-                            for tool in &mut state.tools {
-                                if tool.id == tool_id.clone().unwrap_or_default() {
-                                    tool.status = if success.clone().unwrap_or(false) {
-                                        "success".to_string()
-                                    } else {
-                                        "error".to_string()
-                                    };
-                                    tool.output = output;
-                                    tool.elapsed_ms = elapsed_ms;
-                                    break;
-                                }
-                            }
-                        }
-                        StreamEvent::Complete => {
-                            if !state.current_stream.is_empty() || !state.tools.is_empty() {
-                                let tools = state.tools.clone();
-                                state.messages.push(Message {
-                                    role: "assistant".to_string(),
-                                    content: state.current_stream.clone(),
-                                    timestamp: std::time::SystemTime::now()
-                                        .duration_since(std::time::UNIX_EPOCH)
-                                        .unwrap_or_default()
-                                        .as_millis()
-                                        as u64,
-                                    tools,
-                                });
-                                state.current_stream.clear();
-                                state.tools.clear();
-                                state.stream_state = "idle".to_string();
-                                state.chat_scroll = state.messages.len().saturating_sub(1);
-                            }
-                        }
-                        StreamEvent::Meta { provider, model } => {
-                            state.provider = provider;
-                            state.model = model;
-                        }
-                        StreamEvent::Error { .. } => {
-                            state.stream_state = "error".to_string();
-                        }
-                        StreamEvent::Gate { .. } => {
-                            state.stream_state = "waiting".to_string();
-                        }
-                    },
-                    TuiMessage::AddUserMessage { content } => {
-                        state.messages.push(Message {
-                            role: "user".to_string(),
-                            content,
-                            timestamp: std::time::SystemTime::now()
-                                .duration_since(std::time::UNIX_EPOCH)
-                                .unwrap_or_default()
-                                .as_millis() as u64,
-                            tools: vec![],
-                        });
-                        state.current_stream.clear();
-                        state.stream_state = "thinking".to_string();
-                    }
-                    TuiMessage::SetContext { files, scope } => {
-                        state.context_files = files;
-                        state.context_scope = scope;
-                    }
-                    TuiMessage::UpdateState { state: s } => {
-                        state.stream_state = s;
-                    }
-                    TuiMessage::Destroy => {
-                        disable_raw_mode()?;
-                        execute!(
-                            terminal.backend_mut(),
-                            LeaveAlternateScreen,
-                            DisableMouseCapture
-                        )?;
-                        terminal.show_cursor()?;
-                        return Ok(());
-                    }
-                }
+        if let Some(e) = themes.poll() {
+            tabs.current_mut()
+                .push_system(Role::Error, format!("Theme: {}", e));
+        }
+        terminal.draw(|f| ui(f, &mut tabs, themes.theme(), &config))?;
+
+        while let Ok(line) = rx.try_recv() {
+            let Ok(Envelope { session, message }) = serde_json::from_str::<Envelope>(&line) else {
+                continue;
+            };
+            if let TuiMessage::Destroy = message {
+                store.sync(&tabs, true);
+                disable_raw_mode()?;
+                execute!(
+                    terminal.backend_mut(),
+                    LeaveAlternateScreen,
+                    DisableMouseCapture
+                )?;
+                terminal.show_cursor()?;
+                return Ok(());
             }
+            tabs.deliver(
+                session.as_deref(),
+                || new_state(&config),
+                |state| apply_message(state, message, &config),
+            );
         }
 
+        let mut quit = config
+            .keymap
+            .tick()
+            .is_some_and(|action| run_action(action, tabs.current_mut(), &mut themes, &config));
         if event::poll(Duration::from_millis(50))? {
             if let Event::Key(key) = event::read()? {
                 if key.kind == KeyEventKind::Press {
-                    match key.code {
-                        KeyCode::Char('c')
-                            if key
-                                .modifiers
-                                .contains(crossterm::event::KeyModifiers::CONTROL) =>
-                        {
-                            disable_raw_mode()?;
-                            execute!(
-                                terminal.backend_mut(),
-                                LeaveAlternateScreen,
-                                DisableMouseCapture
-                            )?;
-                            terminal.show_cursor()?;
-                            return Ok(());
-                        }
-                        KeyCode::Enter => {
-                            if !state.input.is_empty() {
-                                let input = state.input.clone();
-                                let msg = serde_json::json!({
-                                    "type": "input",
-                                    "content": input
-                                });
-                                println!("{}", msg);
-                                state.input.clear();
-                            }
-                        }
-                        KeyCode::Char(c) => {
-                            state.input.push(c);
-                        }
-                        KeyCode::Backspace => {
-                            state.input.pop();
-                        }
-                        KeyCode::Up => {
-                            if state.chat_scroll > 0 {
-                                state.chat_scroll -= 1;
-                            }
-                        }
-                        KeyCode::Down => {
-                            if state.chat_scroll < state.messages.len().saturating_sub(1) {
-                                state.chat_scroll += 1;
-                            }
-                        }
-                        KeyCode::PageUp => {
-                            state.chat_scroll = state.chat_scroll.saturating_sub(10);
-                        }
-                        KeyCode::PageDown => {
-                            state.chat_scroll = (state.chat_scroll + 10)
-                                .min(state.messages.len().saturating_sub(1));
-                        }
-                        KeyCode::Home => {
-                            state.chat_scroll = 0;
-                        }
-                        KeyCode::End => {
-                            state.chat_scroll = state.messages.len().saturating_sub(1);
-                        }
-                        _ => {}
+                    let mut next = Some(key);
+                    while let Some(key) = next.take() {
+                        quit |= handle_key(key, tabs.current_mut(), &mut themes, &mut config);
+                        next = config.keymap.take_replay();
                     }
                 }
             }
         }
+        if let Some(action) = tabs.current_mut().tab_action.take() {
+            session_action(&mut tabs, &mut store, action);
+        }
+        if let Some(e) = store.sync(&tabs, quit) {
+            tabs.current_mut().push_system(Role::Error, e);
+        }
+        if quit {
+            disable_raw_mode()?;
+            execute!(
+                terminal.backend_mut(),
+                LeaveAlternateScreen,
+                DisableMouseCapture
+            )?;
+            terminal.show_cursor()?;
+            return Ok(());
+        }
     }
 }
 
-fn ui(frame: &mut Frame, state: &mut AppState) {
-    // OpenCode.ai style dark theme colors
-    let theme = Theme::default();
+fn ui(frame: &mut Frame, tabs: &mut tabs::Tabs, theme: &Theme, config: &Config) {
+    let marker_style = Style::default().fg(theme.warning).bold();
+
+    frame.render_widget(
+        Block::default().style(Style::default().bg(theme.bg)),
+        frame.area(),
+    );
 
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),
-            Constraint::Min(0),
-            Constraint::Length(6),
-        ])
-        .split(frame.area());
+    if layout::too_small(frame.area()) {
+        let area = frame.area();
+        let lines = vec![
+            Line::from(Span::styled(
+                "Terminal too small",
+                Style::default().fg(theme.warning).bold(),
+            )),
+            Line::from(Span::styled(
+                format!(
+                    "{}×{}, need at least {}×{}",
+                    area.width,
+                    area.height,
+                    layout::MIN_WIDTH,
+                    layout::MIN_HEIGHT
+                ),
+                Style::default().fg(theme.dim),
+            )),
+        ];
+        let height = (lines.len() as u16).min(area.height);
+        frame.render_widget(
+            Paragraph::new(lines).alignment(Alignment::Center),
+            Rect::new(
+                area.x,
+                area.y + (area.height - height) / 2,
+                area.width,
+                height,
+            ),
+        );
+        return;
+    }
+    let areas = layout::compute(
+        frame.area(),
+        &config.layout,
+        tabs.current().drawer_open,
+        tabs.len() > 1,
+    );
+    tabs.render(frame, areas.tabs, theme, &config.keymap);
+    let state = tabs.current_mut();
 
     // Header with status
     let (status_color, status_text) = match state.stream_state.as_str() {
@@ -380,66 +1751,219 @@ fn ui(frame: &mut Frame, state: &mut AppState) {
     };
 
     let provider_info = match (&state.provider, &state.model) {
-        (Some(p), Some(m)) => format!(" • {}:{}", p, m),
-        (Some(p), None) => format!(" • {}", p),
+        (Some(p), Some(m)) => format!(" • {}:{}", sanitize::inline(p), sanitize::inline(m)),
+        (Some(p), None) => format!(" • {}", sanitize::inline(p)),
         _ => String::new(),
     };
 
     // Header block with gradient-style title
-    let header = Block::default()
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(theme.border))
-        .title(Span::styled(
-            format!("  DAX {} {} ", status_text, provider_info),
-            Style::default().fg(status_color).bold(),
+    let mut titles = vec![Span::styled(
+        format!("  DAX {} {} ", status_text, provider_info),
+        Style::default().fg(status_color).bold(),
+    )];
+    if state
+        .scope_status
+        .as_ref()
+        .is_some_and(|s| !s.within_limits)
+    {
+        titles.push(Span::styled(
+            " ⚠ SCOPE LIMIT EXCEEDED ",
+            Style::default().fg(theme.bg).bg(theme.error).bold(),
         ));
-    frame.render_widget(header, chunks[0]);
-
-    // Main content area
-    let main_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
-        .split(chunks[1]);
+    }
+    if let Some(plan) = &state.plan {
+        titles.push(Span::styled(
+            plan.badge(),
+            Style::default().fg(theme.accent).bold(),
+        ));
+    }
+    if let Some(phase) = state.timeline.current_phase() {
+        let step = state
+            .timeline
+            .current_step()
+            .map(|s| format!(" → {}", sanitize::inline(s)))
+            .unwrap_or_default();
+        titles.push(Span::styled(
+            format!(" {}{} ", sanitize::inline(phase), step),
+            Style::default().fg(theme.dim),
+        ));
+    }
+    if config.behavior.vim_mode {
+        let color = match state.mode {
+            vim::Mode::Insert => theme.success,
+            vim::Mode::Normal => theme.accent,
+            vim::Mode::Search | vim::Mode::Command => theme.warning,
+        };
+        titles.push(Span::styled(
+            format!(" {} ", state.mode.label()),
+            Style::default().fg(theme.bg).bg(color).bold(),
+        ));
+    }
+    if let Some(notice) = &state.notice {
+        titles.push(Span::styled(
+            format!(" {} ", sanitize::inline(notice)),
+            Style::default().fg(theme.warning),
+        ));
+    }
+    if let Some(action) = state.armed {
+        let what = match action {
+            Action::CloseSession => "close this session",
+            _ => "quit",
+        };
+        titles.push(Span::styled(
+            format!(" Press {} again to {} ", config.keymap.hint(action), what),
+            Style::default().fg(theme.warning).bold(),
+        ));
+    }
+    if areas.compact_header {
+        let mut spans = Vec::new();
+        for title in titles {
+            spans.push(title);
+            spans.push(Span::raw(" "));
+        }
+        frame.render_widget(Paragraph::new(Line::from(spans)), areas.header);
+    } else {
+        let mut header = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border));
+        for title in titles {
+            header = header.title(title);
+        }
+        frame.render_widget(header, areas.header);
+    }
 
     // Chat area with custom styling
-    let chat_block = Block::default()
+    let mut chat_block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(theme.border))
         .title(Span::styled(" Chat ", Style::default().fg(theme.dim)));
-    frame.render_widget(&chat_block, main_chunks[0]);
+    if areas.collapsed {
+        chat_block = chat_block.title(
+            Line::from(Span::styled(
+                format!(" {} context ", config.keymap.hint(Action::ToggleSidebar)),
+                Style::default().fg(theme.dim),
+            ))
+            .right_aligned(),
+        );
+    }
+    frame.render_widget(&chat_block, areas.chat);
 
-    let chat_area = Rect::new(
-        main_chunks[0].x + 1,
-        main_chunks[0].y + 1,
-        main_chunks[0].width.saturating_sub(2),
-        main_chunks[0].height.saturating_sub(2),
-    );
+    let mut chat_area = chat_block.inner(areas.chat);
+    // The error banner takes the bottom of the chat so the tail stays visible.
+    let banner_area = state.error.as_ref().map(|_| {
+        let height = errors::HEIGHT.min(chat_area.height);
+        chat_area.height -= height;
+        Rect::new(
+            chat_area.x,
+            chat_area.y + chat_area.height,
+            chat_area.width,
+            height,
+        )
+    });
 
     let mut chat_lines: Vec<Line> = Vec::new();
+    state.message_lines.clear();
 
     for (i, msg) in state.messages.iter().enumerate() {
+        let after_note = i > 0 && state.messages[i - 1].role.is_note();
+        if after_note && !msg.role.is_note() {
+            chat_lines.push(Line::from(""));
+        }
+        state.message_lines.push(chat_lines.len());
         let is_current = i == state.chat_scroll;
-        let role_color = if msg.role == "user" {
-            theme.user
-        } else if msg.role == "assistant" {
-            theme.assistant
-        } else {
-            theme.error
-        };
+        let role_color = msg.role.color(theme);
+        let prefix = if is_current { "▶" } else { "▸" };
 
-        let role_label = if msg.role == "user" { "You" } else { "DAX" };
+        // Notes are an audit trail: always timestamped, kept to their own lines.
+        if msg.role.is_note() {
+            let time = chrono::DateTime::from_timestamp_millis(msg.timestamp as i64)
+                .map(|t| {
+                    t.with_timezone(&chrono::Local)
+                        .format("%H:%M:%S ")
+                        .to_string()
+                })
+                .unwrap_or_default();
+            for (n, text) in msg.content.lines().enumerate() {
+                let lead = if n == 0 {
+                    vec![
+                        Span::styled(prefix, Style::default().fg(theme.accent).bold()),
+                        Span::styled(
+                            format!(" {} ", msg.role.label()),
+                            Style::default().fg(role_color).bold(),
+                        ),
+                        Span::styled(time.clone(), Style::default().fg(theme.dim)),
+                    ]
+                } else {
+                    vec![Span::raw("   ")]
+                };
+                let mut line = lead;
+                line.push(Span::styled(
+                    sanitize::inline(text),
+                    Style::default().fg(if msg.role == Role::System {
+                        theme.dim
+                    } else {
+                        role_color
+                    }),
+                ));
+                chat_lines.push(Line::from(line));
+            }
+            continue;
+        }
 
-        let prefix = if is_current { "▶" } else { "▸" };
-        chat_lines.push(Line::from(vec![
+        let mut role_line = vec![
             Span::styled(prefix, Style::default().fg(theme.accent).bold()),
             Span::styled(
-                format!(" {} ", role_label),
+                format!(" {} ", msg.role.label()),
                 Style::default().fg(role_color).bold(),
             ),
-        ]));
+        ];
+        if config.behavior.timestamps {
+            if let Some(time) = chrono::DateTime::from_timestamp_millis(msg.timestamp as i64) {
+                role_line.push(Span::styled(
+                    time.with_timezone(&chrono::Local)
+                        .format("%H:%M:%S")
+                        .to_string(),
+                    Style::default().fg(theme.dim),
+                ));
+            }
+        }
+        if let Some((n, count)) = msg
+            .fork
+            .as_deref()
+            .and_then(|key| state.branches.indicator(key))
+        {
+            role_line.push(Span::styled(
+                format!(" ‹{}/{}›", n, count),
+                Style::default().fg(theme.accent).bold(),
+            ));
+            if is_current {
+                role_line.push(Span::styled(
+                    config.keymap.hints(&[
+                        (Action::PrevBranch, "prev"),
+                        (Action::NextBranch, "next branch"),
+                    ]),
+                    Style::default().fg(theme.dim),
+                ));
+            }
+        }
+        chat_lines.push(Line::from(role_line));
+
+        if state.thinking_mode != thinking::Mode::Off {
+            let expanded = msg
+                .thinking_expanded
+                .unwrap_or(state.thinking_mode == thinking::Mode::Verbose);
+            chat_lines.extend(thinking::lines(
+                &msg.thinking,
+                expanded,
+                theme,
+                &config.keymap.hint(Action::ToggleThinking),
+            ));
+        }
 
-        for line in msg.content.lines() {
-            chat_lines.push(Line::from(vec![Span::raw("   "), Span::raw(line)]));
+        let text_style = Style::default().fg(theme.text);
+        for mut line in sanitize::styled_lines(&msg.content, text_style, marker_style) {
+            line.spans.insert(0, Span::raw("   "));
+            chat_lines.push(line);
         }
 
         if !msg.tools.is_empty() {
@@ -464,11 +1988,46 @@ fn ui(frame: &mut Frame, state: &mut AppState) {
                 chat_lines.push(Line::from(vec![
                     Span::raw("   "),
                     Span::styled(
-                        format!("{} {}", icon, tool.name),
+                        format!("{} {}", icon, sanitize::inline(&tool.name)),
                         Style::default().fg(tool_color).bold(),
                     ),
                     Span::styled(elapsed, Style::default().fg(theme.dim)),
                 ]));
+                if !tool.diff.is_empty() {
+                    chat_lines.push(Line::from(vec![
+                        Span::styled("     ± ", Style::default().fg(theme.accent)),
+                        Span::styled(diff::summary(&tool.diff), Style::default().fg(theme.text)),
+                        Span::styled(
+                            format!(
+                                " • {} to view • {} to review",
+                                config.keymap.hint(Action::OpenDiff),
+                                config.keymap.hint(Action::OpenReview)
+                            ),
+                            Style::default().fg(theme.dim),
+                        ),
+                    ]));
+                } else if let Some(output) = &tool.output {
+                    let output_lines = ansi::styled_lines(
+                        output,
+                        Style::default().fg(theme.dim),
+                        marker_style,
+                        theme,
+                    );
+                    let hidden = output_lines.len().saturating_sub(TOOL_OUTPUT_LINES);
+                    if hidden > 0 {
+                        chat_lines.push(Line::from(Span::styled(
+                            format!("     │ … {} earlier lines", hidden),
+                            Style::default().fg(theme.dim),
+                        )));
+                    }
+                    for mut line in output_lines.into_iter().skip(hidden) {
+                        line.spans.insert(
+                            0,
+                            Span::styled("     │ ", Style::default().fg(theme.border)),
+                        );
+                        chat_lines.push(line);
+                    }
+                }
             }
         }
 
@@ -476,20 +2035,35 @@ fn ui(frame: &mut Frame, state: &mut AppState) {
     }
 
     // Current streaming message
-    if !state.current_stream.is_empty() {
+    let live_thinking =
+        state.thinking_mode != thinking::Mode::Off && !state.current_thinking.trim().is_empty();
+    if !state.current_stream.is_empty() || live_thinking {
         chat_lines.push(Line::from(vec![
             Span::styled("▸ ", Style::default().fg(theme.assistant).bold()),
             Span::styled("DAX ", Style::default().fg(theme.assistant).bold()),
         ]));
-        for line in state.current_stream.lines() {
-            chat_lines.push(Line::from(vec![Span::raw("   "), Span::raw(line)]));
+        if live_thinking {
+            let expanded = state
+                .thinking_expanded
+                .unwrap_or(state.thinking_mode == thinking::Mode::Verbose);
+            chat_lines.extend(thinking::lines(
+                &state.current_thinking,
+                expanded,
+                theme,
+                &config.keymap.hint(Action::ToggleThinking),
+            ));
+        }
+        let text_style = Style::default().fg(theme.text);
+        for mut line in sanitize::styled_lines(&state.current_stream, text_style, marker_style) {
+            line.spans.insert(0, Span::raw("   "));
+            chat_lines.push(line);
         }
 
         if let Some(tool_name) = &state.current_tool {
             chat_lines.push(Line::from(vec![
                 Span::raw(""),
                 Span::styled(
-                    format!("   ◐ running: {}", tool_name),
+                    format!("   ◐ running: {}", sanitize::inline(tool_name)),
                     Style::default().fg(theme.warning),
                 ),
             ]));
@@ -497,78 +2071,425 @@ fn ui(frame: &mut Frame, state: &mut AppState) {
     }
 
     let chat_lines_count = chat_lines.len();
-    let chat_list = List::new(chat_lines);
+    let height = chat_area.height as usize;
+    let max_offset = chat_lines_count.saturating_sub(height);
+    if state.follow_tail {
+        state.chat_offset = max_offset;
+    } else if state.reveal {
+        let start = state
+            .message_lines
+            .get(state.chat_scroll)
+            .copied()
+            .unwrap_or(0);
+        let end = state
+            .message_lines
+            .get(state.chat_scroll + 1)
+            .copied()
+            .unwrap_or(chat_lines_count);
+        if start < state.chat_offset {
+            state.chat_offset = start;
+        } else if end > state.chat_offset + height {
+            state.chat_offset = start.min(end.saturating_sub(height));
+        }
+    }
+    state.chat_offset = state.chat_offset.min(max_offset);
+    state.chat_height = height;
+    state.reveal = false;
+
+    let chat_list = List::new(chat_lines.into_iter().skip(state.chat_offset));
     frame.render_widget(chat_list, chat_area);
 
-    if chat_lines_count > chat_area.height as usize {
+    if chat_lines_count > height {
         let scrollbar = Scrollbar::default();
-        state.scroll_state = state.scroll_state.content_length(chat_lines_count);
+        state.scroll_state = state
+            .scroll_state
+            .content_length(max_offset + 1)
+            .position(state.chat_offset);
         frame.render_stateful_widget(scrollbar, chat_area, &mut state.scroll_state);
     }
 
     // Sidebar with context
-    let sidebar_block = Block::default()
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(theme.border))
-        .title(Span::styled(" Context ", Style::default().fg(theme.dim)));
-    frame.render_widget(sidebar_block, main_chunks[1]);
+    if let Some(sidebar) = areas.sidebar {
+        let layout = &config.layout;
+        if areas.drawer {
+            clear_area(frame, sidebar, theme);
+        }
+        let focused = state.sidebar_focus && state.overlay.is_none();
+        let mut sidebar_block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(if focused { theme.accent } else { theme.border }))
+            .title(Span::styled(" Context ", Style::default().fg(theme.dim)));
+        if focused {
+            sidebar_block = sidebar_block.title_bottom(Span::styled(
+                config.keymap.hints(&[
+                    (Action::SidebarFilter, "filter"),
+                    (Action::SidebarPreview, "preview"),
+                    (Action::SidebarPin, "pin"),
+                    (Action::SidebarRemove, "remove"),
+                ]),
+                Style::default().fg(theme.dim),
+            ));
+        }
+        let sidebar_inner = sidebar_block.inner(sidebar);
+        frame.render_widget(sidebar_block, sidebar);
 
-    let sidebar_chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(main_chunks[1]);
+        // The plan pane only takes room while there is a plan.
+        let panes: Vec<&str> = layout
+            .panes
+            .iter()
+            .map(String::as_str)
+            .filter(|p| *p != "plan" || state.plan.is_some())
+            .collect();
+        let count = panes.len() as u32;
+        let sidebar_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(panes.iter().map(|_| Constraint::Ratio(1, count)))
+            .split(sidebar_inner);
 
-    // Context - Files
-    let files_text = if state.context_files.is_empty() {
-        "No files loaded".to_string()
-    } else {
-        state.context_files.join("\n")
-    };
-    let files_para = Paragraph::new(files_text).style(Style::default().fg(theme.text));
-    frame.render_widget(
-        files_para,
-        Rect::new(
-            sidebar_chunks[0].x + 1,
-            sidebar_chunks[0].y + 1,
-            sidebar_chunks[0].width.saturating_sub(2),
-            sidebar_chunks[0].height.saturating_sub(2),
-        ),
-    );
-
-    // Context - Scope
-    let scope_text = if state.context_scope.is_empty() {
-        "No scope defined".to_string()
-    } else {
-        state.context_scope.join("\n")
-    };
-    let scope_para = Paragraph::new(scope_text).style(Style::default().fg(theme.text));
-    frame.render_widget(
-        scope_para,
-        Rect::new(
-            sidebar_chunks[1].x + 1,
-            sidebar_chunks[1].y + 1,
-            sidebar_chunks[1].width.saturating_sub(2),
-            sidebar_chunks[1].height.saturating_sub(2),
-        ),
-    );
+        // Each pane gets a titled rule inside the sidebar border.
+        for (&pane, area) in panes.iter().zip(sidebar_chunks.iter()) {
+            let title = match pane {
+                "timeline" => " Timeline ".to_string(),
+                "plan" => state
+                    .plan
+                    .as_ref()
+                    .map(plan::Plan::title)
+                    .unwrap_or_default(),
+                "files" => state.context.title(&state.context_files),
+                _ => match &state.scope_status {
+                    Some(status) => status.title(),
+                    None => format!(" Scope • {} ", state.context_scope.len()),
+                },
+            };
+            let title_color = if focused && pane == "files" {
+                theme.accent
+            } else {
+                theme.dim
+            };
+            let pane_block = Block::default()
+                .borders(Borders::TOP)
+                .border_style(Style::default().fg(theme.border))
+                .title(Span::styled(title, Style::default().fg(title_color)));
+            let inner = pane_block.inner(*area);
+            frame.render_widget(pane_block, *area);
+            match pane {
+                "timeline" => state.timeline.render(frame, inner, theme),
+                "plan" => {
+                    if let Some(plan) = &state.plan {
+                        plan.render(frame, inner, theme);
+                    }
+                }
+                "files" => state
+                    .context
+                    .render(frame, inner, theme, &state.context_files, focused),
+                _ => match &state.scope_status {
+                    Some(status) => status.render(frame, inner, theme, &state.context_scope),
+                    None => {
+                        let lines: Vec<Line> = if state.context_scope.is_empty() {
+                            vec![Line::from(Span::styled(
+                                "No scope defined",
+                                Style::default().fg(theme.dim),
+                            ))]
+                        } else {
+                            state
+                                .context_scope
+                                .iter()
+                                .map(|s| {
+                                    Line::from(Span::styled(
+                                        sanitize::inline(s),
+                                        Style::default().fg(theme.text),
+                                    ))
+                                })
+                                .collect()
+                        };
+                        frame.render_widget(Paragraph::new(lines), inner);
+                    }
+                },
+            }
+        }
+    }
 
     // Input area
     let input_block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(theme.accent))
-        .title(Span::styled(" Input ", Style::default().fg(theme.dim)));
-    frame.render_widget(input_block, chunks[2]);
+        .title(match &state.editing {
+            Some(_) => Span::styled(
+                format!(
+                    " Edit message •{}",
+                    config.keymap.hints(&[
+                        (Action::Submit, "resend as new branch"),
+                        (Action::NormalMode, "cancel"),
+                    ])
+                ),
+                Style::default().fg(theme.warning).bold(),
+            ),
+            None => Span::styled(" Input ", Style::default().fg(theme.dim)),
+        });
+    frame.render_widget(input_block, areas.input);
 
-    let input_text = Paragraph::new(state.input.as_str()).style(Style::default().fg(theme.text));
-    let input_cursor = if state.input.is_empty() { "▊" } else { "" };
+    let (input_text, input_color) = match state.mode {
+        vim::Mode::Insert => {
+            let input_cursor = if state.input.is_empty() { "▊" } else { "" };
+            (
+                format!("{}{}", sanitize::sanitize(&state.input), input_cursor),
+                theme.text,
+            )
+        }
+        vim::Mode::Normal => (sanitize::sanitize(&state.input), theme.dim),
+        vim::Mode::Search => (format!("/{}▊", sanitize::inline(&state.prompt)), theme.text),
+        vim::Mode::Command => (format!(":{}▊", sanitize::inline(&state.prompt)), theme.text),
+    };
     frame.render_widget(
-        Paragraph::new(format!("{}{}", state.input, input_cursor))
-            .style(Style::default().fg(theme.text)),
+        Paragraph::new(input_text).style(Style::default().fg(input_color)),
         Rect::new(
-            chunks[2].x + 1,
-            chunks[2].y + 1,
-            chunks[2].width.saturating_sub(2),
-            chunks[2].height.saturating_sub(2),
+            areas.input.x + 1,
+            areas.input.y + 1,
+            areas.input.width.saturating_sub(2),
+            areas.input.height.saturating_sub(2),
         ),
     );
+
+    if let (Some(error), Some(area)) = (&state.error, banner_area) {
+        error.render(frame, area, theme, &config.keymap);
+    }
+
+    state
+        .usage
+        .render(frame, areas.status, theme, state.elapsed_ms);
+
+    match &mut state.overlay {
+        Some(Overlay::Diff(view)) => view.render(frame, areas.body, theme, &config.keymap),
+        Some(Overlay::Review(view)) => view.render(frame, areas.body, theme, &config.keymap),
+        Some(Overlay::Gate) => {
+            if let Some(gate) = &state.gate {
+                gate.render(frame, areas.body, theme, &config.keymap);
+            }
+        }
+        Some(Overlay::Diagnostics) => config.render_diagnostics(frame, areas.body, theme),
+        Some(Overlay::Help(view)) => view.render(
+            frame,
+            areas.body,
+            theme,
+            &config.keymap,
+            &state.commands,
+            config.behavior.vim_mode,
+        ),
+        Some(Overlay::Preview(view)) => view.render(frame, areas.body, theme, &config.keymap),
+        Some(Overlay::Waterfall(view)) => {
+            view.render(frame, areas.body, theme, &config.keymap, &state.waterfall)
+        }
+        Some(Overlay::Policy(view)) => view.render(
+            frame,
+            areas.body,
+            theme,
+            &config.keymap,
+            state.policy.state.as_ref(),
+        ),
+        Some(Overlay::Plan(view)) => view.render(
+            frame,
+            areas.body,
+            theme,
+            &config.keymap,
+            state.plan.as_ref(),
+        ),
+        Some(Overlay::Rao(view)) => {
+            view.render(frame, areas.body, theme, &config.keymap, &state.rao_history)
+        }
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses a line the way the input thread does.
+    fn parse(line: &str) -> Envelope {
+        serde_json::from_str(line).unwrap_or_else(|e| panic!("{}: {}", line, e))
+    }
+
+    fn event(line: &str) -> StreamEvent {
+        match parse(line).message {
+            TuiMessage::Dispatch { event, .. } => event,
+            other => panic!("not a dispatch: {:?}", other),
+        }
+    }
+
+    // Shapes below are what src/cli/tui/ratatui/backend.ts sends.
+
+    #[test]
+    fn parses_complete_with_empty_data() {
+        assert!(matches!(
+            event(r#"{"type":"dispatch","timestamp":1,"event":{"type":"complete","data":{}}}"#),
+            StreamEvent::Complete {}
+        ));
+    }
+
+    #[test]
+    fn parses_stream_events() {
+        let lines = [
+            r#"{"type":"dispatch","timestamp":1,"event":{"type":"meta","data":{"provider":"p","model":"m"}}}"#,
+            r#"{"type":"dispatch","timestamp":1,"event":{"type":"state","data":{"state":"thinking"}}}"#,
+            r#"{"type":"dispatch","timestamp":1,"event":{"type":"text_delta","data":{"text":"hi"}}}"#,
+            r#"{"type":"dispatch","timestamp":1,"event":{"type":"thinking_delta","data":{"text":"hm"}}}"#,
+            r#"{"type":"dispatch","timestamp":1,"event":{"type":"tool_call","data":{"name":"read","id":"t1","arguments":"{}"}}}"#,
+            r#"{"type":"dispatch","timestamp":1,"event":{"type":"tool_result","data":{"tool_id":"t1","success":true,"output":"ok","elapsed_ms":3}}}"#,
+            r#"{"type":"dispatch","timestamp":1,"event":{"type":"phase","data":{"phase":"plan"}}}"#,
+            r#"{"type":"dispatch","timestamp":1,"event":{"type":"step","data":{"phase":"plan","step":"read"}}}"#,
+            r#"{"type":"dispatch","timestamp":1,"event":{"type":"plan","data":{"title":"t","steps":[{"title":"a","substeps":[{"title":"b"}]}]}}}"#,
+            r#"{"type":"dispatch","timestamp":1,"event":{"type":"plan_update","data":{"id":"1","status":"done"}}}"#,
+            r#"{"type":"dispatch","timestamp":1,"event":{"type":"usage","data":{}}}"#,
+            r#"{"type":"dispatch","timestamp":1,"event":{"type":"usage","data":{"input_tokens":1,"output_tokens":2,"cost_usd":0.5}}}"#,
+            r#"{"type":"dispatch","timestamp":1,"event":{"type":"error","data":{"message":"boom","code":"E","recoverable":true}}}"#,
+            r#"{"type":"dispatch","timestamp":1,"event":{"type":"gate","data":{"id":"g","blocked":false,"warnings":[{"code":"c","subject":"s"}]}}}"#,
+            r#"{"type":"dispatch","timestamp":1,"event":{"type":"gate_resolved","data":{"action":"approve_once"}}}"#,
+        ];
+        for line in lines {
+            event(line);
+        }
+    }
+
+    #[test]
+    fn parses_host_messages() {
+        let lines = [
+            r#"{"type":"addUserMessage","content":"hi","id":"u1"}"#,
+            r#"{"type":"addUserMessage","content":"hi"}"#,
+            r#"{"type":"addSystemMessage","content":"note","role":"warning"}"#,
+            r#"{"type":"setContext","files":["a.rs"],"scope":["src/**"]}"#,
+            r#"{"type":"updateState","state":"idle"}"#,
+            r#"{"type":"scopeStatus","withinLimits":true,"metrics":{"filesModified":[],"totalFiles":0,"totalLoc":0,"filesAdded":0,"filesRemoved":0,"locAdded":0,"locRemoved":0},"limits":{"maxFiles":10,"maxLoc":500,"allowedPatterns":[]},"touchedFilesCount":0,"changedLoc":0,"warnings":[]}"#,
+            r#"{"type":"pmState","project_id":"p","constraints":{},"preferences":{"risk":"balanced"},"recent_outcomes":[],"last_updated":"2026-01-01T00:00:00Z"}"#,
+            r#"{"type":"raoHistory","history":[{"id":"r","ts":"2026-01-01T00:00:00Z","kind":"clear"}]}"#,
+            r#"{"type":"setCommands","commands":[{"name":"/help","description":"Help"}]}"#,
+            r#"{"type":"snapshot","messages":[{"id":"u1","role":"user","content":"hi"}],"state":"idle"}"#,
+            r#"{"type":"destroy"}"#,
+        ];
+        for line in lines {
+            parse(line);
+        }
+    }
+
+    #[test]
+    fn reads_the_session_beside_the_message() {
+        let envelope = parse(r#"{"type":"updateState","state":"idle","session":"s1"}"#);
+        assert_eq!(envelope.session.as_deref(), Some("s1"));
+        assert!(matches!(envelope.message, TuiMessage::UpdateState { .. }));
+    }
+
+    #[test]
+    fn completes_the_turn() {
+        let config = Config::default();
+        let mut state = new_state(&config);
+        for line in [
+            r#"{"type":"dispatch","timestamp":1,"event":{"type":"text_delta","data":{"text":"hello"}}}"#,
+            r#"{"type":"dispatch","timestamp":2,"event":{"type":"complete","data":{}}}"#,
+        ] {
+            apply_message(&mut state, parse(line).message, &config);
+        }
+        assert!(!state.turn_active());
+        let last = state.messages.last().expect("assistant message");
+        assert_eq!(last.role, Role::Assistant);
+        assert_eq!(last.content, "hello");
+    }
+
+    #[test]
+    fn keeps_partial_output_when_a_turn_fails() {
+        let config = Config::default();
+        let mut state = new_state(&config);
+        for line in [
+            r#"{"type":"dispatch","timestamp":1,"event":{"type":"text_delta","data":{"text":"half a"}}}"#,
+            r#"{"type":"dispatch","timestamp":1,"event":{"type":"tool_call","data":{"name":"edit","id":"t1","arguments":"{}"}}}"#,
+            r#"{"type":"dispatch","timestamp":2,"event":{"type":"error","data":{"message":"boom","recoverable":true}}}"#,
+        ] {
+            apply_message(&mut state, parse(line).message, &config);
+        }
+        let roles: Vec<Role> = state.messages.iter().map(|m| m.role).collect();
+        assert_eq!(roles, [Role::Assistant, Role::Error]);
+        let partial = &state.messages[0];
+        assert_eq!(partial.content, "half a");
+        assert_eq!(partial.tools.len(), 1);
+        assert_eq!(partial.tools[0].status, "error");
+        assert!(state.current_stream.is_empty() && state.tools.is_empty());
+
+        for line in [
+            r#"{"type":"dispatch","timestamp":3,"event":{"type":"text_delta","data":{"text":"retried"}}}"#,
+            r#"{"type":"dispatch","timestamp":4,"event":{"type":"complete","data":{}}}"#,
+        ] {
+            apply_message(&mut state, parse(line).message, &config);
+        }
+        let last = state.messages.last().expect("assistant message");
+        assert_eq!(last.role, Role::Assistant);
+        assert_eq!(last.content, "retried");
+    }
+
+    #[test]
+    fn keeps_an_edit_of_a_hidden_message_as_a_draft() {
+        let config = Config::default();
+        let mut themes = theme::ThemeManager::new(theme::ColorDepth::TrueColor);
+        let mut state = new_state(&config);
+        state.input = "edited".to_string();
+        state.editing = Some("gone".to_string());
+
+        run_action(Action::Submit, &mut state, &mut themes, &config);
+        assert_eq!(state.input, "edited");
+        assert_eq!(state.editing.as_deref(), Some("gone"));
+        assert!(state.messages.is_empty());
+        assert_eq!(
+            state.notice.as_deref(),
+            Some("message is on a hidden branch")
+        );
+    }
+
+    #[test]
+    fn offers_a_review_for_held_calls_with_diffs() {
+        let config = Config::default();
+        let mut state = new_state(&config);
+        // What src/cli/gateEvents.ts sends after the turn completes.
+        for line in [
+            r#"{"type":"dispatch","timestamp":1,"event":{"type":"complete","data":{}}}"#,
+            r#"{"type":"dispatch","timestamp":2,"event":{"type":"tool_call","data":{"name":"edit_file","id":"t1","arguments":"{}","diff":"--- a/src/a.ts\n+++ b/src/a.ts\n@@ -4,3 +4,3 @@\n four\n-five\n+FIVE\n six\n"}}}"#,
+            r#"{"type":"dispatch","timestamp":3,"event":{"type":"gate","data":{"id":"g1","blocked":false,"warnings":[{"code":"require_approval.tool","subject":"edit_file"}]}}}"#,
+        ] {
+            apply_message(&mut state, parse(line).message, &config);
+        }
+        assert!(state.gate.as_ref().expect("gate").reviewable);
+
+        for line in [
+            r#"{"type":"dispatch","timestamp":4,"event":{"type":"tool_result","data":{"tool_id":"t1","success":true,"output":"ok"}}}"#,
+            r#"{"type":"dispatch","timestamp":5,"event":{"type":"gate_resolved","data":{"action":"approve_once"}}}"#,
+            r#"{"type":"dispatch","timestamp":6,"event":{"type":"complete","data":{}}}"#,
+        ] {
+            apply_message(&mut state, parse(line).message, &config);
+        }
+        let last = state.messages.last().expect("the held calls");
+        assert_eq!(last.tools[0].status, "success");
+        assert!(state.tools.is_empty());
+    }
+
+    #[test]
+    fn keeps_a_reviewed_gate_until_the_host_answers() {
+        let config = Config::default();
+        let mut themes = theme::ThemeManager::new(theme::ColorDepth::TrueColor);
+        let mut state = new_state(&config);
+        let gate = r#"{"type":"dispatch","timestamp":1,"event":{"type":"gate","data":{"id":"g1","blocked":false,"warnings":[]}}}"#;
+        apply_message(&mut state, parse(gate).message, &config);
+        state.overlay = Some(Overlay::Review(review::ReviewView::new(
+            Vec::new(),
+            Some("g1".to_string()),
+        )));
+
+        run_action(Action::ReviewSubmit, &mut state, &mut themes, &config);
+        let gate = state.gate.as_ref().expect("gate still pending");
+        assert!(gate.submitted.is_some());
+        assert!(matches!(state.overlay, Some(Overlay::Gate)));
+        assert!(gate.handle_action(Action::GateApprove).is_none());
+        assert!(state.gate_log.is_empty());
+
+        let resolved = r#"{"type":"dispatch","timestamp":2,"event":{"type":"gate_resolved","data":{"action":"reject"}}}"#;
+        apply_message(&mut state, parse(resolved).message, &config);
+        assert!(state.gate.is_none());
+        assert!(state.overlay.is_none());
+        assert_eq!(state.gate_log[0].action, "reject");
+    }
 }
@@ -0,0 +1,429 @@
+use std::collections::HashMap;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::keymap::{Action, Keymap};
+use crate::sanitize;
+use crate::Theme;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    #[default]
+    Pending,
+    InProgress,
+    Done,
+    Skipped,
+}
+
+impl Status {
+    fn glyph(self) -> &'static str {
+        match self {
+            Status::Pending => "○",
+            Status::InProgress => "◐",
+            Status::Done => "✓",
+            Status::Skipped => "⊘",
+        }
+    }
+
+    fn color(self, theme: &Theme) -> Color {
+        match self {
+            Status::Pending => theme.dim,
+            Status::InProgress => theme.warning,
+            Status::Done => theme.success,
+            Status::Skipped => theme.dim,
+        }
+    }
+
+    fn finished(self) -> bool {
+        matches!(self, Status::Done | Status::Skipped)
+    }
+}
+
+/// One checklist entry from a `plan` event. Steps without an id get their
+/// position ("2", "2.1") so updates and feedback can still refer to them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Step {
+    #[serde(default)]
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub status: Status,
+    #[serde(default)]
+    pub substeps: Vec<Step>,
+}
+
+fn number(steps: &mut [Step], prefix: &str) {
+    for (i, step) in steps.iter_mut().enumerate() {
+        let position = format!("{}{}", prefix, i + 1);
+        if step.id.is_empty() {
+            step.id = position.clone();
+        }
+        number(&mut step.substeps, &format!("{}.", position));
+    }
+}
+
+fn find<'a>(steps: &'a mut [Step], id: &str) -> Option<&'a mut Step> {
+    for step in steps {
+        if step.id == id {
+            return Some(step);
+        }
+        if let Some(found) = find(&mut step.substeps, id) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+fn flatten<'a>(steps: &'a [Step], depth: usize, rows: &mut Vec<(usize, &'a Step)>) {
+    for step in steps {
+        rows.push((depth, step));
+        flatten(&step.substeps, depth + 1, rows);
+    }
+}
+
+/// The host's current plan, with the feedback sent on each step.
+pub struct Plan {
+    pub title: Option<String>,
+    pub steps: Vec<Step>,
+    comments: HashMap<String, Vec<String>>,
+}
+
+impl Plan {
+    pub fn new(title: Option<String>, mut steps: Vec<Step>) -> Self {
+        number(&mut steps, "");
+        Self {
+            title,
+            steps,
+            comments: HashMap::new(),
+        }
+    }
+
+    /// Applies a `plan_update`. Returns false if no step has that id.
+    pub fn update(&mut self, id: &str, status: Option<Status>, title: Option<String>) -> bool {
+        let Some(step) = find(&mut self.steps, id) else {
+            return false;
+        };
+        if let Some(status) = status {
+            step.status = status;
+        }
+        if let Some(title) = title {
+            step.title = title;
+        }
+        true
+    }
+
+    pub fn comment(&mut self, id: &str, text: String) {
+        self.comments.entry(id.to_string()).or_default().push(text);
+    }
+
+    fn rows(&self) -> Vec<(usize, &Step)> {
+        let mut rows = Vec::new();
+        flatten(&self.steps, 0, &mut rows);
+        rows
+    }
+
+    /// Finished (done or skipped) and total steps, sub-steps included.
+    pub fn progress(&self) -> (usize, usize) {
+        let rows = self.rows();
+        let done = rows.iter().filter(|(_, s)| s.status.finished()).count();
+        (done, rows.len())
+    }
+
+    /// Header badge, e.g. ` ◐ Plan 3/7 `.
+    pub fn badge(&self) -> String {
+        let (done, total) = self.progress();
+        let glyph = if done == total {
+            Status::Done.glyph()
+        } else {
+            Status::InProgress.glyph()
+        };
+        format!(" {} Plan {}/{} ", glyph, done, total)
+    }
+
+    pub fn title(&self) -> String {
+        let (done, total) = self.progress();
+        format!(" Plan • {}/{} ", done, total)
+    }
+
+    fn line(&self, depth: usize, step: &Step, theme: &Theme, selected: bool) -> Line<'static> {
+        let mut style = Style::default().fg(if step.status == Status::InProgress {
+            theme.text
+        } else {
+            step.status.color(theme)
+        });
+        if step.status == Status::InProgress {
+            style = style.bold();
+        }
+        if step.status == Status::Skipped {
+            style = style.crossed_out();
+        }
+        if selected {
+            style = Style::default().fg(theme.accent).reversed();
+        }
+        let mut spans = vec![
+            Span::raw("  ".repeat(depth)),
+            Span::styled(
+                format!("{} ", step.status.glyph()),
+                Style::default().fg(step.status.color(theme)),
+            ),
+            Span::styled(sanitize::inline(&step.title), style),
+        ];
+        if let Some(count) = self.comments.get(&step.id).map(Vec::len) {
+            spans.push(Span::styled(
+                format!(" ✎{}", count),
+                Style::default().fg(theme.dim),
+            ));
+        }
+        Line::from(spans)
+    }
+
+    /// Compact checklist for the sidebar, scrolled to the step in progress.
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let rows = self.rows();
+        let current = rows
+            .iter()
+            .position(|(_, s)| s.status == Status::InProgress)
+            .unwrap_or(0);
+        let top = (current + 1).saturating_sub(area.height as usize);
+        let lines: Vec<Line> = rows
+            .iter()
+            .skip(top)
+            .map(|(depth, step)| self.line(*depth, step, theme, false))
+            .collect();
+        frame.render_widget(Paragraph::new(lines), area);
+    }
+}
+
+pub enum PlanOutcome {
+    Continue,
+    Close,
+    /// Feedback on a step for the host.
+    Comment {
+        step_id: String,
+        text: String,
+    },
+}
+
+/// Full-size checklist with a selection, for commenting on steps.
+pub struct PlanView {
+    selected: usize,
+    /// Feedback being typed for the selected step.
+    comment: Option<String>,
+}
+
+impl PlanView {
+    pub fn new(plan: Option<&Plan>) -> Self {
+        // Start on the step being worked on.
+        let selected = plan
+            .and_then(|p| {
+                p.rows()
+                    .iter()
+                    .position(|(_, s)| s.status == Status::InProgress)
+            })
+            .unwrap_or(0);
+        Self {
+            selected,
+            comment: None,
+        }
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.comment.is_some()
+    }
+
+    /// Text entry for a comment; bypasses the keymap.
+    pub fn edit_key(&mut self, key: KeyEvent, plan: &Plan) -> PlanOutcome {
+        let Some(text) = &mut self.comment else {
+            return PlanOutcome::Continue;
+        };
+        match key.code {
+            KeyCode::Esc => self.comment = None,
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            KeyCode::Char(c) => text.push(c),
+            KeyCode::Enter => {
+                let text = text.trim().to_string();
+                self.comment = None;
+                if let Some((_, step)) = plan.rows().get(self.selected) {
+                    if !text.is_empty() {
+                        return PlanOutcome::Comment {
+                            step_id: step.id.clone(),
+                            text,
+                        };
+                    }
+                }
+            }
+            _ => {}
+        }
+        PlanOutcome::Continue
+    }
+
+    pub fn handle_action(&mut self, action: Action, plan: &Plan) -> PlanOutcome {
+        let count = plan.rows().len();
+        match action {
+            Action::PlanClose => return PlanOutcome::Close,
+            Action::PlanDown => self.selected = (self.selected + 1).min(count.saturating_sub(1)),
+            Action::PlanUp => self.selected = self.selected.saturating_sub(1),
+            Action::PlanComment if count > 0 => self.comment = Some(String::new()),
+            _ => {}
+        }
+        PlanOutcome::Continue
+    }
+
+    pub fn render(
+        &mut self,
+        frame: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        keys: &Keymap,
+        plan: Option<&Plan>,
+    ) {
+        crate::clear_area(frame, area, theme);
+        let title = match plan {
+            Some(plan) => match &plan.title {
+                Some(title) => format!("{}• {} ", plan.title(), sanitize::inline(title)),
+                None => plan.title(),
+            },
+            None => " Plan ".to_string(),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(Span::styled(
+                title,
+                Style::default().fg(theme.accent).bold(),
+            ))
+            .title_bottom(Span::styled(
+                keys.hints(&[
+                    (Action::PlanDown, "select"),
+                    (Action::PlanComment, "comment"),
+                    (Action::PlanClose, "close"),
+                ]),
+                Style::default().fg(theme.dim),
+            ));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let Some(plan) = plan else {
+            frame.render_widget(
+                Paragraph::new("No plan from the host yet").style(Style::default().fg(theme.dim)),
+                inner,
+            );
+            return;
+        };
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(if self.comment.is_some() { 2 } else { 0 }),
+            ])
+            .split(inner);
+
+        let steps = plan.rows();
+        self.selected = self.selected.min(steps.len().saturating_sub(1));
+        let mut lines = Vec::new();
+        let mut focus = 0;
+        for (i, (depth, step)) in steps.iter().enumerate() {
+            if i == self.selected {
+                focus = lines.len();
+            }
+            lines.push(plan.line(*depth, step, theme, i == self.selected));
+            for comment in plan.comments.get(&step.id).into_iter().flatten() {
+                lines.push(Line::from(vec![
+                    Span::raw("  ".repeat(depth + 1)),
+                    Span::styled(
+                        format!("↳ {}", sanitize::inline(comment)),
+                        Style::default().fg(theme.dim).italic(),
+                    ),
+                ]));
+            }
+        }
+        let scroll = (focus + 1).saturating_sub(rows[0].height as usize);
+        frame.render_widget(Paragraph::new(lines).scroll((scroll as u16, 0)), rows[0]);
+
+        if let Some(text) = &self.comment {
+            frame.render_widget(
+                Paragraph::new(Line::from(vec![
+                    Span::styled("comment: ", Style::default().fg(theme.warning)),
+                    Span::styled(
+                        format!("{}▊", sanitize::inline(text)),
+                        Style::default().fg(theme.text),
+                    ),
+                ]))
+                .block(
+                    Block::default()
+                        .borders(Borders::TOP)
+                        .border_style(Style::default().fg(theme.border)),
+                ),
+                rows[1],
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan() -> Plan {
+        let steps = serde_json::from_str(
+            r#"[
+                {"title": "Read the code"},
+                {"id": "edit", "title": "Edit", "substeps": [
+                    {"title": "parser"},
+                    {"title": "tests", "status": "done"}
+                ]},
+                {"title": "Ship"}
+            ]"#,
+        )
+        .unwrap();
+        Plan::new(Some("Fix".to_string()), steps)
+    }
+
+    #[test]
+    fn numbers_steps_without_ids() {
+        let plan = plan();
+        let ids: Vec<&str> = plan.rows().iter().map(|(_, s)| s.id.as_str()).collect();
+        assert_eq!(ids, ["1", "edit", "2.1", "2.2", "3"]);
+        let depths: Vec<usize> = plan.rows().iter().map(|(d, _)| *d).collect();
+        assert_eq!(depths, [0, 0, 1, 1, 0]);
+    }
+
+    #[test]
+    fn update_finds_nested_steps() {
+        let mut plan = plan();
+        assert!(plan.update("2.1", Some(Status::InProgress), None));
+        assert!(plan.update("3", None, Some("Ship it".to_string())));
+        assert!(!plan.update("missing", Some(Status::Done), None));
+        let rows = plan.rows();
+        assert_eq!(rows[2].1.status, Status::InProgress);
+        assert_eq!(rows[4].1.title, "Ship it");
+        assert_eq!(rows[4].1.status, Status::Pending);
+    }
+
+    #[test]
+    fn progress_counts_done_and_skipped() {
+        let mut plan = plan();
+        assert_eq!(plan.progress(), (1, 5));
+        assert_eq!(plan.badge(), " ◐ Plan 1/5 ");
+        plan.update("1", Some(Status::Done), None);
+        plan.update("2.1", Some(Status::Skipped), None);
+        plan.update("edit", Some(Status::InProgress), None);
+        assert_eq!(plan.progress(), (3, 5));
+        plan.update("edit", Some(Status::Done), None);
+        plan.update("3", Some(Status::Done), None);
+        assert_eq!(plan.progress(), (5, 5));
+        assert_eq!(plan.badge(), " ✓ Plan 5/5 ");
+        assert_eq!(plan.title(), " Plan • 5/5 ");
+    }
+}
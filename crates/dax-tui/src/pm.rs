@@ -0,0 +1,659 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::keymap::{Action, Keymap};
+use crate::sanitize;
+use crate::Theme;
+
+/// Mirrors `AllowRule` in src/pm/types.ts. Older states stored bare tool
+/// names, which `loadPM` reads as tool rules; so does this.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "RawAllowRule")]
+pub struct AllowRule {
+    /// `tool` or `path`.
+    pub kind: String,
+    pub pattern: String,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawAllowRule {
+    Rule { kind: String, pattern: String },
+    Tool(String),
+}
+
+impl From<RawAllowRule> for AllowRule {
+    fn from(raw: RawAllowRule) -> Self {
+        match raw {
+            RawAllowRule::Rule { kind, pattern } => AllowRule { kind, pattern },
+            RawAllowRule::Tool(pattern) => AllowRule {
+                kind: "tool".to_string(),
+                pattern,
+            },
+        }
+    }
+}
+
+impl AllowRule {
+    fn label(&self) -> String {
+        format!("{}: {}", self.kind, self.pattern)
+    }
+}
+
+/// Mirrors `PMConstraints` in src/pm/types.ts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PmConstraints {
+    pub never_touch: Vec<String>,
+    pub require_approval_for: Vec<String>,
+    pub always_allow: Vec<AllowRule>,
+    pub max_files: Option<u64>,
+    pub max_loc: Option<u64>,
+    pub require_approval_for_scope_expansion: Option<bool>,
+}
+
+/// Mirrors `PMPreferences` in src/pm/types.ts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PmPreferences {
+    pub risk: Option<String>,
+    pub verbosity: Option<String>,
+    pub explain_before_edit: Option<bool>,
+    pub plan_before_tools: Option<bool>,
+}
+
+/// The parts of `PMState` (src/pm/types.ts) the policy panel shows.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PmState {
+    pub project_id: String,
+    pub charter: Option<String>,
+    pub constraints: PmConstraints,
+    pub preferences: PmPreferences,
+    pub last_updated: Option<String>,
+}
+
+/// Latest PM state from the host.
+#[derive(Default)]
+pub struct Policy {
+    pub state: Option<PmState>,
+}
+
+impl Policy {
+    /// Stores a new state and describes how its rules changed, so gate
+    /// approvals that add `always_allow` rules leave a trace.
+    pub fn update(&mut self, next: PmState) -> Vec<String> {
+        let mut changes = Vec::new();
+        if let Some(prev) = &self.state {
+            let (before, after) = (&prev.constraints, &next.constraints);
+            for rule in after
+                .always_allow
+                .iter()
+                .filter(|r| !before.always_allow.contains(r))
+            {
+                changes.push(format!("Policy: always allow {}", rule.label()));
+            }
+            for rule in before
+                .always_allow
+                .iter()
+                .filter(|r| !after.always_allow.contains(r))
+            {
+                changes.push(format!("Policy: revoked always allow {}", rule.label()));
+            }
+            for glob in after
+                .never_touch
+                .iter()
+                .filter(|g| !before.never_touch.contains(g))
+            {
+                changes.push(format!("Policy: never touch {}", glob));
+            }
+            for glob in before
+                .never_touch
+                .iter()
+                .filter(|g| !after.never_touch.contains(g))
+            {
+                changes.push(format!("Policy: removed never touch {}", glob));
+            }
+            if prev.preferences.risk != next.preferences.risk {
+                if let Some(risk) = &next.preferences.risk {
+                    changes.push(format!("Policy: risk posture {}", risk));
+                }
+            }
+        }
+        self.state = Some(next);
+        changes
+    }
+}
+
+/// A policy change to send to the host once confirmed.
+#[derive(Debug, Clone)]
+pub struct PmCommand {
+    pub payload: serde_json::Value,
+    pub description: String,
+}
+
+fn command(op: &str, fields: serde_json::Value, description: String) -> PmCommand {
+    let mut payload = serde_json::json!({ "type": "pm_command", "op": op });
+    if let (Some(payload), Some(fields)) = (payload.as_object_mut(), fields.as_object()) {
+        payload.extend(fields.clone());
+    }
+    PmCommand {
+        payload,
+        description,
+    }
+}
+
+/// Rows that can be selected: never-touch globs, then always-allow rules.
+#[derive(Debug, Clone)]
+enum Item {
+    NeverTouch(String),
+    AlwaysAllow(AllowRule),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EditKind {
+    AddNeverTouch,
+    EditNeverTouch,
+    AddAlwaysAllow,
+}
+
+enum Mode {
+    Browse,
+    Edit { kind: EditKind, text: String },
+    Confirm(PmCommand),
+}
+
+pub enum PolicyOutcome {
+    Continue,
+    Close,
+    Send(PmCommand),
+}
+
+/// Overlay showing PM constraints and preferences, with edits to the
+/// never-touch globs and always-allow rules.
+pub struct PolicyView {
+    selected: usize,
+    mode: Mode,
+    error: Option<String>,
+}
+
+impl PolicyView {
+    pub fn new() -> Self {
+        Self {
+            selected: 0,
+            mode: Mode::Browse,
+            error: None,
+        }
+    }
+
+    fn items(state: &PmState) -> Vec<Item> {
+        let c = &state.constraints;
+        c.never_touch
+            .iter()
+            .cloned()
+            .map(Item::NeverTouch)
+            .chain(c.always_allow.iter().cloned().map(Item::AlwaysAllow))
+            .collect()
+    }
+
+    pub fn is_editing(&self) -> bool {
+        matches!(self.mode, Mode::Edit { .. })
+    }
+
+    /// Text entry for a glob or rule; bypasses the keymap.
+    pub fn edit_key(&mut self, key: KeyEvent, state: &PmState) {
+        let Mode::Edit { kind, text } = &mut self.mode else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => self.mode = Mode::Browse,
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            KeyCode::Char(c) => text.push(c),
+            KeyCode::Enter => {
+                let (kind, text) = (*kind, text.trim().to_string());
+                match self.build(kind, &text, state) {
+                    Ok(cmd) => {
+                        self.error = None;
+                        self.mode = Mode::Confirm(cmd);
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn build(&self, kind: EditKind, text: &str, state: &PmState) -> Result<PmCommand, String> {
+        if text.is_empty() {
+            return Err("Pattern is empty".to_string());
+        }
+        match kind {
+            EditKind::AddNeverTouch => Ok(command(
+                "add_never_touch",
+                serde_json::json!({ "pattern": text }),
+                format!("add never_touch '{}'", text),
+            )),
+            EditKind::EditNeverTouch => {
+                let Some(Item::NeverTouch(from)) = Self::items(state).get(self.selected).cloned()
+                else {
+                    return Err("No glob selected".to_string());
+                };
+                Ok(command(
+                    "replace_never_touch",
+                    serde_json::json!({ "from": from, "to": text }),
+                    format!("change never_touch '{}' to '{}'", from, text),
+                ))
+            }
+            EditKind::AddAlwaysAllow => {
+                let (kind, pattern) = text
+                    .split_once(|c: char| c == ':' || c.is_whitespace())
+                    .map(|(k, p)| (k.trim(), p.trim()))
+                    .filter(|(k, p)| matches!(*k, "tool" | "path") && !p.is_empty())
+                    .ok_or("Expected 'tool <pattern>' or 'path <pattern>'")?;
+                Ok(command(
+                    "add_always_allow",
+                    serde_json::json!({ "rule": { "kind": kind, "pattern": pattern } }),
+                    format!("always allow {} '{}'", kind, pattern),
+                ))
+            }
+        }
+    }
+
+    pub fn handle_action(&mut self, action: Action, state: &PmState) -> PolicyOutcome {
+        if let Mode::Confirm(cmd) = &self.mode {
+            let cmd = cmd.clone();
+            self.mode = Mode::Browse;
+            return match action {
+                Action::PolicyConfirm => PolicyOutcome::Send(cmd),
+                _ => PolicyOutcome::Continue,
+            };
+        }
+        let items = Self::items(state);
+        self.error = None;
+        match action {
+            Action::PolicyClose => return PolicyOutcome::Close,
+            Action::PolicyDown => {
+                self.selected = (self.selected + 1).min(items.len().saturating_sub(1))
+            }
+            Action::PolicyUp => self.selected = self.selected.saturating_sub(1),
+            Action::PolicyAddAllow => {
+                self.mode = Mode::Edit {
+                    kind: EditKind::AddAlwaysAllow,
+                    text: "tool ".to_string(),
+                }
+            }
+            Action::PolicyAddNeverTouch => {
+                self.mode = Mode::Edit {
+                    kind: EditKind::AddNeverTouch,
+                    text: String::new(),
+                }
+            }
+            Action::PolicyEdit => match items.get(self.selected) {
+                Some(Item::NeverTouch(glob)) => {
+                    self.mode = Mode::Edit {
+                        kind: EditKind::EditNeverTouch,
+                        text: glob.clone(),
+                    }
+                }
+                _ => self.error = Some("Only never_touch globs can be edited".to_string()),
+            },
+            Action::PolicyRemove => {
+                let cmd = match items.get(self.selected) {
+                    Some(Item::NeverTouch(glob)) => command(
+                        "remove_never_touch",
+                        serde_json::json!({ "pattern": glob }),
+                        format!("remove never_touch '{}'", glob),
+                    ),
+                    Some(Item::AlwaysAllow(rule)) => command(
+                        "revoke_always_allow",
+                        serde_json::json!({ "rule": rule }),
+                        format!("revoke always allow {}", rule.label()),
+                    ),
+                    None => return PolicyOutcome::Continue,
+                };
+                self.mode = Mode::Confirm(cmd);
+            }
+            _ => {}
+        }
+        PolicyOutcome::Continue
+    }
+
+    pub fn render(
+        &mut self,
+        frame: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        keys: &Keymap,
+        state: Option<&PmState>,
+    ) {
+        crate::clear_area(frame, area, theme);
+        let title = match state {
+            Some(s) if !s.project_id.is_empty() => {
+                format!(" Policy • {} ", sanitize::inline(&s.project_id))
+            }
+            _ => " Policy ".to_string(),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(Span::styled(
+                title,
+                Style::default().fg(theme.accent).bold(),
+            ))
+            .title_bottom(Span::styled(
+                keys.hints(&[
+                    (Action::PolicyAddAllow, "allow rule"),
+                    (Action::PolicyAddNeverTouch, "never touch"),
+                    (Action::PolicyEdit, "edit"),
+                    (Action::PolicyRemove, "remove"),
+                    (Action::PolicyClose, "close"),
+                ]),
+                Style::default().fg(theme.dim),
+            ));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let Some(state) = state else {
+            frame.render_widget(
+                Paragraph::new("No PM state received from the host yet")
+                    .style(Style::default().fg(theme.dim)),
+                inner,
+            );
+            return;
+        };
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(2)])
+            .split(inner);
+
+        let dim = Style::default().fg(theme.dim);
+        let text = Style::default().fg(theme.text);
+        let heading = Style::default().fg(theme.accent).bold();
+        let c = &state.constraints;
+        let p = &state.preferences;
+        let limit = |n: Option<u64>| n.map(|n| n.to_string()).unwrap_or_else(|| "—".to_string());
+        let flag = |b: Option<bool>| match b {
+            Some(true) => "on",
+            Some(false) => "off",
+            None => "—",
+        };
+
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled("risk ", dim),
+                Span::styled(
+                    p.risk.as_deref().unwrap_or("balanced").to_string(),
+                    Style::default().fg(theme.warning).bold(),
+                ),
+                Span::styled("  verbosity ", dim),
+                Span::styled(p.verbosity.as_deref().unwrap_or("—").to_string(), text),
+                Span::styled("  explain before edit ", dim),
+                Span::styled(flag(p.explain_before_edit), text),
+                Span::styled("  plan before tools ", dim),
+                Span::styled(flag(p.plan_before_tools), text),
+            ]),
+            Line::from(vec![
+                Span::styled("max files ", dim),
+                Span::styled(limit(c.max_files), text),
+                Span::styled("  max LOC ", dim),
+                Span::styled(limit(c.max_loc), text),
+                Span::styled("  approve scope expansion ", dim),
+                Span::styled(flag(c.require_approval_for_scope_expansion), text),
+            ]),
+        ];
+        if let Some(charter) = state.charter.as_deref().filter(|c| !c.is_empty()) {
+            lines.push(Line::from(vec![
+                Span::styled("charter ", dim),
+                Span::styled(sanitize::inline(charter), text),
+            ]));
+        }
+
+        let items = Self::items(state);
+        self.selected = self.selected.min(items.len().saturating_sub(1));
+        let mut focus = 0;
+        let mut index = 0;
+        let mut push_items = |lines: &mut Vec<Line>, title: &str, labels: Vec<String>| {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(title.to_string(), heading)));
+            if labels.is_empty() {
+                lines.push(Line::from(Span::styled("  none", dim)));
+            }
+            for label in labels {
+                let selected = index == self.selected;
+                if selected {
+                    focus = lines.len();
+                }
+                let style = if selected {
+                    Style::default().fg(theme.accent).reversed()
+                } else {
+                    text
+                };
+                lines.push(Line::from(vec![
+                    Span::raw("  "),
+                    Span::styled(sanitize::inline(&label), style),
+                ]));
+                index += 1;
+            }
+        };
+        push_items(&mut lines, "Never touch", c.never_touch.clone());
+        push_items(
+            &mut lines,
+            "Always allow",
+            c.always_allow.iter().map(AllowRule::label).collect(),
+        );
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("Require approval for", heading)));
+        if c.require_approval_for.is_empty() {
+            lines.push(Line::from(Span::styled("  none", dim)));
+        }
+        for item in &c.require_approval_for {
+            lines.push(Line::from(Span::styled(
+                format!("  {}", sanitize::inline(item)),
+                text,
+            )));
+        }
+
+        let height = rows[0].height as usize;
+        let scroll = (focus + 2).saturating_sub(height);
+        frame.render_widget(Paragraph::new(lines).scroll((scroll as u16, 0)), rows[0]);
+
+        let footer = match &self.mode {
+            Mode::Browse => match &self.error {
+                Some(e) => Line::from(Span::styled(e.clone(), Style::default().fg(theme.error))),
+                None => Line::from(""),
+            },
+            Mode::Edit { kind, text: value } => {
+                let prompt = match kind {
+                    EditKind::AddNeverTouch => "never touch glob: ",
+                    EditKind::EditNeverTouch => "change glob to: ",
+                    EditKind::AddAlwaysAllow => "always allow (tool|path <pattern>): ",
+                };
+                let mut spans = vec![
+                    Span::styled(prompt, Style::default().fg(theme.warning)),
+                    Span::styled(format!("{}▊", sanitize::inline(value)), text),
+                ];
+                if let Some(e) = &self.error {
+                    spans.push(Span::styled(
+                        format!("  {}", e),
+                        Style::default().fg(theme.error),
+                    ));
+                }
+                Line::from(spans)
+            }
+            Mode::Confirm(cmd) => Line::from(vec![
+                Span::styled(
+                    format!("Send to host: {}? ", sanitize::inline(&cmd.description)),
+                    Style::default().fg(theme.warning).bold(),
+                ),
+                Span::styled(
+                    keys.hints(&[
+                        (Action::PolicyConfirm, "confirm"),
+                        (Action::PolicyClose, "cancel"),
+                    ]),
+                    dim,
+                ),
+            ]),
+        };
+        frame.render_widget(
+            Paragraph::new(footer).block(
+                Block::default()
+                    .borders(Borders::TOP)
+                    .border_style(Style::default().fg(theme.border)),
+            ),
+            rows[1],
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(kind: &str, pattern: &str) -> AllowRule {
+        AllowRule {
+            kind: kind.to_string(),
+            pattern: pattern.to_string(),
+        }
+    }
+
+    fn state(never_touch: &[&str], always_allow: Vec<AllowRule>, risk: Option<&str>) -> PmState {
+        PmState {
+            constraints: PmConstraints {
+                never_touch: never_touch.iter().map(|s| s.to_string()).collect(),
+                always_allow,
+                ..Default::default()
+            },
+            preferences: PmPreferences {
+                risk: risk.map(str::to_string),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn allow_rules_read_bare_tool_names() {
+        let rules: Vec<AllowRule> =
+            serde_json::from_str(r#"["bash", {"kind": "path", "pattern": "src/**"}]"#).unwrap();
+        assert_eq!(rules, [rule("tool", "bash"), rule("path", "src/**")]);
+        let written = serde_json::to_value(&rules[0]).unwrap();
+        assert_eq!(
+            written,
+            serde_json::json!({ "kind": "tool", "pattern": "bash" })
+        );
+    }
+
+    #[test]
+    fn first_update_is_silent() {
+        let mut policy = Policy::default();
+        let notes = policy.update(state(&["*.env"], vec![rule("tool", "ls")], Some("low")));
+        assert!(notes.is_empty());
+        assert!(policy.state.is_some());
+    }
+
+    #[test]
+    fn update_notes_every_rule_change() {
+        let mut policy = Policy::default();
+        policy.update(state(
+            &["*.env", "secrets/**"],
+            vec![rule("tool", "ls")],
+            Some("low"),
+        ));
+        let notes = policy.update(state(
+            &["*.env", "dist/**"],
+            vec![rule("tool", "ls"), rule("path", "src/**")],
+            Some("high"),
+        ));
+        assert_eq!(
+            notes,
+            [
+                "Policy: always allow path: src/**",
+                "Policy: never touch dist/**",
+                "Policy: removed never touch secrets/**",
+                "Policy: risk posture high",
+            ]
+        );
+        let notes = policy.update(state(&["*.env", "dist/**"], Vec::new(), Some("high")));
+        assert_eq!(
+            notes,
+            [
+                "Policy: revoked always allow tool: ls",
+                "Policy: revoked always allow path: src/**",
+            ]
+        );
+        let unchanged = policy.state.clone().unwrap();
+        assert!(policy.update(unchanged).is_empty());
+    }
+
+    fn allow(text: &str) -> Result<serde_json::Value, String> {
+        PolicyView::new()
+            .build(EditKind::AddAlwaysAllow, text, &PmState::default())
+            .map(|cmd| cmd.payload)
+    }
+
+    #[test]
+    fn build_parses_tool_and_path_rules() {
+        let expected = |kind: &str, pattern: &str| {
+            serde_json::json!({
+                "type": "pm_command",
+                "op": "add_always_allow",
+                "rule": { "kind": kind, "pattern": pattern },
+            })
+        };
+        assert_eq!(allow("tool bash").unwrap(), expected("tool", "bash"));
+        assert_eq!(allow("path:src/**").unwrap(), expected("path", "src/**"));
+        assert_eq!(allow("tool:  git *").unwrap(), expected("tool", "git *"));
+        for bad in ["bash", "file x", "tool", "path:", ""] {
+            assert!(allow(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn build_edits_the_selected_glob() {
+        let state = state(&["a/**", "b/**"], Vec::new(), None);
+        let mut view = PolicyView::new();
+        view.handle_action(Action::PolicyDown, &state);
+        let cmd = view
+            .build(EditKind::EditNeverTouch, "c/**", &state)
+            .unwrap();
+        assert_eq!(cmd.payload["op"], "replace_never_touch");
+        assert_eq!(cmd.payload["from"], "b/**");
+        assert_eq!(cmd.payload["to"], "c/**");
+        assert_eq!(cmd.description, "change never_touch 'b/**' to 'c/**'");
+
+        let empty = PmState::default();
+        assert!(view
+            .build(EditKind::EditNeverTouch, "c/**", &empty)
+            .is_err());
+    }
+
+    #[test]
+    fn removals_wait_for_confirmation() {
+        let state = state(&["a/**"], vec![rule("tool", "ls")], None);
+        let mut view = PolicyView::new();
+        view.handle_action(Action::PolicyDown, &state);
+        view.handle_action(Action::PolicyRemove, &state);
+        match view.handle_action(Action::PolicyConfirm, &state) {
+            PolicyOutcome::Send(cmd) => {
+                assert_eq!(cmd.payload["op"], "revoke_always_allow");
+                assert_eq!(cmd.payload["rule"]["pattern"], "ls");
+            }
+            _ => panic!("expected a command"),
+        }
+        view.handle_action(Action::PolicyRemove, &state);
+        assert!(matches!(
+            view.handle_action(Action::PolicyClose, &state),
+            PolicyOutcome::Continue
+        ));
+    }
+}
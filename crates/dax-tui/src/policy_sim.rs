@@ -0,0 +1,380 @@
+use std::collections::HashSet;
+use std::fs;
+
+use regex::Regex;
+use serde_json::Value;
+
+use crate::pm::{AllowRule, PmState};
+
+const USAGE: &str =
+    "usage: dax-tui policy-sim --pm <pm.json> --events <session.jsonl> [--baseline <pm.json>]";
+
+/// Arguments that name the files a tool touches, as in `filesFromArgs`
+/// (src/orchestration/gates.ts).
+const FILE_KEYS: [&str; 6] = [
+    "path",
+    "file",
+    "target",
+    "baseline_file",
+    "proposed_file",
+    "files",
+];
+
+/// A tool call recovered from a session log.
+struct ToolCall {
+    name: String,
+    args: Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Allowed,
+    Approval,
+    Blocked,
+}
+
+impl Outcome {
+    fn name(self) -> &'static str {
+        match self {
+            Outcome::Allowed => "allowed",
+            Outcome::Approval => "approval",
+            Outcome::Blocked => "blocked",
+        }
+    }
+}
+
+/// What the gates decide for one call: the outcome and the warnings
+/// (`code: matches`) behind it.
+struct Verdict {
+    outcome: Outcome,
+    warnings: Vec<String>,
+}
+
+/// Runs `dax-tui policy-sim` and returns the exit status: 0 when both
+/// policies agree, 1 when outcomes differ, 2 on bad input.
+pub fn run() -> i32 {
+    let (Some(pm_path), Some(events_path)) = (crate::flag_value("pm"), crate::flag_value("events"))
+    else {
+        eprintln!("{}", USAGE);
+        return 2;
+    };
+    let baseline_path = crate::flag_value("baseline");
+    match simulate(&pm_path, &events_path, baseline_path.as_deref()) {
+        Ok(changed) => i32::from(changed),
+        Err(e) => {
+            eprintln!("policy-sim: {}", e);
+            2
+        }
+    }
+}
+
+fn load_pm(path: &str) -> Result<PmState, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    serde_json::from_str(&text).map_err(|e| format!("{}: {}", path, e))
+}
+
+/// Replays the log against both policies and prints the calls whose
+/// outcome changes. Returns whether any did.
+fn simulate(pm_path: &str, events_path: &str, baseline_path: Option<&str>) -> Result<bool, String> {
+    let policy = load_pm(pm_path)?;
+    // Without a baseline the comparison is against no constraints at all.
+    let baseline = match baseline_path {
+        Some(path) => load_pm(path)?,
+        None => PmState::default(),
+    };
+    let text = fs::read_to_string(events_path).map_err(|e| format!("{}: {}", events_path, e))?;
+    let calls = read_calls(&text);
+
+    println!("Replayed {} tool calls from {}", calls.len(), events_path);
+    println!(
+        "  baseline: {}   policy: {}",
+        baseline_path.unwrap_or("no constraints"),
+        pm_path
+    );
+
+    let mut before = [0usize; 3];
+    let mut after = [0usize; 3];
+    let mut changed = 0;
+    for (index, call) in calls.iter().enumerate() {
+        let old = evaluate(call, &baseline);
+        let new = evaluate(call, &policy);
+        before[old.outcome as usize] += 1;
+        after[new.outcome as usize] += 1;
+        if old.outcome == new.outcome {
+            continue;
+        }
+        changed += 1;
+        println!();
+        let files = files_from_args(&call.args);
+        if files.is_empty() {
+            println!("#{} {}", index + 1, call.name);
+        } else {
+            println!("#{} {} {}", index + 1, call.name, files.join(", "));
+        }
+        for (sign, verdict) in [("-", &old), ("+", &new)] {
+            if verdict.warnings.is_empty() {
+                println!("  {} {}", sign, verdict.outcome.name());
+            }
+            for warning in &verdict.warnings {
+                println!("  {} {:<9} {}", sign, verdict.outcome.name(), warning);
+            }
+        }
+    }
+
+    println!();
+    for outcome in [Outcome::Blocked, Outcome::Approval] {
+        let (b, a) = (before[outcome as usize], after[outcome as usize]);
+        println!(
+            "{:<9} {} → {} ({:+})",
+            outcome.name(),
+            b,
+            a,
+            a as i64 - b as i64
+        );
+    }
+    println!("{} of {} calls change outcome", changed, calls.len());
+    Ok(changed > 0)
+}
+
+/// Tool calls in log order. Accepts the TUI protocol (`dispatch` of a
+/// `tool_call` event), bare stream events, and provider-style calls
+/// (`{function: {name, arguments}}`, alone or in a `tool_calls` list).
+/// Lines that aren't JSON are skipped; a call seen twice by id counts once.
+fn read_calls(text: &str) -> Vec<ToolCall> {
+    let mut calls = Vec::new();
+    let mut seen = HashSet::new();
+    for (number, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<Value>(line) {
+            Ok(value) => collect(&value, &mut calls, &mut seen),
+            Err(e) => eprintln!("policy-sim: skipping line {}: {}", number + 1, e),
+        }
+    }
+    calls
+}
+
+fn collect(value: &Value, calls: &mut Vec<ToolCall>, seen: &mut HashSet<String>) {
+    let mut push = |id: &Value, name: &Value, args: &Value| {
+        let Some(name) = name.as_str() else {
+            return;
+        };
+        if let Some(id) = id.as_str() {
+            if !seen.insert(id.to_string()) {
+                return;
+            }
+        }
+        calls.push(ToolCall {
+            name: name.to_string(),
+            args: parse_args(args),
+        });
+    };
+    match value.get("type").and_then(Value::as_str) {
+        Some("dispatch") => return collect(&value["event"], calls, seen),
+        Some("tool_call") => {
+            let data = &value["data"];
+            return push(&data["id"], &data["name"], &data["arguments"]);
+        }
+        _ => {}
+    }
+    if let Some(function) = value.get("function") {
+        push(&value["id"], &function["name"], &function["arguments"]);
+    }
+    if let Some(list) = value.get("tool_calls").and_then(Value::as_array) {
+        for call in list {
+            collect(call, calls, seen);
+        }
+    }
+}
+
+/// Arguments arrive as a JSON string or an object; anything unreadable is
+/// treated as no arguments, like the host does.
+fn parse_args(args: &Value) -> Value {
+    match args {
+        Value::String(text) => serde_json::from_str(text).unwrap_or(Value::Null),
+        Value::Object(_) => args.clone(),
+        _ => Value::Null,
+    }
+}
+
+fn files_from_args(args: &Value) -> Vec<String> {
+    FILE_KEYS
+        .iter()
+        .flat_map(|key| match args.get(key) {
+            Some(Value::String(s)) if !s.is_empty() => vec![s.clone()],
+            Some(Value::Array(items)) => items
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect(),
+            _ => Vec::new(),
+        })
+        .collect()
+}
+
+/// `matchesPattern` from src/orchestration/gates.ts: a substring match,
+/// or an unanchored regex built the same way. The host's second pass also
+/// rewrites the `*` of the `.*` it just produced for `**`; that is kept so
+/// both sides agree.
+fn matches_pattern(file: &str, pattern: &str) -> bool {
+    if !pattern.contains('*') {
+        return file.contains(pattern);
+    }
+    let source = pattern.replace("**", ".*").replace('*', "[^/]*");
+    Regex::new(&source).is_ok_and(|re| re.is_match(file))
+}
+
+fn allowed_by_tool(tool: &str, rules: &[AllowRule]) -> bool {
+    rules
+        .iter()
+        .filter(|rule| rule.kind == "tool")
+        .any(|rule| matches_pattern(tool, &rule.pattern))
+}
+
+fn allowed_by_path(files: &[String], rules: &[AllowRule]) -> bool {
+    let paths: Vec<&AllowRule> = rules.iter().filter(|rule| rule.kind == "path").collect();
+    !files.is_empty()
+        && !paths.is_empty()
+        && files.iter().all(|file| {
+            paths
+                .iter()
+                .any(|rule| matches_pattern(file, &rule.pattern))
+        })
+}
+
+/// The per-call part of `evaluateGates`: never_touch blocks outright,
+/// always_allow skips approval, then path and tool approval patterns.
+fn evaluate(call: &ToolCall, pm: &PmState) -> Verdict {
+    let c = &pm.constraints;
+    let files = files_from_args(&call.args);
+    let pairs = |patterns: &[String]| -> Vec<String> {
+        files
+            .iter()
+            .flat_map(|file| {
+                patterns
+                    .iter()
+                    .filter(|pattern| matches_pattern(file, pattern))
+                    .map(move |pattern| format!("{} -> {}", pattern, file))
+            })
+            .collect()
+    };
+
+    let restricted = pairs(&c.never_touch);
+    if !restricted.is_empty() {
+        return Verdict {
+            outcome: Outcome::Blocked,
+            warnings: vec![format!("never_touch.path: {}", restricted.join(", "))],
+        };
+    }
+    if allowed_by_tool(&call.name, &c.always_allow) || allowed_by_path(&files, &c.always_allow) {
+        return Verdict {
+            outcome: Outcome::Allowed,
+            warnings: Vec::new(),
+        };
+    }
+
+    let mut warnings = Vec::new();
+    let paths = pairs(&c.require_approval_for);
+    if !paths.is_empty() {
+        warnings.push(format!("require_approval.path: {}", paths.join(", ")));
+    }
+    let tools: Vec<&str> = c
+        .require_approval_for
+        .iter()
+        .filter(|pattern| call.name.contains(pattern.as_str()))
+        .map(String::as_str)
+        .collect();
+    if !tools.is_empty() {
+        warnings.push(format!("require_approval.tool: {}", tools.join(", ")));
+    }
+    Verdict {
+        outcome: if warnings.is_empty() {
+            Outcome::Allowed
+        } else {
+            Outcome::Approval
+        },
+        warnings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pm(constraints: Value) -> PmState {
+        serde_json::from_value(serde_json::json!({
+            "project_id": "p",
+            "constraints": constraints,
+            "preferences": {},
+        }))
+        .unwrap()
+    }
+
+    fn call(name: &str, args: Value) -> ToolCall {
+        ToolCall {
+            name: name.to_string(),
+            args,
+        }
+    }
+
+    #[test]
+    fn reads_calls_from_every_log_shape() {
+        let log = [
+            r#"{"type":"dispatch","event":{"type":"tool_call","data":{"id":"a","name":"read","arguments":"{\"path\":\"x\"}"}}}"#,
+            r#"{"type":"tool_call","data":{"id":"a","name":"read"}}"#,
+            r#"{"id":"b","function":{"name":"write","arguments":{"path":"y"}}}"#,
+            r#"{"tool_calls":[{"id":"c","function":{"name":"bash","arguments":"nope"}}]}"#,
+            "not json",
+        ]
+        .join("\n");
+        let calls = read_calls(&log);
+        let names: Vec<&str> = calls.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["read", "write", "bash"]);
+        assert_eq!(calls[0].args["path"], "x");
+        assert_eq!(calls[1].args["path"], "y");
+        assert!(calls[2].args.is_null());
+    }
+
+    #[test]
+    fn matches_patterns_like_the_host() {
+        assert!(matches_pattern("src/main.rs", "main"));
+        assert!(matches_pattern("src/a/b.rs", "src/**"));
+        assert!(matches_pattern("src/a.rs", "src/*.rs"));
+        assert!(!matches_pattern("lib/a.rs", "src/*.rs"));
+    }
+
+    #[test]
+    fn never_touch_beats_always_allow() {
+        let pm = pm(serde_json::json!({
+            "never_touch": [".env"],
+            "always_allow": [{"kind": "tool", "pattern": "write"}],
+        }));
+        let verdict = evaluate(&call("write", serde_json::json!({"path": ".env"})), &pm);
+        assert_eq!(verdict.outcome, Outcome::Blocked);
+        assert_eq!(verdict.warnings, ["never_touch.path: .env -> .env"]);
+    }
+
+    #[test]
+    fn always_allow_skips_approval() {
+        let pm = pm(serde_json::json!({
+            "require_approval_for": ["src/**", "bash"],
+            "always_allow": ["bash", {"kind": "path", "pattern": "src/gen/**"}],
+        }));
+        let allowed = evaluate(&call("bash", Value::Null), &pm);
+        assert_eq!(allowed.outcome, Outcome::Allowed);
+        let generated = evaluate(
+            &call("write", serde_json::json!({"path": "src/gen/a.rs"})),
+            &pm,
+        );
+        assert_eq!(generated.outcome, Outcome::Allowed);
+        let source = evaluate(
+            &call("write", serde_json::json!({"files": ["src/a.rs"]})),
+            &pm,
+        );
+        assert_eq!(source.outcome, Outcome::Approval);
+        assert_eq!(
+            source.warnings,
+            ["require_approval.path: src/** -> src/a.rs"]
+        );
+    }
+}
@@ -0,0 +1,612 @@
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::export::code_span;
+use crate::keymap::{Action, Keymap};
+use crate::sanitize;
+use crate::Theme;
+
+/// `run` in `RaoSnapshot` (src/pm/types.ts).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunInfo {
+    pub tool: String,
+    pub targets: Vec<String>,
+    pub ok: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditWarning {
+    pub code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    pub message: String,
+}
+
+/// `audit` in `RaoSnapshot`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditInfo {
+    pub blocked: bool,
+    pub warnings: Vec<AuditWarning>,
+}
+
+/// `override` in `RaoSnapshot`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OverrideInfo {
+    pub event_id: String,
+    pub changed_keys: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+}
+
+/// Mirrors `RaoSnapshot` in src/pm/types.ts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub id: String,
+    pub ts: String,
+    /// `run`, `audit`, `override` or `clear`.
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run: Option<RunInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audit: Option<AuditInfo>,
+    #[serde(default, rename = "override", skip_serializing_if = "Option::is_none")]
+    pub override_: Option<OverrideInfo>,
+}
+
+impl Snapshot {
+    /// Local time of the snapshot, or the raw timestamp if it isn't RFC 3339.
+    fn time(&self) -> String {
+        chrono::DateTime::parse_from_rfc3339(&self.ts)
+            .map(|t| {
+                t.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_else(|_| self.ts.clone())
+    }
+
+    /// One-line description for the list.
+    fn summary(&self) -> String {
+        match self.kind.as_str() {
+            "run" => match &self.run {
+                Some(run) => format!(
+                    "{} {} {}",
+                    run.tool,
+                    if run.ok { "ok" } else { "failed" },
+                    run.targets.join(", ")
+                ),
+                None => "run".to_string(),
+            },
+            "audit" => match &self.audit {
+                Some(audit) if audit.blocked => {
+                    format!("blocked • {} warnings", audit.warnings.len())
+                }
+                Some(audit) => format!("{} warnings", audit.warnings.len()),
+                None => "audit".to_string(),
+            },
+            "override" => match &self.override_ {
+                Some(o) => format!("changed {}", o.changed_keys.join(", ")),
+                None => "override".to_string(),
+            },
+            "clear" => "history cleared".to_string(),
+            other => other.to_string(),
+        }
+    }
+}
+
+/// Which snapshot kinds the list shows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Filter {
+    #[default]
+    All,
+    Run,
+    Audit,
+    Override,
+    Clear,
+}
+
+impl Filter {
+    fn name(self) -> &'static str {
+        match self {
+            Filter::All => "all",
+            Filter::Run => "run",
+            Filter::Audit => "audit",
+            Filter::Override => "override",
+            Filter::Clear => "clear",
+        }
+    }
+
+    fn next(self) -> Filter {
+        match self {
+            Filter::All => Filter::Run,
+            Filter::Run => Filter::Audit,
+            Filter::Audit => Filter::Override,
+            Filter::Override => Filter::Clear,
+            Filter::Clear => Filter::All,
+        }
+    }
+
+    fn matches(self, snapshot: &Snapshot) -> bool {
+        self == Filter::All || snapshot.kind == self.name()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Markdown,
+}
+
+pub enum RaoOutcome {
+    Continue,
+    Close,
+    Export(ExportFormat),
+}
+
+/// Browser over the RAO snapshot history: a chronological list filtered by
+/// kind, with the selected snapshot's details beside it.
+pub struct HistoryView {
+    filter: Filter,
+    /// Index into the filtered list; `None` follows the newest snapshot.
+    selected: Option<usize>,
+}
+
+impl HistoryView {
+    pub fn new() -> Self {
+        Self {
+            filter: Filter::All,
+            selected: None,
+        }
+    }
+
+    /// Snapshots the current filter shows, oldest first.
+    pub fn visible<'a>(&self, history: &'a [Snapshot]) -> Vec<&'a Snapshot> {
+        history.iter().filter(|s| self.filter.matches(s)).collect()
+    }
+
+    fn index(&self, count: usize) -> usize {
+        self.selected
+            .unwrap_or(count.saturating_sub(1))
+            .min(count.saturating_sub(1))
+    }
+
+    pub fn handle_action(&mut self, action: Action, history: &[Snapshot]) -> RaoOutcome {
+        let count = self.visible(history).len();
+        let index = self.index(count);
+        match action {
+            Action::RaoClose => return RaoOutcome::Close,
+            Action::RaoDown => self.selected = Some((index + 1).min(count.saturating_sub(1))),
+            Action::RaoUp => self.selected = Some(index.saturating_sub(1)),
+            Action::RaoTop => self.selected = Some(0),
+            Action::RaoBottom => self.selected = None,
+            Action::RaoFilter => {
+                self.filter = self.filter.next();
+                self.selected = None;
+            }
+            Action::RaoExportJson => return RaoOutcome::Export(ExportFormat::Json),
+            Action::RaoExportMarkdown => return RaoOutcome::Export(ExportFormat::Markdown),
+            _ => {}
+        }
+        RaoOutcome::Continue
+    }
+
+    /// Writes the filtered snapshots to `dax-rao-<time>.json|md` in the
+    /// working directory, never over an existing file, and returns the path.
+    pub fn export(&self, history: &[Snapshot], format: ExportFormat) -> std::io::Result<PathBuf> {
+        let snapshots = self.visible(history);
+        let now = chrono::Local::now();
+        let (extension, body) = match format {
+            ExportFormat::Json => (
+                "json",
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "exported_at": now.to_rfc3339(),
+                    "filter": self.filter.name(),
+                    "history": snapshots,
+                }))?,
+            ),
+            ExportFormat::Markdown => ("md", markdown(&snapshots, self.filter, &now)),
+        };
+        crate::export::write_unique(
+            &format!("dax-rao-{}", now.format("%Y%m%d-%H%M%S")),
+            extension,
+            &body,
+        )
+    }
+
+    pub fn render(
+        &mut self,
+        frame: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        keys: &Keymap,
+        history: &[Snapshot],
+    ) {
+        crate::clear_area(frame, area, theme);
+        let snapshots = self.visible(history);
+        let index = self.index(snapshots.len());
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(Span::styled(
+                format!(
+                    " RAO history • {} • {}/{} ",
+                    self.filter.name(),
+                    snapshots.len(),
+                    history.len()
+                ),
+                Style::default().fg(theme.accent).bold(),
+            ))
+            .title_bottom(Span::styled(
+                keys.hints(&[
+                    (Action::RaoFilter, "filter kind"),
+                    (Action::RaoExportJson, "export JSON"),
+                    (Action::RaoExportMarkdown, "export markdown"),
+                    (Action::RaoClose, "close"),
+                ]),
+                Style::default().fg(theme.dim),
+            ));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        if snapshots.is_empty() {
+            let text = if history.is_empty() {
+                "No RAO history received from the host yet"
+            } else {
+                "No snapshots of this kind"
+            };
+            frame.render_widget(
+                Paragraph::new(text).style(Style::default().fg(theme.dim)),
+                inner,
+            );
+            return;
+        }
+
+        let direction = if inner.width >= 90 {
+            Direction::Horizontal
+        } else {
+            Direction::Vertical
+        };
+        let panes = Layout::default()
+            .direction(direction)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(inner);
+
+        let height = panes[0].height as usize;
+        let top = (index + 1).saturating_sub(height);
+        let rows: Vec<Line> = snapshots
+            .iter()
+            .enumerate()
+            .skip(top)
+            .take(height)
+            .map(|(i, snapshot)| {
+                let selected = i == index;
+                let base = if selected {
+                    Style::default().fg(theme.accent).reversed()
+                } else {
+                    Style::default().fg(theme.text)
+                };
+                Line::from(vec![
+                    Span::styled(
+                        format!("{} ", snapshot.time()),
+                        Style::default().fg(theme.dim),
+                    ),
+                    Span::styled(
+                        format!("{:<8} ", snapshot.kind),
+                        Style::default().fg(kind_color(&snapshot.kind, theme)),
+                    ),
+                    Span::styled(sanitize::inline(&snapshot.summary()), base),
+                ])
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(rows), panes[0]);
+
+        let border = match direction {
+            Direction::Horizontal => Borders::LEFT,
+            Direction::Vertical => Borders::TOP,
+        };
+        frame.render_widget(
+            Paragraph::new(detail(snapshots[index], theme))
+                .wrap(Wrap { trim: false })
+                .block(
+                    Block::default()
+                        .borders(border)
+                        .border_style(Style::default().fg(theme.border)),
+                ),
+            panes[1],
+        );
+    }
+}
+
+fn kind_color(kind: &str, theme: &Theme) -> ratatui::style::Color {
+    match kind {
+        "run" => theme.success,
+        "audit" => theme.warning,
+        "override" => theme.accent,
+        _ => theme.dim,
+    }
+}
+
+/// Targets, warnings and changed keys of one snapshot.
+fn detail(snapshot: &Snapshot, theme: &Theme) -> Vec<Line<'static>> {
+    let dim = Style::default().fg(theme.dim);
+    let text = Style::default().fg(theme.text);
+    let heading = Style::default().fg(theme.accent).bold();
+    let field = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{:<10}", label), dim),
+            Span::styled(sanitize::inline(&value), text),
+        ])
+    };
+    let mut lines = vec![
+        field("id", snapshot.id.clone()),
+        field("time", snapshot.time()),
+        field("kind", snapshot.kind.clone()),
+    ];
+    if let Some(run) = &snapshot.run {
+        lines.push(Line::from(""));
+        lines.push(field("tool", run.tool.clone()));
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<10}", "result"), dim),
+            if run.ok {
+                Span::styled("ok", Style::default().fg(theme.success))
+            } else {
+                Span::styled("failed", Style::default().fg(theme.error))
+            },
+        ]));
+        lines.push(Line::from(Span::styled("Targets", heading)));
+        if run.targets.is_empty() {
+            lines.push(Line::from(Span::styled("  none", dim)));
+        }
+        for target in &run.targets {
+            lines.push(Line::from(Span::styled(
+                format!("  {}", sanitize::inline(target)),
+                text,
+            )));
+        }
+    }
+    if let Some(audit) = &snapshot.audit {
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<10}", "blocked"), dim),
+            if audit.blocked {
+                Span::styled("yes", Style::default().fg(theme.error).bold())
+            } else {
+                Span::styled("no", text)
+            },
+        ]));
+        lines.push(Line::from(Span::styled("Warnings", heading)));
+        if audit.warnings.is_empty() {
+            lines.push(Line::from(Span::styled("  none", dim)));
+        }
+        for warning in &audit.warnings {
+            let mut spans = vec![Span::styled(
+                format!("  {}", sanitize::inline(&warning.code)),
+                Style::default().fg(theme.warning),
+            )];
+            if let Some(subject) = &warning.subject {
+                spans.push(Span::styled(
+                    format!(" {}", sanitize::inline(subject)),
+                    text,
+                ));
+            }
+            lines.push(Line::from(spans));
+            lines.push(Line::from(Span::styled(
+                format!("    {}", sanitize::inline(&warning.message)),
+                dim,
+            )));
+        }
+    }
+    if let Some(o) = &snapshot.override_ {
+        lines.push(Line::from(""));
+        lines.push(field("event", o.event_id.clone()));
+        if let Some(command) = &o.command {
+            lines.push(field("command", command.clone()));
+        }
+        lines.push(Line::from(Span::styled("Changed keys", heading)));
+        for key in &o.changed_keys {
+            lines.push(Line::from(Span::styled(
+                format!("  {}", sanitize::inline(key)),
+                text,
+            )));
+        }
+    }
+    lines
+}
+
+/// Untrusted text on one line with markdown syntax escaped, so a host
+/// string can't open a code span or start a new list item.
+fn plain(text: &str) -> String {
+    let mut out = String::new();
+    for c in sanitize::inline(text).chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '#'
+        ) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// The audit trail as a markdown document, one section per snapshot.
+/// Every host-supplied field is sanitised and escaped like session exports.
+fn markdown(
+    snapshots: &[&Snapshot],
+    filter: Filter,
+    now: &chrono::DateTime<chrono::Local>,
+) -> String {
+    let mut out = String::from("# RAO audit trail\n\n");
+    let _ = writeln!(
+        out,
+        "Exported {} • filter: {} • {} snapshots\n",
+        now.format("%Y-%m-%d %H:%M:%S %Z"),
+        filter.name(),
+        snapshots.len()
+    );
+    for snapshot in snapshots {
+        let _ = writeln!(
+            out,
+            "## {} • {} • {}\n",
+            plain(&snapshot.time()),
+            plain(&snapshot.kind),
+            code_span(&snapshot.id)
+        );
+        if let Some(run) = &snapshot.run {
+            let _ = writeln!(
+                out,
+                "- Tool: {} ({})",
+                code_span(&run.tool),
+                if run.ok { "ok" } else { "failed" }
+            );
+            let _ = writeln!(out, "- Targets:");
+            for target in &run.targets {
+                let _ = writeln!(out, "  - {}", code_span(target));
+            }
+        }
+        if let Some(audit) = &snapshot.audit {
+            let _ = writeln!(
+                out,
+                "- Blocked: {}",
+                if audit.blocked { "yes" } else { "no" }
+            );
+            let _ = writeln!(out, "- Warnings:");
+            for warning in &audit.warnings {
+                match &warning.subject {
+                    Some(subject) => {
+                        let _ = writeln!(
+                            out,
+                            "  - {} {}: {}",
+                            code_span(&warning.code),
+                            plain(subject),
+                            plain(&warning.message)
+                        );
+                    }
+                    None => {
+                        let _ = writeln!(
+                            out,
+                            "  - {}: {}",
+                            code_span(&warning.code),
+                            plain(&warning.message)
+                        );
+                    }
+                }
+            }
+        }
+        if let Some(o) = &snapshot.override_ {
+            let _ = writeln!(out, "- Event: {}", code_span(&o.event_id));
+            if let Some(command) = &o.command {
+                let _ = writeln!(out, "- Command: {}", code_span(command));
+            }
+            let keys: Vec<String> = o.changed_keys.iter().map(|k| plain(k)).collect();
+            let _ = writeln!(out, "- Changed keys: {}", keys.join(", "));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history() -> Vec<Snapshot> {
+        serde_json::from_str(
+            r#"[
+                {"id":"r1","ts":"2026-01-01T10:00:00Z","kind":"run","run":{"tool":"write_file","targets":["src/a.rs"],"ok":true}},
+                {"id":"a1","ts":"2026-01-01T10:01:00Z","kind":"audit","audit":{"blocked":true,"warnings":[{"code":"never_touch","subject":"src/auth/x.rs","message":"protected path"}]}},
+                {"id":"o1","ts":"2026-01-01T10:02:00Z","kind":"override","override":{"event_id":"e1","changed_keys":["risk"]}},
+                {"id":"r2","ts":"2026-01-01T10:03:00Z","kind":"run","run":{"tool":"bash","targets":[],"ok":false}}
+            ]"#,
+        )
+        .unwrap()
+    }
+
+    fn ids(view: &HistoryView, history: &[Snapshot]) -> Vec<String> {
+        view.visible(history).iter().map(|s| s.id.clone()).collect()
+    }
+
+    #[test]
+    fn filters_by_kind() {
+        let history = history();
+        let mut view = HistoryView::new();
+        assert_eq!(ids(&view, &history).len(), 4);
+        view.handle_action(Action::RaoFilter, &history);
+        assert_eq!(ids(&view, &history), ["r1", "r2"]);
+        view.handle_action(Action::RaoFilter, &history);
+        assert_eq!(ids(&view, &history), ["a1"]);
+        for _ in 0..3 {
+            view.handle_action(Action::RaoFilter, &history);
+        }
+        assert_eq!(view.filter, Filter::All);
+    }
+
+    #[test]
+    fn selection_follows_the_newest_and_stays_in_range() {
+        let history = history();
+        let mut view = HistoryView::new();
+        assert_eq!(view.index(history.len()), 3);
+        view.handle_action(Action::RaoDown, &history);
+        assert_eq!(view.index(history.len()), 3);
+        view.handle_action(Action::RaoTop, &history);
+        view.handle_action(Action::RaoUp, &history);
+        assert_eq!(view.index(history.len()), 0);
+        view.handle_action(Action::RaoFilter, &history);
+        assert_eq!(view.selected, None);
+        assert_eq!(view.index(2), 1);
+        assert!(matches!(
+            view.handle_action(Action::RaoExportMarkdown, &history),
+            RaoOutcome::Export(ExportFormat::Markdown)
+        ));
+    }
+
+    #[test]
+    fn writes_markdown_sections() {
+        let history = history();
+        let snapshots: Vec<&Snapshot> = history.iter().collect();
+        let out = markdown(&snapshots, Filter::All, &chrono::Local::now());
+        assert!(out.starts_with("# RAO audit trail\n"));
+        assert!(out.contains("filter: all • 4 snapshots"));
+        assert!(out.contains("- Tool: `write_file` (ok)\n- Targets:\n  - `src/a.rs`\n"));
+        assert!(out.contains("- Blocked: yes\n"));
+        assert!(out.contains("  - `never_touch` src/auth/x.rs: protected path\n"));
+        assert!(out.contains("- Event: `e1`\n- Changed keys: risk\n"));
+        assert_eq!(out.matches("\n## ").count(), 4);
+    }
+
+    #[test]
+    fn escapes_host_strings_in_markdown() {
+        let history: Vec<Snapshot> = serde_json::from_str(
+            r#"[
+                {"id":"r1","ts":"2026-01-01T10:00:00Z","kind":"run","run":{"tool":"a`b","targets":["x\n- injected","`tick"],"ok":true}},
+                {"id":"a1","ts":"2026-01-01T10:01:00Z","kind":"audit","audit":{"blocked":false,"warnings":[{"code":"c","subject":"*s*","message":"line\n## heading \u001b[31mred"}]}},
+                {"id":"o1","ts":"2026-01-01T10:02:00Z","kind":"override","override":{"event_id":"e1","command":"rm ``x``","changed_keys":["a\nb"]}}
+            ]"#,
+        )
+        .unwrap();
+        let snapshots: Vec<&Snapshot> = history.iter().collect();
+        let out = markdown(&snapshots, Filter::All, &chrono::Local::now());
+        assert!(!out.contains('\x1b'));
+        assert!(!out.contains("\n- injected"));
+        assert!(!out.contains("\n## heading"));
+        assert!(out.contains("- Tool: ``a`b`` (ok)\n"));
+        assert!(out.contains("  - `` `tick ``\n"));
+        assert!(out.contains("  - `c` \\*s\\*: line␊\\#\\# heading"));
+        assert!(out.contains("- Command: ``` rm ``x`` ```\n"));
+        assert!(out.contains("- Changed keys: a␊b\n"));
+        assert_eq!(out.matches("\n## ").count(), 3);
+    }
+}
@@ -0,0 +1,405 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use crate::diff::{self, FileDiff};
+use crate::keymap::{Action, Keymap};
+use crate::sanitize;
+use crate::Theme;
+
+const HUNK_LIST_WIDTH: u16 = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Pending,
+    Accepted,
+    Rejected,
+}
+
+impl Verdict {
+    fn name(self) -> &'static str {
+        match self {
+            Verdict::Pending => "pending",
+            Verdict::Accepted => "accepted",
+            Verdict::Rejected => "rejected",
+        }
+    }
+}
+
+/// One reviewable hunk. `id` is `<tool_id>:<file index>:<hunk index>`, which
+/// the host can map back onto the diff it sent for that tool call.
+pub struct ReviewHunk {
+    pub id: String,
+    pub file: FileDiff,
+    pub verdict: Verdict,
+    pub comment: Option<String>,
+}
+
+impl ReviewHunk {
+    fn header(&self) -> &str {
+        self.file.hunks.first().map_or("", |h| h.header.as_str())
+    }
+}
+
+pub enum ReviewOutcome {
+    Continue,
+    Cancel,
+    Submit(serde_json::Value),
+}
+
+pub struct ReviewView {
+    hunks: Vec<ReviewHunk>,
+    gate_id: Option<String>,
+    selected: usize,
+    scroll: usize,
+    /// Comment being typed for the selected hunk, if any.
+    editing: Option<String>,
+}
+
+impl ReviewView {
+    /// Splits each tool's diff into single-hunk files so every hunk can be
+    /// decided on and rendered on its own.
+    pub fn new(tools: Vec<(String, Vec<FileDiff>)>, gate_id: Option<String>) -> Self {
+        let mut hunks = Vec::new();
+        for (tool_id, files) in tools {
+            for (file_index, file) in files.iter().enumerate() {
+                for (hunk_index, hunk) in file.hunks.iter().enumerate() {
+                    hunks.push(ReviewHunk {
+                        id: format!("{}:{}:{}", tool_id, file_index, hunk_index),
+                        file: FileDiff {
+                            old_path: file.old_path.clone(),
+                            new_path: file.new_path.clone(),
+                            hunks: vec![hunk.clone()],
+                        },
+                        verdict: Verdict::Pending,
+                        comment: None,
+                    });
+                }
+            }
+        }
+        Self {
+            hunks,
+            gate_id,
+            selected: 0,
+            scroll: 0,
+            editing: None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hunks.is_empty()
+    }
+
+    fn select(&mut self, index: usize) {
+        self.selected = index.min(self.hunks.len().saturating_sub(1));
+        self.scroll = 0;
+    }
+
+    fn decide(&mut self, verdict: Verdict) {
+        if let Some(hunk) = self.hunks.get_mut(self.selected) {
+            hunk.verdict = verdict;
+        }
+        // Move on to the next undecided hunk, as reviewers usually work top-down.
+        if let Some(next) = self
+            .hunks
+            .iter()
+            .enumerate()
+            .skip(self.selected + 1)
+            .find(|(_, h)| h.verdict == Verdict::Pending)
+            .map(|(i, _)| i)
+        {
+            self.select(next);
+        }
+    }
+
+    fn result(&self) -> serde_json::Value {
+        let ids = |verdict: Verdict| -> Vec<&str> {
+            self.hunks
+                .iter()
+                .filter(|h| h.verdict == verdict)
+                .map(|h| h.id.as_str())
+                .collect()
+        };
+        let comments: Vec<serde_json::Value> = self
+            .hunks
+            .iter()
+            .filter_map(|h| {
+                h.comment.as_ref().map(|c| {
+                    serde_json::json!({
+                        "hunk_id": h.id,
+                        "path": h.file.path(),
+                        "header": h.header(),
+                        "comment": c,
+                    })
+                })
+            })
+            .collect();
+        // Where each hunk is, so the host can tell the agent which parts of
+        // the change to keep without mapping ids back onto its diffs.
+        let hunks: Vec<serde_json::Value> = self
+            .hunks
+            .iter()
+            .map(|h| {
+                serde_json::json!({
+                    "id": h.id,
+                    "path": h.file.path(),
+                    "header": h.header(),
+                    "verdict": h.verdict.name(),
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "type": "review_result",
+            "gate_id": self.gate_id,
+            "accepted": ids(Verdict::Accepted),
+            "rejected": ids(Verdict::Rejected),
+            "pending": ids(Verdict::Pending),
+            "comments": comments,
+            "hunks": hunks,
+        })
+    }
+
+    /// `2 accepted, 1 rejected, 1 comment`, leaving out what is zero.
+    pub fn summary(&self) -> String {
+        let count = |verdict: Verdict| self.hunks.iter().filter(|h| h.verdict == verdict).count();
+        let comments = self.hunks.iter().filter(|h| h.comment.is_some()).count();
+        let parts: Vec<String> = [
+            (count(Verdict::Accepted), "accepted", "accepted"),
+            (count(Verdict::Rejected), "rejected", "rejected"),
+            (count(Verdict::Pending), "pending", "pending"),
+            (comments, "comment", "comments"),
+        ]
+        .into_iter()
+        .filter(|(n, _, _)| *n > 0)
+        .map(|(n, one, many)| format!("{} {}", n, if n == 1 { one } else { many }))
+        .collect();
+        parts.join(", ")
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    /// Text entry for the comment being written; bypasses the keymap.
+    pub fn edit_key(&mut self, key: KeyEvent) {
+        if let Some(draft) = &mut self.editing {
+            match key.code {
+                KeyCode::Enter => {
+                    let text = draft.trim().to_string();
+                    if let Some(hunk) = self.hunks.get_mut(self.selected) {
+                        hunk.comment = (!text.is_empty()).then_some(text);
+                    }
+                    self.editing = None;
+                }
+                KeyCode::Esc => self.editing = None,
+                KeyCode::Backspace => {
+                    draft.pop();
+                }
+                KeyCode::Char(c) => draft.push(c),
+                _ => {}
+            }
+        }
+    }
+
+    pub fn handle_action(&mut self, action: Action) -> ReviewOutcome {
+        match action {
+            Action::ReviewClose => return ReviewOutcome::Cancel,
+            Action::ReviewNext => self.select(self.selected + 1),
+            Action::ReviewPrev => self.select(self.selected.saturating_sub(1)),
+            Action::ReviewPageDown => self.scroll += 10,
+            Action::ReviewPageUp => self.scroll = self.scroll.saturating_sub(10),
+            Action::ReviewAccept => self.decide(Verdict::Accepted),
+            Action::ReviewReject => self.decide(Verdict::Rejected),
+            Action::ReviewUndo => self.decide(Verdict::Pending),
+            Action::ReviewAcceptAll => self
+                .hunks
+                .iter_mut()
+                .for_each(|h| h.verdict = Verdict::Accepted),
+            Action::ReviewRejectAll => self
+                .hunks
+                .iter_mut()
+                .for_each(|h| h.verdict = Verdict::Rejected),
+            Action::ReviewComment => {
+                let existing = self
+                    .hunks
+                    .get(self.selected)
+                    .and_then(|h| h.comment.clone())
+                    .unwrap_or_default();
+                self.editing = Some(existing);
+            }
+            Action::ReviewSubmit => return ReviewOutcome::Submit(self.result()),
+            _ => {}
+        }
+        ReviewOutcome::Continue
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme, keys: &Keymap) {
+        crate::clear_area(frame, area, theme);
+
+        let count = |v: Verdict| self.hunks.iter().filter(|h| h.verdict == v).count();
+        let title = format!(
+            " Review • {} accepted • {} rejected • {} pending ",
+            count(Verdict::Accepted),
+            count(Verdict::Rejected),
+            count(Verdict::Pending)
+        );
+        let hint = if self.editing.is_some() {
+            " Enter save comment • Esc cancel ".to_string()
+        } else {
+            keys.hints(&[
+                (Action::ReviewAccept, "accept"),
+                (Action::ReviewReject, "reject"),
+                (Action::ReviewUndo, "undo"),
+                (Action::ReviewComment, "comment"),
+                (Action::ReviewAcceptAll, "all"),
+                (Action::ReviewSubmit, "submit"),
+                (Action::ReviewClose, "cancel"),
+            ])
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.warning))
+            .title(Span::styled(
+                title,
+                Style::default().fg(theme.warning).bold(),
+            ))
+            .title_bottom(Span::styled(hint, Style::default().fg(theme.dim)));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let panes = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(HUNK_LIST_WIDTH), Constraint::Min(0)])
+            .split(inner);
+
+        let items: Vec<ListItem> = self
+            .hunks
+            .iter()
+            .map(|h| {
+                let (icon, color) = match h.verdict {
+                    Verdict::Accepted => ("✓", theme.success),
+                    Verdict::Rejected => ("✕", theme.error),
+                    Verdict::Pending => ("○", theme.dim),
+                };
+                let header = h
+                    .file
+                    .hunks
+                    .first()
+                    .map(|hk| hk.header.as_str())
+                    .unwrap_or("");
+                let range = header
+                    .split(" @@")
+                    .next()
+                    .unwrap_or(header)
+                    .trim_start_matches("@@ ");
+                let note = if h.comment.is_some() { " ✎" } else { "" };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{} ", icon), Style::default().fg(color).bold()),
+                    Span::styled(
+                        sanitize::inline(h.file.path()),
+                        Style::default().fg(theme.text),
+                    ),
+                    Span::styled(format!(" {}", range), Style::default().fg(theme.dim)),
+                    Span::styled(note, Style::default().fg(theme.accent)),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::RIGHT)
+                    .border_style(Style::default().fg(theme.border)),
+            )
+            .highlight_style(Style::default().fg(theme.accent).reversed());
+        let mut list_state = ListState::default().with_selected(Some(self.selected));
+        frame.render_stateful_widget(list, panes[0], &mut list_state);
+
+        let Some(hunk) = self.hunks.get(self.selected) else {
+            frame.render_widget(
+                Paragraph::new("No hunks to review").style(Style::default().fg(theme.dim)),
+                panes[1],
+            );
+            return;
+        };
+
+        let mut detail = panes[1];
+        let comment = self.editing.as_ref().or(hunk.comment.as_ref());
+        if let Some(text) = comment {
+            let rows = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(3)])
+                .split(panes[1]);
+            detail = rows[0];
+            let cursor = if self.editing.is_some() { "▊" } else { "" };
+            frame.render_widget(
+                Paragraph::new(format!("{}{}", sanitize::inline(text), cursor))
+                    .style(Style::default().fg(theme.text))
+                    .block(
+                        Block::default()
+                            .borders(Borders::TOP)
+                            .border_style(Style::default().fg(theme.border))
+                            .title(Span::styled(" Comment ", Style::default().fg(theme.dim))),
+                    ),
+                rows[1],
+            );
+        }
+
+        let view = diff::file_view(&hunk.file, false, detail.width, theme);
+        self.scroll = self
+            .scroll
+            .min(view.lines.len().saturating_sub(detail.height as usize));
+        let visible: Vec<Line> = view
+            .lines
+            .into_iter()
+            .skip(self.scroll)
+            .take(detail.height as usize)
+            .collect();
+        frame.render_widget(Paragraph::new(visible), detail);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATCH: &str = "\
+--- a/src/a.rs
++++ b/src/a.rs
+@@ -1 +1 @@
+-one
++uno
+@@ -9 +9 @@
+-nine
++nueve
+";
+
+    #[test]
+    fn result_lists_each_hunk_with_its_place() {
+        let mut view = ReviewView::new(
+            vec![("call_1".to_string(), diff::parse(PATCH))],
+            Some("g1".to_string()),
+        );
+        view.decide(Verdict::Accepted);
+        view.hunks[1].comment = Some("keep nine".to_string());
+        let result = view.result();
+        assert_eq!(result["gate_id"], "g1");
+        assert_eq!(result["accepted"], serde_json::json!(["call_1:0:0"]));
+        assert_eq!(result["pending"], serde_json::json!(["call_1:0:1"]));
+        assert_eq!(
+            result["hunks"][1],
+            serde_json::json!({
+                "id": "call_1:0:1",
+                "path": "src/a.rs",
+                "header": "@@ -9 +9 @@",
+                "verdict": "pending",
+            })
+        );
+        assert_eq!(result["comments"][0]["header"], "@@ -9 +9 @@");
+        assert_eq!(view.summary(), "1 accepted, 1 pending, 1 comment");
+    }
+}
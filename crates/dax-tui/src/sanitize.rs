@@ -0,0 +1,296 @@
+use ratatui::{
+    style::Style,
+    text::{Line, Span},
+};
+use unicode_width::UnicodeWidthChar;
+
+pub const TAB_WIDTH: usize = 4;
+
+const ESC: char = '\x1b';
+const BEL: char = '\x07';
+const C1_DCS: char = '\u{90}';
+const C1_SOS: char = '\u{98}';
+const C1_CSI: char = '\u{9b}';
+const C1_ST: char = '\u{9c}';
+const C1_OSC: char = '\u{9d}';
+const C1_PM: char = '\u{9e}';
+const C1_APC: char = '\u{9f}';
+
+/// A lexical piece of untrusted terminal text.
+///
+/// Everything that could change terminal state is either surfaced as a
+/// `Csi` (so callers may interpret SGR) or collapsed into `Escape`, which
+/// renderers drop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token<'a> {
+    Text(&'a str),
+    Newline,
+    Tab,
+    /// A C0/C1 control or a bidi/format character that must be shown visibly.
+    Control(char),
+    Csi {
+        params: &'a str,
+        final_byte: char,
+    },
+    Escape,
+}
+
+pub struct Tokens<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+pub fn tokenize(src: &str) -> Tokens<'_> {
+    Tokens { src, pos: 0 }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let rest = &self.src[self.pos..];
+        let c = rest.chars().next()?;
+
+        if !is_special(c) {
+            let len = rest.find(is_special).unwrap_or(rest.len());
+            self.pos += len;
+            return Some(Token::Text(&rest[..len]));
+        }
+
+        self.pos += c.len_utf8();
+        let token = match c {
+            '\n' => Token::Newline,
+            '\r' if self.src[self.pos..].starts_with('\n') => {
+                self.pos += 1;
+                Token::Newline
+            }
+            '\t' => Token::Tab,
+            ESC => self.escape(),
+            C1_CSI => self.csi(),
+            C1_OSC | C1_DCS | C1_SOS | C1_PM | C1_APC => self.string_sequence(),
+            other => Token::Control(other),
+        };
+        Some(token)
+    }
+}
+
+impl<'a> Tokens<'a> {
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn escape(&mut self) -> Token<'a> {
+        match self.peek() {
+            Some('[') => {
+                self.pos += 1;
+                self.csi()
+            }
+            Some(']' | 'P' | 'X' | '^' | '_') => {
+                self.pos += 1;
+                self.string_sequence()
+            }
+            Some(c @ ' '..='/') => {
+                // nF sequences: intermediates followed by one final byte.
+                self.pos += c.len_utf8();
+                while let Some(c @ ' '..='/') = self.peek() {
+                    self.pos += c.len_utf8();
+                }
+                if let Some('0'..='~') = self.peek() {
+                    self.pos += 1;
+                }
+                Token::Escape
+            }
+            Some('0'..='~') => {
+                self.pos += 1;
+                Token::Escape
+            }
+            _ => Token::Control(ESC),
+        }
+    }
+
+    fn csi(&mut self) -> Token<'a> {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            match c {
+                '0'..='?' | ' '..='/' => self.pos += 1,
+                '@'..='~' => {
+                    let params = &self.src[start..self.pos];
+                    self.pos += 1;
+                    return Token::Csi {
+                        params,
+                        final_byte: c,
+                    };
+                }
+                _ => break,
+            }
+        }
+        Token::Escape
+    }
+
+    /// OSC, DCS, SOS, PM and APC run until BEL or ST. An unterminated string
+    /// is cut at the end of the line so it cannot swallow later output.
+    fn string_sequence(&mut self) -> Token<'a> {
+        while let Some(c) = self.peek() {
+            match c {
+                BEL | C1_ST => {
+                    self.pos += c.len_utf8();
+                    break;
+                }
+                ESC if self.src[self.pos + 1..].starts_with('\\') => {
+                    self.pos += 2;
+                    break;
+                }
+                '\n' => break,
+                _ => self.pos += c.len_utf8(),
+            }
+        }
+        Token::Escape
+    }
+}
+
+fn is_special(c: char) -> bool {
+    c.is_control() || is_bidi(c) || matches!(c, '\u{2028}' | '\u{2029}')
+}
+
+/// Bidirectional override, embedding and isolate characters used in
+/// Trojan Source attacks, plus the implicit direction marks.
+pub fn is_bidi(c: char) -> bool {
+    matches!(
+        c,
+        '\u{061c}' | '\u{200e}' | '\u{200f}' | '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}'
+    )
+}
+
+/// Visible stand-in for a control or format character.
+pub fn escape_char(c: char) -> String {
+    match c {
+        '\0'..='\x1f' => char::from_u32(0x2400 + c as u32)
+            .map(String::from)
+            .unwrap_or_default(),
+        '\x7f' => "␡".to_string(),
+        _ => format!("⟨U+{:04X}⟩", c as u32),
+    }
+}
+
+/// Spaces needed to reach the next tab stop from `column`.
+pub fn tab_fill(column: usize) -> usize {
+    TAB_WIDTH - column % TAB_WIDTH
+}
+
+/// Strips escape sequences and makes every other control visible, keeping
+/// newlines. Tabs are expanded to `TAB_WIDTH` stops.
+pub fn sanitize(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut column = 0;
+    for token in tokenize(text) {
+        match token {
+            Token::Text(s) => {
+                out.push_str(s);
+                column += s.chars().map(|c| c.width().unwrap_or(0)).sum::<usize>();
+            }
+            Token::Newline => {
+                out.push('\n');
+                column = 0;
+            }
+            Token::Tab => {
+                let fill = tab_fill(column);
+                out.extend(std::iter::repeat_n(' ', fill));
+                column += fill;
+            }
+            Token::Control(c) => {
+                let escaped = escape_char(c);
+                column += escaped.chars().count();
+                out.push_str(&escaped);
+            }
+            Token::Csi { .. } | Token::Escape => {}
+        }
+    }
+    out
+}
+
+/// Sanitises text that must stay on one line, such as tool names and paths.
+pub fn inline(text: &str) -> String {
+    sanitize(text).replace('\n', "␊")
+}
+
+/// Splits untrusted text into display lines. Escaped controls and flagged
+/// bidi characters are drawn with `marker` so they stand out from `style`.
+pub fn styled_lines(text: &str, style: Style, marker: Style) -> Vec<Line<'static>> {
+    styled_lines_with(text, style, marker, |current, _, _| current)
+}
+
+/// Like `styled_lines`, but hands every CSI sequence to `on_csi`, which
+/// returns the style for the text that follows it.
+pub fn styled_lines_with(
+    text: &str,
+    base: Style,
+    marker: Style,
+    mut on_csi: impl FnMut(Style, &str, char) -> Style,
+) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut style = base;
+    let mut column = 0;
+    for token in tokenize(text) {
+        match token {
+            Token::Text(s) => {
+                column += s.chars().map(|c| c.width().unwrap_or(0)).sum::<usize>();
+                spans.push(Span::styled(s.to_string(), style));
+            }
+            Token::Newline => {
+                lines.push(Line::from(std::mem::take(&mut spans)));
+                column = 0;
+            }
+            Token::Tab => {
+                let fill = tab_fill(column);
+                spans.push(Span::styled(" ".repeat(fill), style));
+                column += fill;
+            }
+            Token::Control(c) => {
+                let escaped = escape_char(c);
+                column += escaped.chars().count();
+                spans.push(Span::styled(escaped, marker));
+            }
+            Token::Csi { params, final_byte } => style = on_csi(style, params, final_byte),
+            Token::Escape => {}
+        }
+    }
+    if !spans.is_empty() {
+        lines.push(Line::from(spans));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_escape_sequences() {
+        assert_eq!(sanitize("\x1b[31mred\x1b[0m"), "red");
+        assert_eq!(sanitize("a\x1b]0;title\x07b"), "ab");
+        assert_eq!(sanitize("a\x1b]52;c;aGk=\x1b\\b"), "ab");
+        assert_eq!(sanitize("a\x1bPq#0\x1b\\b"), "ab");
+        assert_eq!(sanitize("a\u{9b}2Jb"), "ab");
+    }
+
+    #[test]
+    fn shows_controls_and_bidi() {
+        assert_eq!(sanitize("a\rb\x08c"), "a␍b␈c");
+        assert_eq!(sanitize("x\u{202e}y"), "x⟨U+202E⟩y");
+        assert_eq!(inline("one\ntwo"), "one␊two");
+    }
+
+    #[test]
+    fn expands_tabs_to_stops() {
+        assert_eq!(sanitize("ab\tc\n\td"), "ab  c\n    d");
+    }
+
+    #[test]
+    fn splits_styled_lines() {
+        let lines = styled_lines("a\x07\nb", Style::default(), Style::default());
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].to_string(), "a␇");
+        assert_eq!(lines[1].to_string(), "b");
+    }
+}
@@ -0,0 +1,160 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Wrap},
+    Frame,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::sanitize;
+use crate::Theme;
+
+/// Mirrors `ScopeMetrics` in src/scope/tracker.ts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScopeMetrics {
+    pub files_modified: Vec<String>,
+    pub total_files: u64,
+    pub total_loc: u64,
+    pub files_added: u64,
+    pub files_removed: u64,
+    pub loc_added: u64,
+    pub loc_removed: u64,
+}
+
+/// Mirrors `ScopeLimits` in src/scope/tracker.ts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScopeLimits {
+    pub max_files: u64,
+    pub max_loc: u64,
+    pub allowed_patterns: Vec<String>,
+}
+
+/// Mirrors `ScopeStatus` in src/scope/tracker.ts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScopeStatus {
+    pub within_limits: bool,
+    #[serde(default)]
+    pub metrics: ScopeMetrics,
+    #[serde(default)]
+    pub limits: ScopeLimits,
+    #[serde(default)]
+    pub touched_files_count: u64,
+    #[serde(default)]
+    pub changed_loc: u64,
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+impl ScopeStatus {
+    /// Pane title: files touched against the limit.
+    pub fn title(&self) -> String {
+        format!(
+            " Scope • {}/{} files ",
+            self.touched_files_count, self.limits.max_files
+        )
+    }
+
+    /// Budget gauges, modified files and the patterns in scope.
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme, scope: &[String]) {
+        let width = area.width as usize;
+        let mut lines = vec![
+            gauge(
+                "files",
+                self.touched_files_count,
+                self.limits.max_files,
+                width,
+                theme,
+            ),
+            gauge("LOC", self.changed_loc, self.limits.max_loc, width, theme),
+            Line::from(vec![
+                Span::styled(
+                    format!("      +{}", self.metrics.loc_added),
+                    Style::default().fg(theme.success),
+                ),
+                Span::styled(
+                    format!(" -{}", self.metrics.loc_removed),
+                    Style::default().fg(theme.error),
+                ),
+                Span::styled(
+                    format!(
+                        "  {} new, {} deleted",
+                        self.metrics.files_added, self.metrics.files_removed
+                    ),
+                    Style::default().fg(theme.dim),
+                ),
+            ]),
+        ];
+        for warning in &self.warnings {
+            lines.push(Line::from(Span::styled(
+                format!("⚠ {}", sanitize::inline(warning)),
+                Style::default().fg(theme.error).bold(),
+            )));
+        }
+        if !self.metrics.files_modified.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "Modified",
+                Style::default().fg(theme.accent),
+            )));
+            for file in &self.metrics.files_modified {
+                lines.push(Line::from(Span::styled(
+                    format!("  {}", sanitize::inline(file)),
+                    Style::default().fg(theme.text),
+                )));
+            }
+        }
+        let patterns = if scope.is_empty() {
+            &self.limits.allowed_patterns[..]
+        } else {
+            scope
+        };
+        if !patterns.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "In scope",
+                Style::default().fg(theme.accent),
+            )));
+            for pattern in patterns {
+                lines.push(Line::from(Span::styled(
+                    format!("  {}", sanitize::inline(pattern)),
+                    Style::default().fg(theme.dim),
+                )));
+            }
+        }
+        frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), area);
+    }
+}
+
+/// `files ██████░░░░ 6/10`, coloured by how much of the budget is used.
+fn gauge(label: &str, used: u64, max: u64, width: usize, theme: &Theme) -> Line<'static> {
+    let count = format!(" {}/{}", used, max);
+    let bar_width = width.saturating_sub(6 + count.len()).max(4);
+    let fill = if max == 0 {
+        if used > 0 {
+            f64::INFINITY
+        } else {
+            0.0
+        }
+    } else {
+        used as f64 / max as f64
+    };
+    let filled = ((fill.min(1.0) * bar_width as f64).round() as usize).min(bar_width);
+    let color: Color = if fill > 1.0 {
+        theme.error
+    } else if fill >= 0.8 {
+        theme.warning
+    } else {
+        theme.success
+    };
+    Line::from(vec![
+        Span::styled(format!("{:<6}", label), Style::default().fg(theme.dim)),
+        Span::styled("█".repeat(filled), Style::default().fg(color)),
+        Span::styled(
+            "░".repeat(bar_width - filled),
+            Style::default().fg(theme.border),
+        ),
+        Span::styled(count, Style::default().fg(color).bold()),
+    ])
+}
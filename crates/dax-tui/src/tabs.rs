@@ -0,0 +1,298 @@
+use std::collections::HashSet;
+
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::keymap::{Action, Keymap};
+use crate::{sanitize, AppState, Theme};
+
+/// One host session and everything shown for it. The session id is
+/// `state.session`, `None` until the host names the first session.
+pub struct Tab {
+    state: AppState,
+    /// Transcript entries added while another tab was in view.
+    unread: usize,
+}
+
+impl Tab {
+    fn name(&self) -> String {
+        match &self.state.session {
+            Some(id) if id.chars().count() > 12 => {
+                format!("{}…", id.chars().take(8).collect::<String>())
+            }
+            Some(id) => id.clone(),
+            None => "main".to_string(),
+        }
+    }
+}
+
+pub struct Tabs {
+    tabs: Vec<Tab>,
+    current: usize,
+    /// Sessions closed here; anything the host still sends for them is
+    /// dropped rather than reopening the tab.
+    closed: HashSet<String>,
+    /// A new session was asked for; the next unknown one is switched to.
+    opening: bool,
+    /// The host has named a session, so it can open and close them too.
+    multi: bool,
+}
+
+impl Tabs {
+    pub fn new(state: AppState) -> Self {
+        Self {
+            tabs: vec![Tab { state, unread: 0 }],
+            current: 0,
+            closed: HashSet::new(),
+            opening: false,
+            multi: false,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tabs.len()
+    }
+
+    pub fn current(&self) -> &AppState {
+        &self.tabs[self.current].state
+    }
+
+    pub fn current_mut(&mut self) -> &mut AppState {
+        &mut self.tabs[self.current].state
+    }
+
+    /// Whether the host runs sessions. One that never names a session
+    /// ignores `new_session`, so the keys for it are turned off.
+    pub fn multi(&self) -> bool {
+        self.multi
+    }
+
+    pub fn selected(&self) -> usize {
+        self.current
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Option<&str>, &AppState)> {
+        self.tabs
+            .iter()
+            .map(|t| (t.state.session.as_deref(), &t.state))
+    }
+
+    pub fn name_current(&mut self, session: Option<String>) {
+        self.tabs[self.current].state.session = session;
+    }
+
+    /// Adds a tab without switching to it.
+    pub fn push(&mut self, session: Option<String>, mut state: AppState) -> &mut AppState {
+        state.session = session;
+        self.tabs.push(Tab { state, unread: 0 });
+        &mut self.tabs.last_mut().expect("just pushed").state
+    }
+
+    /// Applies a host message to its session's tab. Messages without a
+    /// session go to the unnamed tab, or the one in view once every tab is
+    /// named, so a single-session host needn't send one; an unknown session
+    /// gets a new tab from `open`.
+    pub fn deliver(
+        &mut self,
+        session: Option<&str>,
+        open: impl FnOnce() -> AppState,
+        apply: impl FnOnce(&mut AppState),
+    ) {
+        let unnamed = self.tabs.iter().position(|t| t.state.session.is_none());
+        self.multi |= session.is_some();
+        let index = match session {
+            None => unnamed.unwrap_or(self.current),
+            Some(id) if self.closed.contains(id) => return,
+            Some(id) => match self
+                .tabs
+                .iter()
+                .position(|t| t.state.session.as_deref() == Some(id))
+            {
+                Some(index) => index,
+                None => match unnamed.filter(|_| !self.opening) {
+                    // The first session the host names is the one already shown.
+                    Some(index) => {
+                        self.tabs[index].state.session = Some(id.to_string());
+                        index
+                    }
+                    None => {
+                        let mut state = open();
+                        state.session = Some(id.to_string());
+                        self.tabs.push(Tab { state, unread: 0 });
+                        let index = self.tabs.len() - 1;
+                        if std::mem::take(&mut self.opening) {
+                            self.select(index);
+                        }
+                        index
+                    }
+                },
+            },
+        };
+        let tab = &mut self.tabs[index];
+        let before = tab.state.messages.len();
+        apply(&mut tab.state);
+        if index != self.current {
+            tab.unread += tab.state.messages.len().saturating_sub(before);
+        }
+    }
+
+    pub fn select(&mut self, index: usize) {
+        self.current = index.min(self.tabs.len() - 1);
+        self.tabs[self.current].unread = 0;
+    }
+
+    pub fn cycle(&mut self, forward: bool) {
+        let count = self.tabs.len();
+        let next = if forward {
+            (self.current + 1) % count
+        } else {
+            (self.current + count - 1) % count
+        };
+        self.select(next);
+    }
+
+    /// Notes that a new session was asked for, so its tab is brought to
+    /// the front when the host starts it.
+    pub fn open(&mut self) {
+        self.opening = true;
+    }
+
+    /// Closes the tab in view and returns its session. The last tab stays.
+    pub fn close(&mut self) -> Result<Option<String>, &'static str> {
+        if self.tabs.len() == 1 {
+            return Err("can't close the last session");
+        }
+        let tab = self.tabs.remove(self.current);
+        if let Some(id) = &tab.state.session {
+            self.closed.insert(id.clone());
+        }
+        self.select(self.current);
+        Ok(tab.state.session)
+    }
+
+    /// One line of tabs with their activity, gate and unread markers.
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme, keys: &Keymap) {
+        if area.height == 0 {
+            return;
+        }
+        let mut spans = Vec::new();
+        for (i, tab) in self.tabs.iter().enumerate() {
+            let state = &tab.state;
+            let style = if i == self.current {
+                Style::default().fg(theme.bg).bg(theme.accent).bold()
+            } else {
+                Style::default().fg(theme.dim)
+            };
+            spans.push(Span::styled(
+                format!(" {} {} ", i + 1, sanitize::inline(&tab.name())),
+                style,
+            ));
+            if state.gate.is_some() {
+                spans.push(Span::styled(
+                    " ⚠ gate ",
+                    Style::default().fg(theme.bg).bg(theme.warning).bold(),
+                ));
+            } else if state.error.is_some() {
+                spans.push(Span::styled("✕ ", Style::default().fg(theme.error)));
+            } else if state.turn_active() {
+                spans.push(Span::styled("● ", Style::default().fg(theme.success)));
+            }
+            if tab.unread > 0 {
+                spans.push(Span::styled(
+                    format!("+{} ", tab.unread),
+                    Style::default().fg(theme.accent).bold(),
+                ));
+            }
+            spans.push(Span::raw(" "));
+        }
+        let line = Line::from(spans);
+        let used = line.width();
+        frame.render_widget(Paragraph::new(line), area);
+
+        let hints = keys.hints(&[
+            (Action::NextSession, "switch"),
+            (Action::NewSession, "new"),
+            (Action::CloseSession, "close"),
+        ]);
+        if used + hints.chars().count() <= area.width as usize {
+            frame.render_widget(
+                Paragraph::new(
+                    Line::from(Span::styled(hints, Style::default().fg(theme.dim))).right_aligned(),
+                ),
+                area,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Role;
+
+    fn note(state: &mut AppState) {
+        state.push_system(Role::System, "x".to_string());
+    }
+
+    fn sessions(tabs: &Tabs) -> Vec<Option<&str>> {
+        tabs.iter().map(|(session, _)| session).collect()
+    }
+
+    #[test]
+    fn names_the_first_tab_then_opens_new_ones() {
+        let mut tabs = Tabs::new(AppState::default());
+        tabs.deliver(Some("a"), AppState::default, note);
+        tabs.deliver(Some("b"), AppState::default, note);
+        assert_eq!(sessions(&tabs), [Some("a"), Some("b")]);
+        assert_eq!(tabs.selected(), 0);
+        assert!(tabs.multi());
+        assert_eq!(tabs.tabs[1].unread, 1);
+    }
+
+    #[test]
+    fn unnamed_messages_follow_the_unnamed_tab() {
+        let mut tabs = Tabs::new(AppState::default());
+        tabs.open();
+        tabs.deliver(Some("b"), AppState::default, note);
+        assert_eq!(sessions(&tabs), [None, Some("b")]);
+        assert_eq!(tabs.selected(), 1);
+
+        tabs.deliver(None, AppState::default, note);
+        assert_eq!(tabs.tabs[0].state.messages.len(), 1);
+
+        // With the unnamed tab closed they go to the tab in view, not to
+        // whichever session is now first.
+        tabs.select(0);
+        tabs.close().unwrap();
+        tabs.deliver(Some("c"), AppState::default, note);
+        tabs.select(1);
+        tabs.deliver(None, AppState::default, note);
+        assert_eq!(sessions(&tabs), [Some("b"), Some("c")]);
+        assert_eq!(tabs.tabs[1].state.messages.len(), 2);
+        assert_eq!(tabs.tabs[0].state.messages.len(), 1);
+    }
+
+    #[test]
+    fn single_session_hosts_are_not_multi() {
+        let mut tabs = Tabs::new(AppState::default());
+        tabs.deliver(None, AppState::default, note);
+        assert!(!tabs.multi());
+    }
+
+    #[test]
+    fn drops_messages_for_closed_sessions() {
+        let mut tabs = Tabs::new(AppState::default());
+        tabs.deliver(Some("a"), AppState::default, note);
+        tabs.deliver(Some("b"), AppState::default, note);
+        tabs.select(1);
+        assert_eq!(tabs.close(), Ok(Some("b".to_string())));
+        tabs.deliver(Some("b"), AppState::default, note);
+        assert_eq!(sessions(&tabs), [Some("a")]);
+        assert!(tabs.close().is_err());
+    }
+}
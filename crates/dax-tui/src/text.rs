@@ -0,0 +1,51 @@
+//! Formatting shared by the panes, the status bar and exports.
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// `850ms`, `12.3s` or `4m05s`.
+pub fn duration_label(ms: u64) -> String {
+    if ms < 1_000 {
+        format!("{}ms", ms)
+    } else if ms < 60_000 {
+        format!("{:.1}s", ms as f64 / 1_000.0)
+    } else {
+        format!("{}m{:02}s", ms / 60_000, (ms % 60_000) / 1_000)
+    }
+}
+
+/// Cuts `text` to `width` columns, ending in `…` when anything was cut.
+pub fn truncate(text: &str, width: usize) -> String {
+    if UnicodeWidthStr::width(text) <= width {
+        return text.to_string();
+    }
+    let mut out = String::new();
+    for c in text.chars() {
+        if UnicodeWidthStr::width(out.as_str()) + UnicodeWidthChar::width(c).unwrap_or(0) + 1
+            > width
+        {
+            break;
+        }
+        out.push(c);
+    }
+    out + "…"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_durations() {
+        assert_eq!(duration_label(850), "850ms");
+        assert_eq!(duration_label(12_340), "12.3s");
+        assert_eq!(duration_label(245_000), "4m05s");
+    }
+
+    #[test]
+    fn truncates_to_columns() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("abcdefgh", 5), "abcd…");
+        assert_eq!(truncate("日本語テキスト", 6), "日本…");
+        assert_eq!(truncate("abc", 0), "…");
+    }
+}
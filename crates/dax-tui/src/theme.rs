@@ -0,0 +1,489 @@
+use ratatui::style::Color;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant, SystemTime};
+
+pub const BUILTIN: [&str; 4] = ["dark", "light", "high-contrast", "solarized"];
+
+const RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub bg: Color,
+    pub text: Color,
+    pub dim: Color,
+    pub border: Color,
+    pub accent: Color,
+    pub success: Color,
+    pub warning: Color,
+    pub error: Color,
+    pub user: Color,
+    pub assistant: Color,
+    /// What the terminal can display; colours from tool output are fitted to it.
+    pub depth: ColorDepth,
+}
+
+// OpenCode.ai style dark theme colors
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            bg: Color::Reset,
+            text: Color::White,
+            dim: Color::DarkGray,
+            border: Color::DarkGray,
+            accent: Color::Cyan,
+            success: Color::Green,
+            warning: Color::Yellow,
+            error: Color::Red,
+            user: Color::LightBlue,
+            assistant: Color::LightGreen,
+            depth: ColorDepth::TrueColor,
+        }
+    }
+}
+
+impl Theme {
+    pub fn builtin(name: &str) -> Option<Self> {
+        let theme = match name {
+            "dark" => Self::default(),
+            "light" => Self {
+                bg: Color::Reset,
+                text: Color::Black,
+                dim: Color::Gray,
+                border: Color::Gray,
+                accent: Color::Rgb(0x00, 0x5f, 0xaf),
+                success: Color::Rgb(0x00, 0x87, 0x00),
+                warning: Color::Rgb(0xaf, 0x5f, 0x00),
+                error: Color::Rgb(0xd7, 0x00, 0x00),
+                user: Color::Rgb(0x00, 0x5f, 0xd7),
+                assistant: Color::Rgb(0x5f, 0x00, 0x87),
+                ..Self::default()
+            },
+            "high-contrast" => Self {
+                bg: Color::Black,
+                text: Color::White,
+                dim: Color::Gray,
+                border: Color::White,
+                accent: Color::LightCyan,
+                success: Color::LightGreen,
+                warning: Color::LightYellow,
+                error: Color::LightRed,
+                user: Color::LightBlue,
+                assistant: Color::LightGreen,
+                ..Self::default()
+            },
+            "solarized" => Self {
+                bg: Color::Rgb(0x00, 0x2b, 0x36),
+                text: Color::Rgb(0x83, 0x94, 0x96),
+                dim: Color::Rgb(0x58, 0x6e, 0x75),
+                border: Color::Rgb(0x58, 0x6e, 0x75),
+                accent: Color::Rgb(0x2a, 0xa1, 0x98),
+                success: Color::Rgb(0x85, 0x99, 0x00),
+                warning: Color::Rgb(0xb5, 0x89, 0x00),
+                error: Color::Rgb(0xdc, 0x32, 0x2f),
+                user: Color::Rgb(0x26, 0x8b, 0xd2),
+                assistant: Color::Rgb(0x6c, 0x71, 0xc4),
+                ..Self::default()
+            },
+            _ => return None,
+        };
+        Some(theme)
+    }
+
+    /// Lowers every colour to what the terminal can show.
+    pub fn adapt(self, depth: ColorDepth) -> Self {
+        let f = |color| fit(color, depth);
+        Self {
+            bg: f(self.bg),
+            text: f(self.text),
+            dim: f(self.dim),
+            border: f(self.border),
+            accent: f(self.accent),
+            success: f(self.success),
+            warning: f(self.warning),
+            error: f(self.error),
+            user: f(self.user),
+            assistant: f(self.assistant),
+            depth,
+        }
+    }
+}
+
+pub fn fit(color: Color, depth: ColorDepth) -> Color {
+    match depth {
+        ColorDepth::None => Color::Reset,
+        ColorDepth::Ansi16 => to_ansi16(color),
+        ColorDepth::Ansi256 => to_ansi256(color),
+        ColorDepth::TrueColor => color,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorDepth {
+    None,
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Honours `NO_COLOR` (any non-empty value), then `COLORTERM` and `TERM`.
+    pub fn detect() -> Self {
+        let var = |name: &str| std::env::var(name).unwrap_or_default();
+        Self::from_env(&var("NO_COLOR"), &var("COLORTERM"), &var("TERM"))
+    }
+
+    fn from_env(no_color: &str, colorterm: &str, term: &str) -> Self {
+        if !no_color.is_empty() {
+            return Self::None;
+        }
+        let colorterm = colorterm.to_lowercase();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return Self::TrueColor;
+        }
+        if term == "dumb" {
+            Self::None
+        } else if term.contains("256color") {
+            Self::Ansi256
+        } else {
+            Self::Ansi16
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::None => "no color",
+            Self::Ansi16 => "16 colors",
+            Self::Ansi256 => "256 colors",
+            Self::TrueColor => "truecolor",
+        }
+    }
+}
+
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// RGB value of a colour, `None` for the terminal's default.
+pub fn rgb(color: Color) -> Option<(u8, u8, u8)> {
+    match color {
+        Color::Rgb(r, g, b) => Some((r, g, b)),
+        Color::Indexed(i) => Some(indexed_rgb(i)),
+        named => ANSI16
+            .iter()
+            .find(|(c, _)| *c == named)
+            .map(|(_, rgb)| *rgb),
+    }
+}
+
+fn indexed_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI16[index as usize].1,
+        16..=231 => {
+            let i = index - 16;
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            (level(i / 36), level((i / 6) % 6), level(i % 6))
+        }
+        _ => {
+            let v = 8 + (index - 232) * 10;
+            (v, v, v)
+        }
+    }
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+fn to_ansi16(color: Color) -> Color {
+    let rgb = match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Indexed(i) => indexed_rgb(i),
+        other => return other,
+    };
+    ANSI16
+        .iter()
+        .min_by_key(|(_, candidate)| distance(rgb, *candidate))
+        .map(|(c, _)| *c)
+        .unwrap_or(Color::Reset)
+}
+
+fn to_ansi256(color: Color) -> Color {
+    let Color::Rgb(r, g, b) = color else {
+        return color;
+    };
+    (16..=255u8)
+        .min_by_key(|&i| distance((r, g, b), indexed_rgb(i)))
+        .map(Color::Indexed)
+        .unwrap_or(color)
+}
+
+/// A theme file in `<config dir>/themes/<name>.toml`. Any colour left out
+/// comes from `extends`, or from the built-in theme of the same name, or
+/// from the dark theme.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    extends: Option<String>,
+    bg: Option<String>,
+    text: Option<String>,
+    dim: Option<String>,
+    border: Option<String>,
+    accent: Option<String>,
+    success: Option<String>,
+    warning: Option<String>,
+    error: Option<String>,
+    user: Option<String>,
+    assistant: Option<String>,
+}
+
+fn themes_dir() -> Option<PathBuf> {
+    crate::config::config_dir().map(|dir| dir.join("themes"))
+}
+
+fn parse_file(path: &Path, name: &str) -> Result<Theme, String> {
+    let raw = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    parse(&raw, name).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn parse(raw: &str, name: &str) -> Result<Theme, String> {
+    let file: ThemeFile = toml::from_str(raw).map_err(|e| e.message().to_string())?;
+
+    let base_name = file.extends.as_deref().unwrap_or(name);
+    let mut theme = match (Theme::builtin(base_name), &file.extends) {
+        (Some(theme), _) => theme,
+        (None, Some(extends)) => return Err(format!("unknown theme '{}' in extends", extends)),
+        (None, None) => Theme::default(),
+    };
+
+    let mut errors = Vec::new();
+    let mut set = |slot: &mut Color, key: &str, value: &Option<String>| {
+        if let Some(value) = value {
+            match Color::from_str(value) {
+                Ok(color) => *slot = color,
+                Err(_) => errors.push(format!("{} = \"{}\"", key, value)),
+            }
+        }
+    };
+    set(&mut theme.bg, "bg", &file.bg);
+    set(&mut theme.text, "text", &file.text);
+    set(&mut theme.dim, "dim", &file.dim);
+    set(&mut theme.border, "border", &file.border);
+    set(&mut theme.accent, "accent", &file.accent);
+    set(&mut theme.success, "success", &file.success);
+    set(&mut theme.warning, "warning", &file.warning);
+    set(&mut theme.error, "error", &file.error);
+    set(&mut theme.user, "user", &file.user);
+    set(&mut theme.assistant, "assistant", &file.assistant);
+    if !errors.is_empty() {
+        return Err(format!("invalid colour {}", errors.join(", ")));
+    }
+    Ok(theme)
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Owns the active theme: resolves names to built-ins or theme files,
+/// adapts colours to the terminal and reloads the file when it changes.
+pub struct ThemeManager {
+    pub name: String,
+    pub depth: ColorDepth,
+    theme: Theme,
+    path: Option<PathBuf>,
+    mtime: Option<SystemTime>,
+    last_check: Instant,
+}
+
+impl ThemeManager {
+    pub fn new(depth: ColorDepth) -> Self {
+        Self {
+            name: "dark".to_string(),
+            depth,
+            theme: Theme::default().adapt(depth),
+            path: None,
+            mtime: None,
+            last_check: Instant::now(),
+        }
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// Built-in names followed by any extra theme files.
+    pub fn available(&self) -> Vec<String> {
+        let mut names: Vec<String> = BUILTIN.iter().map(|s| s.to_string()).collect();
+        if let Some(entries) = themes_dir().and_then(|d| std::fs::read_dir(d).ok()) {
+            let mut extra: Vec<String> = entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "toml"))
+                .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().into_owned()))
+                .filter(|n| !names.contains(n))
+                .collect();
+            extra.sort();
+            names.extend(extra);
+        }
+        names
+    }
+
+    /// Switches to `name`. On error the current theme is kept. Names are
+    /// file stems, so anything that could leave the themes directory is
+    /// refused.
+    pub fn select(&mut self, name: &str) -> Result<(), String> {
+        if name.is_empty() || name.contains("..") || name.contains(std::path::is_separator) {
+            return Err(format!("invalid theme name '{}'", name));
+        }
+        let path = themes_dir()
+            .map(|d| d.join(format!("{}.toml", name)))
+            .filter(|p| p.is_file());
+        let theme = match &path {
+            Some(path) => parse_file(path, name)?,
+            None => Theme::builtin(name).ok_or_else(|| {
+                format!(
+                    "unknown theme '{}' (available: {})",
+                    name,
+                    self.available().join(", ")
+                )
+            })?,
+        };
+        self.name = name.to_string();
+        self.theme = theme.adapt(self.depth);
+        self.mtime = path.as_deref().and_then(modified);
+        self.path = path;
+        Ok(())
+    }
+
+    /// Re-reads the theme file if it changed on disk. Returns the parse
+    /// error, if any, so it can be surfaced; the previous theme stays active.
+    pub fn poll(&mut self) -> Option<String> {
+        if self.last_check.elapsed() < RELOAD_INTERVAL {
+            return None;
+        }
+        self.last_check = Instant::now();
+        let path = self.path.clone()?;
+        let mtime = modified(&path);
+        if mtime == self.mtime {
+            return None;
+        }
+        self.mtime = mtime;
+        match parse_file(&path, &self.name) {
+            Ok(theme) => {
+                self.theme = theme.adapt(self.depth);
+                None
+            }
+            Err(e) => Some(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_honours_no_color_first() {
+        assert_eq!(
+            ColorDepth::from_env("1", "truecolor", "xterm-256color"),
+            ColorDepth::None
+        );
+        assert_eq!(
+            ColorDepth::from_env("", "truecolor", "xterm"),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            ColorDepth::from_env("", "24BIT", "xterm"),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            ColorDepth::from_env("", "", "xterm-256color"),
+            ColorDepth::Ansi256
+        );
+        assert_eq!(ColorDepth::from_env("", "", "xterm"), ColorDepth::Ansi16);
+        assert_eq!(ColorDepth::from_env("", "", "dumb"), ColorDepth::None);
+    }
+
+    #[test]
+    fn fit_lowers_to_the_terminal_depth() {
+        let orange = Color::Rgb(0xff, 0x87, 0x00);
+        assert_eq!(fit(orange, ColorDepth::TrueColor), orange);
+        assert_eq!(fit(orange, ColorDepth::Ansi256), Color::Indexed(208));
+        assert_eq!(fit(orange, ColorDepth::None), Color::Reset);
+        assert_eq!(fit(Color::Cyan, ColorDepth::Ansi256), Color::Cyan);
+    }
+
+    #[test]
+    fn ansi16_picks_the_nearest_named_colour() {
+        assert_eq!(to_ansi16(Color::Rgb(250, 5, 5)), Color::LightRed);
+        assert_eq!(to_ansi16(Color::Rgb(0, 0, 0)), Color::Black);
+        assert_eq!(to_ansi16(Color::Indexed(231)), Color::White);
+        assert_eq!(to_ansi16(Color::Reset), Color::Reset);
+    }
+
+    #[test]
+    fn ansi256_maps_cube_and_grey_ramp() {
+        assert_eq!(to_ansi256(Color::Rgb(0x5f, 0x87, 0xaf)), Color::Indexed(67));
+        assert_eq!(
+            to_ansi256(Color::Rgb(0x80, 0x80, 0x80)),
+            Color::Indexed(244)
+        );
+        assert_eq!(to_ansi256(Color::Indexed(3)), Color::Indexed(3));
+    }
+
+    #[test]
+    fn extends_fills_unset_colours_from_the_base() {
+        let theme = parse("extends = \"light\"\naccent = \"#ff0000\"", "mine").unwrap();
+        let light = Theme::builtin("light").unwrap();
+        assert_eq!(theme.accent, Color::Rgb(0xff, 0, 0));
+        assert_eq!(theme.text, light.text);
+        assert_eq!(theme.error, light.error);
+    }
+
+    #[test]
+    fn without_extends_a_builtin_name_is_the_base() {
+        let theme = parse("user = \"red\"", "solarized").unwrap();
+        let solarized = Theme::builtin("solarized").unwrap();
+        assert_eq!(theme.user, Color::Red);
+        assert_eq!(theme.bg, solarized.bg);
+        let theme = parse("", "mine").unwrap();
+        assert_eq!(theme.text, Theme::default().text);
+    }
+
+    #[test]
+    fn parse_reports_bad_extends_and_colours() {
+        let err = parse("extends = \"nope\"", "mine").unwrap_err();
+        assert!(err.contains("unknown theme 'nope'"), "{}", err);
+        let err = parse("accent = \"not-a-colour\"", "mine").unwrap_err();
+        assert!(err.contains("accent"), "{}", err);
+        assert!(parse("colour = \"red\"", "mine").is_err());
+    }
+
+    #[test]
+    fn select_refuses_names_outside_the_themes_dir() {
+        let mut themes = ThemeManager::new(ColorDepth::TrueColor);
+        for name in ["../../x", "a/b", "..", ""] {
+            assert!(themes.select(name).is_err(), "{}", name);
+        }
+        assert_eq!(themes.name, "dark");
+        assert!(themes.select("light").is_ok());
+    }
+}
@@ -0,0 +1,83 @@
+use ratatui::{
+    style::Style,
+    text::{Line, Span},
+};
+
+use crate::sanitize;
+use crate::text::truncate;
+use crate::Theme;
+
+/// How much of the model's reasoning to show, mirroring the host's
+/// thinking policy (src/cli/thinkingPolicy.ts).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Mode {
+    Off,
+    /// Reasoning is collapsed to a one-line summary until expanded.
+    #[default]
+    Minimal,
+    /// Reasoning is expanded unless collapsed by hand.
+    Verbose,
+}
+
+impl Mode {
+    pub fn parse(name: &str) -> Option<Mode> {
+        match name {
+            "off" => Some(Mode::Off),
+            "minimal" => Some(Mode::Minimal),
+            "verbose" => Some(Mode::Verbose),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Mode::Off => "off",
+            Mode::Minimal => "minimal",
+            Mode::Verbose => "verbose",
+        }
+    }
+
+    pub fn next(self) -> Mode {
+        match self {
+            Mode::Off => Mode::Minimal,
+            Mode::Minimal => Mode::Verbose,
+            Mode::Verbose => Mode::Off,
+        }
+    }
+}
+
+/// Dimmed reasoning block drawn above an assistant message. Collapsed, it
+/// is a single line with the first sentence and the size of the rest.
+pub fn lines(text: &str, expanded: bool, theme: &Theme, toggle_hint: &str) -> Vec<Line<'static>> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Vec::new();
+    }
+    let dim = Style::default().fg(theme.dim);
+    let count = text.lines().count();
+    if !expanded {
+        let first = text.lines().next().unwrap_or_default();
+        let more = if count > 1 {
+            format!(" (+{} more)", count - 1)
+        } else {
+            String::new()
+        };
+        return vec![Line::from(vec![
+            Span::styled("   ▸ thinking ", dim.italic()),
+            Span::styled(truncate(&sanitize::inline(first), 60), dim),
+            Span::styled(format!("{} • {} to expand", more, toggle_hint), dim),
+        ])];
+    }
+
+    let mut lines = vec![Line::from(vec![
+        Span::styled("   ▾ thinking", dim.italic()),
+        Span::styled(format!(" • {} to collapse", toggle_hint), dim),
+    ])];
+    for line in text.lines() {
+        lines.push(Line::from(vec![
+            Span::styled("   ┆ ", Style::default().fg(theme.border)),
+            Span::styled(sanitize::inline(line), dim.italic()),
+        ]));
+    }
+    lines
+}
@@ -0,0 +1,253 @@
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use std::time::{Duration, Instant};
+
+use crate::sanitize;
+use crate::text::duration_label;
+use crate::Theme;
+
+/// Execution phases in the order the host moves through them
+/// (src/cli/phaseController.ts).
+pub const PHASES: &[&str] = &[
+    "understanding",
+    "discovery",
+    "analysis",
+    "planning",
+    "execution",
+    "verification",
+];
+
+const STEP_MAX_CHARS: usize = 120;
+/// Steps kept per phase; older ones are dropped.
+const MAX_STEPS: usize = 50;
+
+struct Step {
+    text: String,
+    started: Instant,
+    ended: Option<Instant>,
+}
+
+struct PhaseRow {
+    name: String,
+    entered: Instant,
+    left: Option<Instant>,
+    /// Time spent in earlier visits when the host returns to a phase.
+    spent: Duration,
+    steps: Vec<Step>,
+}
+
+impl PhaseRow {
+    fn close(&mut self, now: Instant) {
+        if self.left.is_none() {
+            self.left = Some(now);
+            self.spent += now.duration_since(self.entered);
+        }
+        if let Some(step) = self.steps.last_mut() {
+            step.ended.get_or_insert(now);
+        }
+    }
+
+    fn duration(&self, now: Instant) -> Duration {
+        match self.left {
+            Some(_) => self.spent,
+            None => self.spent + now.duration_since(self.entered),
+        }
+    }
+}
+
+/// Phases and steps of the current turn, built from `phase`/`step` events.
+#[derive(Default)]
+pub struct Timeline {
+    rows: Vec<PhaseRow>,
+    current: Option<usize>,
+}
+
+impl Timeline {
+    pub fn reset(&mut self) {
+        self.rows.clear();
+        self.current = None;
+    }
+
+    pub fn enter(&mut self, phase: &str) {
+        let now = Instant::now();
+        if self.current_phase() == Some(phase) {
+            return;
+        }
+        if let Some(row) = self.current.and_then(|i| self.rows.get_mut(i)) {
+            row.close(now);
+        }
+        // Re-entering a phase continues its row rather than adding another.
+        let index = match self.rows.iter().position(|r| r.name == phase) {
+            Some(index) => {
+                self.rows[index].entered = now;
+                self.rows[index].left = None;
+                index
+            }
+            None => {
+                self.rows.push(PhaseRow {
+                    name: phase.to_string(),
+                    entered: now,
+                    left: None,
+                    spent: Duration::ZERO,
+                    steps: Vec::new(),
+                });
+                self.rows.len() - 1
+            }
+        };
+        self.current = Some(index);
+    }
+
+    /// Adds a step, collapsing whitespace. A step the phase has already
+    /// shown moves to the end rather than being added again, and only the
+    /// latest `MAX_STEPS` are kept.
+    pub fn step(&mut self, phase: Option<&str>, text: &str) {
+        if let Some(phase) = phase {
+            self.enter(phase);
+        }
+        let Some(row) = self.current.and_then(|i| self.rows.get_mut(i)) else {
+            return;
+        };
+        let mut text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if text.chars().count() > STEP_MAX_CHARS {
+            text = text.chars().take(STEP_MAX_CHARS - 1).collect::<String>() + "…";
+        }
+        if text.is_empty() || row.steps.last().is_some_and(|s| s.text == text) {
+            return;
+        }
+        let now = Instant::now();
+        if let Some(last) = row.steps.last_mut() {
+            last.ended.get_or_insert(now);
+        }
+        if let Some(seen) = row.steps.iter().position(|s| s.text == text) {
+            row.steps.remove(seen);
+        }
+        if row.steps.len() >= MAX_STEPS {
+            row.steps.remove(0);
+        }
+        row.steps.push(Step {
+            text,
+            started: now,
+            ended: None,
+        });
+    }
+
+    /// Stops the clocks when the turn completes.
+    pub fn finish(&mut self) {
+        let now = Instant::now();
+        if let Some(row) = self.current.and_then(|i| self.rows.get_mut(i)) {
+            row.close(now);
+        }
+    }
+
+    pub fn current_phase(&self) -> Option<&str> {
+        self.current
+            .and_then(|i| self.rows.get(i))
+            .map(|r| r.name.as_str())
+    }
+
+    pub fn current_step(&self) -> Option<&str> {
+        self.current
+            .and_then(|i| self.rows.get(i))
+            .and_then(|r| r.steps.last())
+            .map(|s| s.text.as_str())
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let now = Instant::now();
+        let mut names: Vec<&str> = PHASES.to_vec();
+        for row in &self.rows {
+            if !names.contains(&row.name.as_str()) {
+                names.push(&row.name);
+            }
+        }
+
+        let mut lines = Vec::new();
+        let mut focus = 0;
+        for name in names {
+            let index = self.rows.iter().position(|r| r.name == name);
+            let row = index.map(|i| &self.rows[i]);
+            let is_current = index == self.current && row.is_some_and(|r| r.left.is_none());
+            let (icon, style) = match row {
+                _ if is_current => ("▶", Style::default().fg(theme.accent).bold()),
+                Some(_) => ("✓", Style::default().fg(theme.success)),
+                None => ("○", Style::default().fg(theme.dim)),
+            };
+            let mut spans = vec![Span::styled(format!("{} {}", icon, name), style)];
+            if let Some(row) = row {
+                spans.push(Span::styled(
+                    format!(" {}", duration_label(row.duration(now).as_millis() as u64)),
+                    Style::default().fg(theme.dim),
+                ));
+            }
+            lines.push(Line::from(spans));
+
+            for step in row.map(|r| r.steps.as_slice()).unwrap_or(&[]) {
+                let took = step.ended.unwrap_or(now).duration_since(step.started);
+                let color = if is_current && step.ended.is_none() {
+                    theme.text
+                } else {
+                    theme.dim
+                };
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("  · {}", sanitize::inline(&step.text)),
+                        Style::default().fg(color),
+                    ),
+                    Span::styled(
+                        format!(" {}", duration_label(took.as_millis() as u64)),
+                        Style::default().fg(theme.dim),
+                    ),
+                ]));
+            }
+            if index.is_some() && index == self.current {
+                focus = lines.len();
+            }
+        }
+
+        // Keep the latest step of the current phase in view.
+        let height = area.height as usize;
+        let skip = focus
+            .saturating_sub(height)
+            .min(lines.len().saturating_sub(height));
+        frame.render_widget(Paragraph::new(lines.split_off(skip)), area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn steps(timeline: &Timeline) -> Vec<&str> {
+        timeline.rows[0]
+            .steps
+            .iter()
+            .map(|s| s.text.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn alternating_steps_do_not_grow() {
+        let mut timeline = Timeline::default();
+        for _ in 0..100 {
+            timeline.step(Some("execution"), "read a.rs");
+            timeline.step(Some("execution"), "read  b.rs");
+        }
+        assert_eq!(steps(&timeline), ["read a.rs", "read b.rs"]);
+    }
+
+    #[test]
+    fn keeps_the_latest_steps() {
+        let mut timeline = Timeline::default();
+        for i in 0..MAX_STEPS + 10 {
+            timeline.step(Some("execution"), &format!("step {}", i));
+        }
+        let steps = steps(&timeline);
+        assert_eq!(steps.len(), MAX_STEPS);
+        assert_eq!(steps[0], "step 10");
+    }
+}
@@ -0,0 +1,233 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use std::time::Instant;
+
+use crate::text::duration_label;
+use crate::Theme;
+
+/// Token counts and cost reported by the host for one or more requests.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Usage {
+    pub input: u64,
+    pub output: u64,
+    pub cached: u64,
+    pub cost: f64,
+}
+
+impl std::ops::AddAssign for Usage {
+    fn add_assign(&mut self, other: Usage) {
+        self.input += other.input;
+        self.output += other.output;
+        self.cached += other.cached;
+        self.cost += other.cost;
+    }
+}
+
+/// Per-turn and session usage plus turn timing, recorded as events arrive.
+#[derive(Default)]
+pub struct UsageTracker {
+    turn: Usage,
+    session: Usage,
+    context_tokens: Option<u64>,
+    context_window: Option<u64>,
+    started: Option<Instant>,
+    first_token: Option<Instant>,
+    finished: Option<Instant>,
+}
+
+impl UsageTracker {
+    /// A new turn starts when the user sends a message.
+    pub fn start_turn(&mut self) {
+        self.turn = Usage::default();
+        self.started = Some(Instant::now());
+        self.first_token = None;
+        self.finished = None;
+    }
+
+    pub fn token(&mut self) {
+        if self.started.is_none() {
+            self.start_turn();
+        }
+        self.first_token.get_or_insert_with(Instant::now);
+    }
+
+    /// Returns the turn duration in milliseconds.
+    pub fn finish(&mut self) -> Option<u64> {
+        let started = self.started?;
+        let finished = *self.finished.get_or_insert_with(Instant::now);
+        Some(finished.duration_since(started).as_millis() as u64)
+    }
+
+    /// Each `usage` event covers one request; a turn with tool calls may
+    /// send several.
+    pub fn record(&mut self, usage: Usage, context_tokens: Option<u64>, window: Option<u64>) {
+        self.turn += usage;
+        self.session += usage;
+        if context_tokens.is_some() {
+            self.context_tokens = context_tokens;
+        }
+        if window.is_some() {
+            self.context_window = window;
+        }
+    }
+
+    fn time_to_first_token(&self) -> Option<u64> {
+        Some(self.first_token?.duration_since(self.started?).as_millis() as u64)
+    }
+
+    /// Time from first token to completion, or to now while streaming.
+    fn stream_ms(&self) -> Option<u64> {
+        let end = self.finished.unwrap_or_else(Instant::now);
+        Some(end.duration_since(self.first_token?).as_millis() as u64)
+    }
+
+    fn context_fill(&self) -> Option<f64> {
+        match (self.context_tokens, self.context_window) {
+            (Some(used), Some(window)) if window > 0 => Some(used as f64 / window as f64),
+            _ => None,
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme, elapsed_ms: Option<u64>) {
+        let dim = Style::default().fg(theme.dim);
+        let text = Style::default().fg(theme.text);
+        let sep = || Span::styled(" • ", dim);
+        let mut spans = vec![
+            Span::styled(" turn ", dim),
+            Span::styled(usage_label(&self.turn), text),
+            sep(),
+            Span::styled("session ", dim),
+            Span::styled(usage_label(&self.session), text),
+        ];
+
+        if let Some(fill) = self.context_fill() {
+            let color: Color = if fill >= 0.95 {
+                theme.error
+            } else if fill >= 0.8 {
+                theme.warning
+            } else {
+                theme.success
+            };
+            spans.push(sep());
+            spans.push(Span::styled("ctx ", dim));
+            spans.push(Span::styled(
+                format!("{:.0}%", fill * 100.0),
+                Style::default().fg(color).bold(),
+            ));
+        }
+        if let Some(ttft) = self.time_to_first_token() {
+            spans.push(sep());
+            spans.push(Span::styled("ttft ", dim));
+            spans.push(Span::styled(duration_label(ttft), text));
+        }
+        if let Some(stream) = self.stream_ms() {
+            spans.push(sep());
+            spans.push(Span::styled("stream ", dim));
+            spans.push(Span::styled(duration_label(stream), text));
+            if stream > 0 && self.turn.output > 0 {
+                spans.push(Span::styled(
+                    format!(
+                        " {:.0} tok/s",
+                        self.turn.output as f64 * 1000.0 / stream as f64
+                    ),
+                    text,
+                ));
+            }
+        }
+        if let Some(total) = elapsed_ms.filter(|_| self.finished.is_some()) {
+            spans.push(sep());
+            spans.push(Span::styled("total ", dim));
+            spans.push(Span::styled(duration_label(total), text));
+        }
+
+        frame.render_widget(Paragraph::new(Line::from(spans)), area);
+    }
+}
+
+/// `↑1.2k ↓340 (800 cached) $0.0123`
+fn usage_label(usage: &Usage) -> String {
+    let mut label = format!(
+        "↑{} ↓{}",
+        count_label(usage.input),
+        count_label(usage.output)
+    );
+    if usage.cached > 0 {
+        label.push_str(&format!(" ({} cached)", count_label(usage.cached)));
+    }
+    if usage.cost > 0.0 {
+        label.push_str(&format!(" ${:.4}", usage.cost));
+    }
+    label
+}
+
+fn count_label(n: u64) -> String {
+    match n {
+        0..=999 => n.to_string(),
+        1_000..=999_999 => format!("{:.1}k", n as f64 / 1_000.0),
+        _ => format!("{:.1}M", n as f64 / 1_000_000.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(input: u64, output: u64, cached: u64, cost: f64) -> Usage {
+        Usage {
+            input,
+            output,
+            cached,
+            cost,
+        }
+    }
+
+    #[test]
+    fn record_adds_to_turn_and_session() {
+        let mut tracker = UsageTracker::default();
+        tracker.start_turn();
+        tracker.record(usage(1000, 100, 800, 0.01), None, None);
+        tracker.record(usage(500, 50, 0, 0.005), None, None);
+        assert_eq!(
+            (tracker.turn.input, tracker.turn.output, tracker.turn.cached),
+            (1500, 150, 800)
+        );
+        assert!((tracker.turn.cost - 0.015).abs() < 1e-9);
+
+        tracker.start_turn();
+        tracker.record(usage(10, 1, 0, 0.0), None, None);
+        assert_eq!(tracker.turn.input, 10);
+        assert_eq!(tracker.session.input, 1510);
+        assert_eq!(tracker.session.cached, 800);
+    }
+
+    #[test]
+    fn context_fill_needs_both_sides() {
+        let mut tracker = UsageTracker::default();
+        assert_eq!(tracker.context_fill(), None);
+        tracker.record(Usage::default(), Some(32_000), None);
+        assert_eq!(tracker.context_fill(), None);
+        tracker.record(Usage::default(), None, Some(128_000));
+        assert_eq!(tracker.context_fill(), Some(0.25));
+        // A report without context keeps the last known size.
+        tracker.record(usage(1, 1, 0, 0.0), None, None);
+        assert_eq!(tracker.context_fill(), Some(0.25));
+        tracker.record(Usage::default(), Some(64_000), Some(0));
+        assert_eq!(tracker.context_fill(), None);
+    }
+
+    #[test]
+    fn labels_counts_cache_and_cost() {
+        assert_eq!(usage_label(&usage(0, 0, 0, 0.0)), "↑0 ↓0");
+        assert_eq!(
+            usage_label(&usage(1234, 340, 800, 0.0123)),
+            "↑1.2k ↓340 (800 cached) $0.0123"
+        );
+        assert_eq!(usage_label(&usage(2_500_000, 999, 0, 0.0)), "↑2.5M ↓999");
+        assert_eq!(count_label(1_000), "1.0k");
+    }
+}
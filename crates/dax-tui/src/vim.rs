@@ -0,0 +1,228 @@
+use crate::keymap::Action;
+use crate::{Message, Role};
+
+/// Input mode when `behavior.vim_mode` is on. Without it the TUI stays in
+/// `Insert` for good.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    #[default]
+    Insert,
+    Normal,
+    Search,
+    Command,
+}
+
+impl Mode {
+    pub fn label(self) -> &'static str {
+        match self {
+            Mode::Insert => "INSERT",
+            Mode::Normal => "NORMAL",
+            Mode::Search => "SEARCH",
+            Mode::Command => "COMMAND",
+        }
+    }
+}
+
+/// Index of the nearest message after `from` (before it when `forward` is
+/// false) for which `pred` holds.
+pub fn find(
+    messages: &[Message],
+    from: usize,
+    forward: bool,
+    pred: impl Fn(&Message) -> bool,
+) -> Option<usize> {
+    if forward {
+        messages
+            .iter()
+            .enumerate()
+            .skip(from + 1)
+            .find(|(_, m)| pred(m))
+            .map(|(i, _)| i)
+    } else {
+        messages[..from.min(messages.len())].iter().rposition(pred)
+    }
+}
+
+pub fn has_tools(message: &Message) -> bool {
+    !message.tools.is_empty()
+}
+
+pub fn has_error(message: &Message) -> bool {
+    message.role == Role::Error || message.tools.iter().any(|t| t.status == "error")
+}
+
+/// Case-insensitive search over message text, tool names and tool output.
+/// Wraps around the ends like vim's `wrapscan`.
+pub fn search(messages: &[Message], from: usize, query: &str, forward: bool) -> Option<usize> {
+    let query = query.to_lowercase();
+    let matches = |m: &Message| {
+        m.content.to_lowercase().contains(&query)
+            || m.tools.iter().any(|t| {
+                t.name.to_lowercase().contains(&query)
+                    || t.output
+                        .as_deref()
+                        .is_some_and(|o| o.to_lowercase().contains(&query))
+            })
+    };
+    find(messages, from, forward, matches).or_else(|| {
+        if forward {
+            messages.iter().position(matches)
+        } else {
+            messages.iter().rposition(matches)
+        }
+    })
+}
+
+pub enum Command {
+    Quit,
+    Action(Action),
+    /// 0-based message index.
+    Goto(usize),
+    /// A slash command the TUI handles itself.
+    Local(String),
+    /// Anything else goes to the host as a slash command.
+    Host(String),
+}
+
+#[derive(Clone, Copy)]
+enum Run {
+    Quit,
+    Action(Action),
+    /// Rewritten to the slash command of the same name.
+    Local,
+}
+
+/// A named `:` command. The help overlay lists this table, so adding a
+/// command here documents it too.
+pub struct NamedCommand {
+    /// The first name is the one shown in help; the rest are aliases.
+    pub names: &'static [&'static str],
+    /// Argument hint for help, e.g. `<name>`.
+    args: &'static str,
+    run: Run,
+    /// Help text for commands that don't run a keymap action.
+    text: &'static str,
+}
+
+impl NamedCommand {
+    const fn action(names: &'static [&'static str], action: Action) -> Self {
+        Self {
+            names,
+            args: "",
+            run: Run::Action(action),
+            text: "",
+        }
+    }
+
+    const fn local(names: &'static [&'static str], args: &'static str, text: &'static str) -> Self {
+        Self {
+            names,
+            args,
+            run: Run::Local,
+            text,
+        }
+    }
+
+    pub fn usage(&self) -> String {
+        let name = self.names.first().copied().unwrap_or_default();
+        if self.args.is_empty() {
+            format!(":{}", name)
+        } else {
+            format!(":{} {}", name, self.args)
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self.run {
+            Run::Action(action) => action.description(),
+            Run::Quit | Run::Local => self.text,
+        }
+    }
+}
+
+pub const COMMANDS: &[NamedCommand] = &[
+    NamedCommand {
+        names: &["q", "q!", "qa", "quit"],
+        args: "",
+        run: Run::Quit,
+        text: "Quit",
+    },
+    NamedCommand::action(&["diff"], Action::OpenDiff),
+    NamedCommand::action(&["review"], Action::OpenReview),
+    NamedCommand::action(&["gate"], Action::ShowGate),
+    NamedCommand::action(&["diag", "diagnostics"], Action::ShowDiagnostics),
+    NamedCommand::action(&["waterfall", "timing"], Action::OpenWaterfall),
+    NamedCommand::action(&["policy"], Action::OpenPolicy),
+    NamedCommand::action(&["plan"], Action::OpenPlan),
+    NamedCommand::action(&["tabn", "tabnext"], Action::NextSession),
+    NamedCommand::action(&["tabp", "tabprevious"], Action::PrevSession),
+    NamedCommand::action(&["tabnew"], Action::NewSession),
+    NamedCommand::action(&["tabc", "tabclose"], Action::CloseSession),
+    NamedCommand::local(&["theme"], "<name>", "Switch colour theme"),
+    NamedCommand::local(&["thinking"], "<mode>", "Set reasoning display"),
+    NamedCommand::local(&["export"], "[format]", "Save the session to a file"),
+];
+
+/// Parses the text typed after `:`.
+pub fn parse_command(text: &str) -> Option<Command> {
+    let text = text.trim();
+    let (name, rest) = text.split_once(' ').unwrap_or((text, ""));
+    if name.is_empty() {
+        return None;
+    }
+    if let Some(named) = COMMANDS.iter().find(|c| c.names.contains(&name)) {
+        return Some(match named.run {
+            Run::Quit => Command::Quit,
+            Run::Action(action) => Command::Action(action),
+            Run::Local => Command::Local(format!("/{} {}", name, rest.trim())),
+        });
+    }
+    let command = if name.chars().all(|c| c.is_ascii_digit()) {
+        Command::Goto(name.parse::<usize>().ok()?.saturating_sub(1))
+    } else {
+        Command::Host(format!("/{}", text))
+    };
+    Some(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands() {
+        assert!(parse_command("  ").is_none());
+        assert!(matches!(parse_command("q!"), Some(Command::Quit)));
+        assert!(matches!(
+            parse_command("tabn"),
+            Some(Command::Action(Action::NextSession))
+        ));
+        assert!(matches!(parse_command("12"), Some(Command::Goto(11))));
+        assert!(matches!(parse_command("0"), Some(Command::Goto(0))));
+    }
+
+    #[test]
+    fn every_named_command_parses() {
+        for named in COMMANDS {
+            for name in named.names {
+                assert!(
+                    !matches!(parse_command(name), Some(Command::Host(_)) | None),
+                    "{}",
+                    name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn keeps_arguments_for_local_and_host_commands() {
+        match parse_command("export html  out.html ") {
+            Some(Command::Local(text)) => assert_eq!(text, "/export html  out.html"),
+            _ => panic!("expected a local command"),
+        }
+        match parse_command("model gpt-4o") {
+            Some(Command::Host(text)) => assert_eq!(text, "/model gpt-4o"),
+            _ => panic!("expected a host command"),
+        }
+    }
+}
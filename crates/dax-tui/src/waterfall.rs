@@ -0,0 +1,441 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::keymap::{Action, Keymap};
+use crate::sanitize;
+use crate::text::{duration_label, truncate};
+use crate::{StreamEvent, Theme};
+
+/// Turns kept for browsing; older ones are dropped.
+const MAX_TURNS: usize = 50;
+const LABEL_WIDTH: usize = 22;
+const DETAIL_HEIGHT: u16 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BarKind {
+    Request,
+    Stream,
+    Tool,
+    Gate,
+    Done,
+}
+
+struct Bar {
+    kind: BarKind,
+    label: String,
+    /// Milliseconds since the Unix epoch.
+    start: u64,
+    end: Option<u64>,
+    id: Option<String>,
+    details: Vec<(&'static str, String)>,
+}
+
+/// Every timed step of one turn.
+struct Trace {
+    start: u64,
+    end: Option<u64>,
+    bars: Vec<Bar>,
+}
+
+impl Trace {
+    fn open(&mut self, kind: BarKind) -> Option<&mut Bar> {
+        self.bars
+            .iter_mut()
+            .rev()
+            .find(|b| b.kind == kind && b.end.is_none())
+    }
+
+    fn push(&mut self, kind: BarKind, label: String, start: u64) -> &mut Bar {
+        self.bars.push(Bar {
+            kind,
+            label,
+            start,
+            end: None,
+            id: None,
+            details: Vec::new(),
+        });
+        self.bars.last_mut().unwrap()
+    }
+}
+
+pub fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Timing of each turn, recorded as events arrive. Times come from the
+/// host's event `timestamp` when present and the local clock otherwise;
+/// both are epoch milliseconds on the same machine.
+#[derive(Default)]
+pub struct Recorder {
+    traces: Vec<Trace>,
+}
+
+impl Recorder {
+    /// A turn starts when the user's message is sent.
+    pub fn begin(&mut self, ts: u64) {
+        if self.traces.len() == MAX_TURNS {
+            self.traces.remove(0);
+        }
+        let mut trace = Trace {
+            start: ts,
+            end: None,
+            bars: Vec::new(),
+        };
+        trace.push(BarKind::Request, "request → first token".to_string(), ts);
+        self.traces.push(trace);
+    }
+
+    fn live(&mut self, ts: u64) -> &mut Trace {
+        if self.traces.last().is_none_or(|t| t.end.is_some()) {
+            self.begin(ts);
+        }
+        self.traces.last_mut().unwrap()
+    }
+
+    pub fn record(&mut self, ts: u64, event: &StreamEvent) {
+        match event {
+            StreamEvent::TextDelta { .. } | StreamEvent::ThinkingDelta { .. } => {
+                let trace = self.live(ts);
+                if trace.bars.iter().any(|b| b.kind == BarKind::Stream) {
+                    return;
+                }
+                if let Some(request) = trace.open(BarKind::Request) {
+                    request.end = Some(ts);
+                }
+                trace.push(BarKind::Stream, "streaming".to_string(), ts);
+            }
+            StreamEvent::ToolCall {
+                name,
+                id,
+                arguments,
+                ..
+            } => {
+                let trace = self.live(ts);
+                if let Some(request) = trace.open(BarKind::Request) {
+                    request.end = Some(ts);
+                }
+                let name = name.clone().unwrap_or_else(|| "tool".to_string());
+                let bar = trace.push(BarKind::Tool, name.clone(), ts);
+                bar.id = id.clone();
+                bar.details.push(("tool", name));
+                if let Some(id) = id {
+                    bar.details.push(("id", id.clone()));
+                }
+                if let Some(arguments) = arguments {
+                    bar.details.push(("arguments", arguments.clone()));
+                }
+            }
+            StreamEvent::ToolResult {
+                tool_id,
+                success,
+                output,
+                elapsed_ms,
+                ..
+            } => {
+                let trace = self.live(ts);
+                let Some(bar) = trace
+                    .bars
+                    .iter_mut()
+                    .rev()
+                    .find(|b| b.kind == BarKind::Tool && b.end.is_none() && b.id == *tool_id)
+                else {
+                    return;
+                };
+                bar.end = Some(ts);
+                let outcome = if success.unwrap_or(false) {
+                    "success"
+                } else {
+                    "error"
+                };
+                bar.details.push(("outcome", outcome.to_string()));
+                if let Some(output) = output {
+                    bar.details.push((
+                        "output",
+                        format!("{} bytes, {} lines", output.len(), output.lines().count()),
+                    ));
+                }
+                if let Some(ms) = elapsed_ms {
+                    bar.details.push(("host elapsed", format!("{}ms", ms)));
+                }
+            }
+            StreamEvent::Gate { id, blocked, .. } => {
+                let trace = self.live(ts);
+                let bar = trace.push(BarKind::Gate, "gate wait".to_string(), ts);
+                bar.id = id.clone();
+                if let Some(id) = id {
+                    bar.details.push(("gate", id.clone()));
+                }
+                bar.details
+                    .push(("blocked", blocked.unwrap_or(false).to_string()));
+            }
+            StreamEvent::GateResolved { action } => {
+                let trace = self.live(ts);
+                if let Some(bar) = trace.open(BarKind::Gate) {
+                    bar.end = Some(ts);
+                    let action = action.clone().unwrap_or_else(|| "resolved".to_string());
+                    bar.details.push(("resolution", action));
+                }
+            }
+            StreamEvent::Complete {} | StreamEvent::Error { .. } => {
+                let Some(trace) = self.traces.last_mut().filter(|t| t.end.is_none()) else {
+                    return;
+                };
+                for bar in &mut trace.bars {
+                    bar.end.get_or_insert(ts);
+                }
+                trace.end = Some(ts);
+                let label = if matches!(event, StreamEvent::Complete {}) {
+                    "complete"
+                } else {
+                    "error"
+                };
+                let bar = trace.push(BarKind::Done, label.to_string(), ts);
+                bar.end = Some(ts);
+                if let StreamEvent::Error {
+                    message: Some(message),
+                    ..
+                } = event
+                {
+                    bar.details.push(("message", message.clone()));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Overlay drawing one turn's bars against a shared time axis.
+pub struct WaterfallView {
+    /// Index into the recorder's turns; `None` follows the latest.
+    turn: Option<usize>,
+    selected: usize,
+}
+
+impl WaterfallView {
+    pub fn new() -> Self {
+        Self {
+            turn: None,
+            selected: 0,
+        }
+    }
+
+    /// Returns false when the view should be closed.
+    pub fn handle_action(&mut self, action: Action, recorder: &Recorder) -> bool {
+        let last = recorder.traces.len().saturating_sub(1);
+        let turn = self.turn.unwrap_or(last);
+        match action {
+            Action::WaterfallClose => return false,
+            Action::WaterfallDown => self.selected += 1,
+            Action::WaterfallUp => self.selected = self.selected.saturating_sub(1),
+            Action::WaterfallPrevTurn => {
+                self.turn = Some(turn.saturating_sub(1));
+                self.selected = 0;
+            }
+            Action::WaterfallNextTurn => {
+                self.turn = (turn + 1 < last).then_some(turn + 1);
+                self.selected = 0;
+            }
+            _ => {}
+        }
+        true
+    }
+
+    pub fn render(
+        &mut self,
+        frame: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        keys: &Keymap,
+        recorder: &Recorder,
+    ) {
+        crate::clear_area(frame, area, theme);
+        let count = recorder.traces.len();
+        let index = self
+            .turn
+            .unwrap_or(count.saturating_sub(1))
+            .min(count.saturating_sub(1));
+        let trace = recorder.traces.get(index);
+
+        let now = now_ms();
+        let total = trace
+            .map(|t| t.end.unwrap_or(now).saturating_sub(t.start).max(1))
+            .unwrap_or(1);
+        let title = match trace {
+            Some(_) => format!(
+                " Waterfall • turn {}/{} • {} ",
+                index + 1,
+                count,
+                duration_label(total)
+            ),
+            None => " Waterfall ".to_string(),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(Span::styled(
+                title,
+                Style::default().fg(theme.accent).bold(),
+            ))
+            .title_bottom(Span::styled(
+                keys.hints(&[
+                    (Action::WaterfallDown, "select"),
+                    (Action::WaterfallPrevTurn, "prev turn"),
+                    (Action::WaterfallNextTurn, "next turn"),
+                    (Action::WaterfallClose, "close"),
+                ]),
+                Style::default().fg(theme.dim),
+            ));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let Some(trace) = trace else {
+            frame.render_widget(
+                Paragraph::new("No turns recorded yet").style(Style::default().fg(theme.dim)),
+                inner,
+            );
+            return;
+        };
+        self.selected = self.selected.min(trace.bars.len().saturating_sub(1));
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(DETAIL_HEIGHT)])
+            .split(inner);
+
+        let track = (rows[0].width as usize)
+            .saturating_sub(LABEL_WIDTH + 1)
+            .max(1);
+        let column = |ms: u64| {
+            ((ms.saturating_sub(trace.start)) as usize * track / total as usize).min(track)
+        };
+
+        let mut lines = vec![Line::from(vec![
+            Span::raw(" ".repeat(LABEL_WIDTH + 1)),
+            Span::styled(
+                format!(
+                    "0{:>width$}",
+                    duration_label(total),
+                    width = track.saturating_sub(1)
+                ),
+                Style::default().fg(theme.dim),
+            ),
+        ])];
+        for (i, bar) in trace.bars.iter().enumerate() {
+            let color = bar_color(bar, theme);
+            let from = column(bar.start);
+            let to = column(bar.end.unwrap_or(now)).max(from + 1).min(track);
+            let glyph = if bar.kind == BarKind::Done {
+                "◆"
+            } else {
+                "█"
+            };
+            let label = truncate(&sanitize::inline(&bar.label), LABEL_WIDTH);
+            let label_style = if i == self.selected {
+                Style::default().fg(theme.accent).reversed()
+            } else {
+                Style::default().fg(theme.text)
+            };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{:<width$}", label, width = LABEL_WIDTH),
+                    label_style,
+                ),
+                Span::raw(" "),
+                Span::raw(" ".repeat(from.min(track.saturating_sub(1)))),
+                Span::styled(
+                    glyph.repeat(to.saturating_sub(from).max(1)),
+                    Style::default().fg(color),
+                ),
+            ]));
+        }
+        let height = rows[0].height as usize;
+        let skip = (self.selected + 2).saturating_sub(height);
+        let visible: Vec<Line> = lines
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| *i == 0 || *i > skip)
+            .map(|(_, l)| l)
+            .collect();
+        frame.render_widget(Paragraph::new(visible), rows[0]);
+
+        let mut detail = Vec::new();
+        if let Some(bar) = trace.bars.get(self.selected) {
+            let took = bar.end.unwrap_or(now).saturating_sub(bar.start);
+            let running = if bar.end.is_none() { " (running)" } else { "" };
+            detail.push(Line::from(vec![
+                Span::styled(
+                    sanitize::inline(&bar.label),
+                    Style::default().fg(bar_color(bar, theme)).bold(),
+                ),
+                Span::styled(
+                    format!(
+                        "  +{} • {}{}",
+                        duration_label(bar.start.saturating_sub(trace.start)),
+                        duration_label(took),
+                        running
+                    ),
+                    Style::default().fg(theme.dim),
+                ),
+            ]));
+            for (key, value) in &bar.details {
+                detail.push(Line::from(vec![
+                    Span::styled(format!("{}: ", key), Style::default().fg(theme.dim)),
+                    Span::styled(sanitize::inline(value), Style::default().fg(theme.text)),
+                ]));
+            }
+        }
+        frame.render_widget(
+            Paragraph::new(detail).wrap(Wrap { trim: false }).block(
+                Block::default()
+                    .borders(Borders::TOP)
+                    .border_style(Style::default().fg(theme.border)),
+            ),
+            rows[1],
+        );
+    }
+}
+
+fn bar_color(bar: &Bar, theme: &Theme) -> Color {
+    match bar.kind {
+        BarKind::Request => theme.dim,
+        BarKind::Stream => theme.assistant,
+        BarKind::Tool
+            if bar
+                .details
+                .iter()
+                .any(|(k, v)| *k == "outcome" && v == "error") =>
+        {
+            theme.error
+        }
+        BarKind::Tool => theme.accent,
+        BarKind::Gate => theme.warning,
+        BarKind::Done if bar.label == "error" => theme.error,
+        BarKind::Done => theme.success,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn complete_closes_the_turn() {
+        let mut recorder = Recorder::default();
+        let complete: StreamEvent =
+            serde_json::from_str(r#"{"type":"complete","data":{}}"#).unwrap();
+        recorder.record(10, &StreamEvent::TextDelta { text: "hi".into() });
+        recorder.record(25, &complete);
+        let trace = recorder.traces.last().unwrap();
+        assert_eq!(trace.end, Some(25));
+        assert!(trace.bars.iter().all(|b| b.end.is_some()));
+        assert_eq!(trace.bars.last().unwrap().label, "complete");
+    }
+}
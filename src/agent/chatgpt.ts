@@ -154,7 +154,10 @@ export class ChatGPTProvider {
     return {
       content: choice?.content || "",
       tool_calls: choice?.tool_calls,
-      usage: data.usage,
+      usage: data.usage && {
+        ...data.usage,
+        cached_tokens: data.usage.prompt_tokens_details?.cached_tokens || 0,
+      },
     };
   }
 
@@ -5,6 +5,7 @@ import type {
   Tool,
   ToolCall,
   LLMConfig,
+  LLMResponse,
 } from "../llm/types.js";
 import type { ToolRegistry, ToolContext, ToolResult } from "../tools/types.js";
 import { createSystemPrompt } from "../llm/types.js";
@@ -15,7 +16,7 @@ import {
   initializeExperimentRunner,
 } from "../tools/validation.js";
 import { createWorkNotesPrompt, formatWorkNotesSummary } from "./prompts.js";
-import { applyPMCommands } from "../pm/commands.js";
+import { applyPMCommands, applyPMEdit, type PMEdit } from "../pm/commands.js";
 import { buildContextPack } from "../pm/contextPack.js";
 import {
   getOrCreateProject,
@@ -48,6 +49,8 @@ export interface AgentConfig {
   contract?: string;
   llmConfig?: LLMConfig;
   requireApproval?: boolean;
+  /** Called as each tool call finishes, whether or not it went through a gate. */
+  onToolResult?: (toolCall: ToolCall, result: ToolResult, elapsedMs: number) => void;
 }
 
 export interface AgentMessage {
@@ -84,6 +87,11 @@ export class Agent {
   private pmState: PMState;
   private userId: string;
   private pendingGate?: PendingGate;
+  /**
+   * Tokens the provider reported since `takeUsage` was last called, and the
+   * size of the latest request's context.
+   */
+  private usage = { input: 0, output: 0, cached: 0, context: 0 };
   private approveOnce = false;
   private lastRun?: {
     tool: string;
@@ -322,7 +330,7 @@ export class Agent {
   private async executeToolCalls(toolCalls: ToolCall[]) {
     const toolResults: ToolResult[] = [];
     for (const toolCall of toolCalls) {
-      const result = await this.executeTool(toolCall);
+      const result = await this.runTool(toolCall);
       this.setLastRun(toolCall, result);
       await this.appendRao({
         kind: "run",
@@ -355,6 +363,29 @@ export class Agent {
     return this.workNotes;
   }
 
+  private noteUsage(usage?: LLMResponse["usage"]) {
+    if (!usage) return;
+    this.usage.input += usage.prompt_tokens || 0;
+    this.usage.output += usage.completion_tokens || 0;
+    this.usage.cached += usage.cached_tokens || 0;
+    if (usage.prompt_tokens) {
+      this.usage.context = usage.prompt_tokens + (usage.completion_tokens || 0);
+    }
+  }
+
+  /** Tokens used since the last call, or null if the provider reported none. */
+  takeUsage() {
+    const { input, output, cached, context } = this.usage;
+    this.usage = { input: 0, output: 0, cached: 0, context: 0 };
+    if (!input && !output) return null;
+    return {
+      input_tokens: input,
+      output_tokens: output,
+      cached_tokens: cached,
+      context_tokens: context || undefined,
+    };
+  }
+
   getPendingGate() {
     return this.pendingGate;
   }
@@ -561,6 +592,25 @@ export class Agent {
     return true;
   }
 
+  async getPMState() {
+    await this.ensureProject();
+    return this.pmState;
+  }
+
+  /** Applies a structured constraints edit, recorded like a chat PM command. */
+  async editPM(edit: PMEdit) {
+    await this.ensureProject();
+    if (!this.projectId) return null;
+    const result = applyPMEdit(edit, this.pmState);
+    if (!result.applied) return null;
+    this.pmState = await savePM(this.projectId, result.updates, {
+      command: `pm_command ${JSON.stringify(edit)}`,
+      actor: "user",
+    });
+    await this.refreshLastOverride(true);
+    return result.confirmation || "Updated project PM settings.";
+  }
+
   rejectPendingGate() {
     this.pendingGate = undefined;
   }
@@ -685,6 +735,7 @@ export class Agent {
     if (typeof this.config.provider.stream !== "function") {
       // Fallback to complete if no streaming
       const response = await this.config.provider.complete(msgs, undefined, this.planningConfig());
+      this.noteUsage(response.usage);
       const content = response.content;
       onChunk(content);
       await this.parseWorkNotesFromContent(content);
@@ -758,6 +809,7 @@ export class Agent {
       undefined,
       this.planningConfig(),
     );
+    this.noteUsage(response.usage);
 
     try {
       const content = response.content.replace(/```json\n?|\n?```/g, "").trim();
@@ -814,6 +866,7 @@ export class Agent {
       tools,
       this.config.llmConfig,
     );
+    this.noteUsage(response.usage);
 
     this.messages.push({
       role: "assistant",
@@ -841,6 +894,7 @@ export class Agent {
       tools,
       this.config.llmConfig,
     );
+    this.noteUsage(response.usage);
 
     this.messages.push({
       role: "assistant",
@@ -911,6 +965,13 @@ export class Agent {
     return !isComplete;
   }
 
+  private async runTool(toolCall: ToolCall): Promise<ToolResult> {
+    const started = Date.now();
+    const result = await this.executeTool(toolCall);
+    this.config.onToolResult?.(toolCall, result, Date.now() - started);
+    return result;
+  }
+
   private async executeTool(toolCall: ToolCall): Promise<ToolResult> {
     const tool = this.config.tools.get(toolCall.function.name);
 
@@ -976,6 +1037,7 @@ export class Agent {
       undefined,
       this.config.llmConfig,
     );
+    this.noteUsage(response.usage);
 
     this.messages.push({
       role: "assistant",
@@ -1006,7 +1068,7 @@ export class Agent {
           | "awaiting_first_token"
           | "streaming"
           | "tool_executing",
-        data?: { name: string },
+        data?: { name: string; id?: string; arguments?: string },
       ) => void;
     },
   ): Promise<void> {
@@ -1040,6 +1102,7 @@ export class Agent {
           this.config.tools.toLLMTools(),
           this.config.llmConfig,
         );
+        this.noteUsage(response.usage);
 
         this.messages.push({
           role: "assistant",
@@ -1091,11 +1154,11 @@ export class Agent {
             ? remaining
             : Math.min(firstTokenTimeout, remaining);
           const next = await Promise.race<
-            | IteratorResult<{ content: string; tool_calls?: ToolCall[] }>
+            | IteratorResult<LLMResponse>
             | { timeout: true }
           >([
             it.next() as Promise<
-              IteratorResult<{ content: string; tool_calls?: ToolCall[] }>
+              IteratorResult<LLMResponse>
             >,
             Bun.sleep(budget).then(() => ({ timeout: true as const })),
           ]);
@@ -1112,6 +1175,7 @@ export class Agent {
           }
 
           const chunk = next.value;
+          this.noteUsage(chunk.usage);
           const hasVisibleText = !!chunk.content;
           const hasToolCalls = (chunk.tool_calls?.length ?? 0) > 0;
 
@@ -1160,6 +1224,7 @@ export class Agent {
           this.config.tools.toLLMTools(),
           this.config.llmConfig,
         );
+        this.noteUsage(response.usage);
         content = response.content;
         calls = response.tool_calls;
         if (response.content) {
@@ -1188,8 +1253,10 @@ export class Agent {
       for (const toolCall of calls) {
         options?.onStateChange?.("tool_executing", {
           name: toolCall.function.name,
+          id: toolCall.id,
+          arguments: toolCall.function.arguments,
         });
-        const result = await this.executeTool(toolCall);
+        const result = await this.runTool(toolCall);
         toolResults.push(result);
         this.messages.push({
           role: "tool",
@@ -1215,6 +1282,23 @@ export class Agent {
     this.conversation = history;
   }
 
+  /**
+   * Like `loadHistory`, but the turns are also sent to the model again, so a
+   * rebuilt agent carries on the same conversation. Tool traffic is dropped.
+   */
+  resumeHistory(history: AgentMessage[]) {
+    this.conversation = history;
+    const system = this.messages.filter((m) => m.role === "system");
+    this.messages = [
+      ...system,
+      ...history.flatMap((m): Message[] =>
+        (m.role === "user" || m.role === "assistant") && m.content
+          ? [{ role: m.role, content: m.content }]
+          : [],
+      ),
+    ];
+  }
+
   setWorkNotes(notes: WorkNotes) {
     this.workNotes = notes;
   }
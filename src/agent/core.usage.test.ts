@@ -0,0 +1,34 @@
+import { describe, expect, test } from "bun:test"
+import { createAgent } from "./core.js"
+import { ToolRegistry } from "../tools/types.js"
+import type { LLMProvider, Message, LLMResponse } from "../llm/types.js"
+
+class UsageProvider implements LLMProvider {
+  name = "usage"
+  async complete(_messages: Message[]): Promise<LLMResponse> {
+    return {
+      content: "ok",
+      usage: { prompt_tokens: 1200, completion_tokens: 80, total_tokens: 1280, cached_tokens: 1000 },
+    }
+  }
+}
+
+describe("agent usage", () => {
+  test("reports cached tokens and the context size, then resets", async () => {
+    const agent = createAgent({
+      name: "DAX",
+      mode: "build",
+      provider: new UsageProvider(),
+      tools: new ToolRegistry(),
+      workDir: process.cwd(),
+    })
+    await agent.chatStream("hi", () => {})
+    expect(agent.takeUsage()).toEqual({
+      input_tokens: 1200,
+      output_tokens: 80,
+      cached_tokens: 1000,
+      context_tokens: 1280,
+    })
+    expect(agent.takeUsage()).toBeNull()
+  })
+})
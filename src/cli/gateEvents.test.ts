@@ -0,0 +1,69 @@
+import { describe, expect, test } from "bun:test"
+import type { PendingGate } from "../agent/core.js"
+import type { ToolCall } from "../llm/types.js"
+import { gateEvents, proposedDiff, toolResultEvent, unifiedDiff } from "./gateEvents.js"
+
+function call(name: string, args: Record<string, unknown>, id = "t1"): ToolCall {
+  return { id, type: "function", function: { name, arguments: JSON.stringify(args) } }
+}
+
+const files: Record<string, string> = { "src/a.ts": "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\n" }
+const read = (path: string) => files[path]
+
+const gate: PendingGate = {
+  blocked: false,
+  warnings: [
+    {
+      kind: "require_approval",
+      code: "require_approval.tool",
+      subject: "edit_file",
+      message: "edit_file requires approval",
+    },
+  ],
+  toolCalls: [call("edit_file", { path: "src/a.ts", oldString: "five", newString: "FIVE" })],
+}
+
+describe("gate events", () => {
+  test("diffs a change with three lines of context", () => {
+    expect(unifiedDiff("src/a.ts", files["src/a.ts"], files["src/a.ts"].replace("five", "FIVE"))).toBe(
+      "--- a/src/a.ts\n+++ b/src/a.ts\n@@ -2,7 +2,7 @@\n two\n three\n four\n-five\n+FIVE\n six\n seven\n eight\n",
+    )
+  })
+
+  test("diffs a new file from /dev/null", () => {
+    expect(proposedDiff(call("write_file", { path: "src/b.ts", content: "x\ny\n" }), read)).toBe(
+      "--- /dev/null\n+++ b/src/b.ts\n@@ -0,0 +1,2 @@\n+x\n+y\n",
+    )
+  })
+
+  test("has nothing to show for calls that change no file", () => {
+    expect(proposedDiff(call("read_file", { path: "src/a.ts" }), read)).toBeUndefined()
+    expect(proposedDiff(call("edit_file", { path: "src/a.ts", oldString: "nine", newString: "9" }), read)).toBeUndefined()
+    expect(proposedDiff(call("write_file", { path: "src/a.ts", content: files["src/a.ts"] }), read)).toBeUndefined()
+  })
+
+  test("sends a require_approval gate with its calls and diffs first", () => {
+    const events = gateEvents("g1", gate, read)
+    expect(events.map((e) => e.type)).toEqual(["tool_call", "gate"])
+    // The TUI offers a review when a held call carries a diff it can parse.
+    const tool = events[0].data.tool
+    expect(tool?.id).toBe("t1")
+    expect(tool?.arguments).toBe(gate.toolCalls[0].function.arguments)
+    expect(tool?.diff?.startsWith("--- a/src/a.ts\n+++ b/src/a.ts\n@@ -2,7 +2,7 @@\n")).toBe(true)
+    expect(events[1].data.gate).toEqual({
+      id: "g1",
+      blocked: false,
+      warnings: [{ code: "require_approval.tool", subject: "edit_file" }],
+      pending: true,
+    })
+  })
+
+  test("doesn't repeat the calls when the gate is asked again", () => {
+    expect(gateEvents("g2", gate, read, false).map((e) => e.type)).toEqual(["gate"])
+  })
+
+  test("reports a failed call's error as its output", () => {
+    const event = toolResultEvent(gate.toolCalls[0], { success: false, output: "", error: "denied" }, 4)
+    expect(event.data.result).toEqual({ tool_id: "t1", success: false, output: "denied", error: "denied", elapsed_ms: 4 })
+  })
+})
@@ -0,0 +1,112 @@
+import type { PendingGate } from "../agent/core.js"
+import type { ToolCall } from "../llm/types.js"
+import type { ToolResult } from "../tools/types.js"
+import {
+  createGateEvent,
+  createToolCallEvent,
+  createToolResultEvent,
+  type DaxStreamEvent,
+} from "./tui/types/stream.js"
+
+/** Unchanged lines kept around a change, as `diff -u` does. */
+const CONTEXT = 3
+
+function lines(text: string) {
+  const rows = text ? text.split("\n") : []
+  if (rows.at(-1) === "") rows.pop()
+  return rows
+}
+
+/**
+ * A unified diff of one file, with a single hunk spanning everything
+ * between the unchanged head and tail. A missing file diffs from
+ * `/dev/null`; identical contents give an empty string.
+ */
+export function unifiedDiff(path: string, before: string | undefined, after: string) {
+  const old = lines(before ?? "")
+  const next = lines(after)
+  let head = 0
+  while (head < old.length && head < next.length && old[head] === next[head]) head++
+  let tail = 0
+  while (
+    tail < old.length - head &&
+    tail < next.length - head &&
+    old[old.length - 1 - tail] === next[next.length - 1 - tail]
+  ) {
+    tail++
+  }
+  if (head + tail === old.length && head + tail === next.length) return ""
+  const start = Math.max(0, head - CONTEXT)
+  const trailing = Math.min(tail, CONTEXT)
+  const oldLen = old.length - tail + trailing - start
+  const newLen = next.length - tail + trailing - start
+  // Line numbers are 1-based; an empty side names the line before it.
+  const range = (len: number) => `${len === 0 ? start : start + 1},${len}`
+  return [
+    before === undefined ? "--- /dev/null" : `--- a/${path}`,
+    `+++ b/${path}`,
+    `@@ -${range(oldLen)} +${range(newLen)} @@`,
+    ...old.slice(start, head).map((line) => ` ${line}`),
+    ...old.slice(head, old.length - tail).map((line) => `-${line}`),
+    ...next.slice(head, next.length - tail).map((line) => `+${line}`),
+    ...old.slice(old.length - tail, old.length - tail + trailing).map((line) => ` ${line}`),
+  ].join("\n") + "\n"
+}
+
+/**
+ * The change a file tool call would make, for review before it runs.
+ * `read` returns a file's current contents, or undefined if it has none.
+ */
+export function proposedDiff(call: ToolCall, read: (path: string) => string | undefined) {
+  let args: Record<string, unknown>
+  try {
+    args = JSON.parse(call.function.arguments || "{}")
+  } catch {
+    return undefined
+  }
+  const path = typeof args.path === "string" ? args.path : ""
+  if (!path) return undefined
+  const { content, oldString, newString } = args
+  if (call.function.name === "write_file" && typeof content === "string") {
+    return unifiedDiff(path, read(path), content) || undefined
+  }
+  if (call.function.name === "edit_file" && typeof oldString === "string" && typeof newString === "string") {
+    const current = read(path)
+    if (current === undefined || !oldString || !current.includes(oldString)) return undefined
+    return unifiedDiff(path, current, current.replace(oldString, () => newString)) || undefined
+  }
+  return undefined
+}
+
+/**
+ * What the TUI needs to show a gate: each held tool call with its
+ * arguments and proposed diff, then the gate itself. The diffs are what
+ * make the gate reviewable hunk by hunk.
+ */
+export function gateEvents(
+  id: string,
+  pending: PendingGate,
+  read: (path: string) => string | undefined,
+  announce = true,
+): DaxStreamEvent[] {
+  const calls = announce
+    ? pending.toolCalls.map((call) =>
+        createToolCallEvent(call.function.name, call.id, call.function.arguments, proposedDiff(call, read)),
+      )
+    : []
+  return [
+    ...calls,
+    createGateEvent({
+      id,
+      blocked: pending.blocked,
+      warnings: pending.warnings.map(({ code, subject }) => ({ code, subject })),
+      pending: true,
+    }),
+  ]
+}
+
+/** A finished tool call; failures carry their error as the output. */
+export function toolResultEvent(call: ToolCall, result: ToolResult, elapsedMs?: number) {
+  const output = result.success ? result.output : result.error || result.output
+  return createToolResultEvent(call.id, result.success, output, result.error, elapsedMs)
+}
@@ -1,6 +1,6 @@
 #!/usr/bin/env bun
 
-import { createAgent } from "../agent/index.js";
+import { createAgent, type PendingGate } from "../agent/index.js";
 import {
   getDefaultProvider,
   createProvider,
@@ -11,6 +11,7 @@ import { createToolRegistry, createLocalRegistry } from "../tools/index.js";
 import { parseArgs } from "util";
 import { dirname, join, resolve } from "path";
 import { createInterface } from "node:readline";
+import { readFileSync } from "node:fs";
 import { mkdir } from "node:fs/promises";
 import {
   authenticateGemini,
@@ -36,16 +37,36 @@ import {
   type ThinkingMode,
 } from "./uiState.js";
 import { buildThinkingView } from "./phaseController.js";
+import { PlanProgress, type PlanUpdate } from "./planProgress.js";
+import { acceptsAll, formatComments, parseReview, replanPrompt } from "./review.js";
+import { gateEvents, toolResultEvent } from "./gateEvents.js";
 import { renderThinkingDelta, renderThinkingPanel } from "./renderThinking.js";
+import { createThinkSplitter } from "./thinkTags.js";
 import { createTelemetryBus } from "./telemetryBus.js";
 import { thinkingPolicy } from "./thinkingPolicy.js";
 import type { TelemetryEvent } from "./telemetry.js";
 import { warnLegacyEnvOnce } from "./migrations.js";
 import { getScopeTracker } from "../tools/validation.js";
 import type { ToolCall } from "../llm/types.js";
+import type { ToolResult } from "../tools/types.js";
 import { initDb } from "../db/index.js";
 import { diffKeys, formatPMEventRow, redacted, safeJsonPreview } from "../pm/format.js";
-import { createTUIBackend } from "./tui/index.js";
+import { parsePMEdit } from "../pm/commands.js";
+import { contextWindow, estimateCost } from "../llm/pricing.js";
+import { MAX_RAO_HISTORY } from "../pm/types.js";
+import {
+  createEvent,
+  createPhaseEvent,
+  createPlanEvent,
+  createPlanUpdateEvent,
+  createStepEvent,
+  createThinkingDeltaEvent,
+  createToolCallEvent,
+  createToolResultEvent,
+  createTUIBackend,
+  createUsageEvent,
+  type DaxStreamData,
+} from "./tui/index.js";
 
 interface CLIOptions {
   mode: "build" | "plan";
@@ -1450,24 +1471,27 @@ function renderToolResults(
   }
 }
 
+function inferPathPattern(toolCalls: ToolCall[]) {
+  const files = toolCalls.flatMap((call) => toolTargets(call))
+  if (files.length === 0) return null
+  if (files.length === 1) return files[0]
+  const parts = files.map((file) => file.split("/").filter(Boolean))
+  const min = Math.min(...parts.map((row) => row.length))
+  const shared: string[] = []
+  for (let i = 0; i < min; i++) {
+    const head = parts[0][i]
+    if (!parts.every((row) => row[i] === head)) break
+    shared.push(head)
+  }
+  if (shared.length === 0) return null
+  return `${shared.join("/")}/**`
+}
+
+type GateAction = NonNullable<DaxStreamData["resolution"]>["action"]
+
 async function promptGateResolution(gate: { blocked: boolean; warnings: { kind: "never_touch" | "require_approval"; code: string; subject: string; message: string; matches?: string[] }[]; toolCalls: ToolCall[] }) {
   const stdin = process.stdin
   const stdout = process.stdout
-  const inferPathPattern = () => {
-    const files = gate.toolCalls.flatMap((call) => toolTargets(call))
-    if (files.length === 0) return null
-    if (files.length === 1) return files[0]
-    const parts = files.map((file) => file.split("/").filter(Boolean))
-    const min = Math.min(...parts.map((row) => row.length))
-    const shared: string[] = []
-    for (let i = 0; i < min; i++) {
-      const head = parts[0][i]
-      if (!parts.every((row) => row[i] === head)) break
-      shared.push(head)
-    }
-    if (shared.length === 0) return null
-    return `${shared.join("/")}/**`
-  }
   const print = () => {
     console.log(`\n${warn("Gate: approval required before execution")}`)
     gate.warnings.forEach((line) => console.log(`- [${line.code}] ${line.message} (subject: ${line.subject})`))
@@ -1510,7 +1534,7 @@ async function promptGateResolution(gate: { blocked: boolean; warnings: { kind:
       stdin.pause()
       if (next === "t") return { action: "always_allow_tool" as const }
       if (next === "p") {
-        const pattern = inferPathPattern()
+        const pattern = inferPathPattern(gate.toolCalls)
         if (!pattern) return { action: "cancel" as const }
         return { action: "always_allow_path" as const, pattern }
       }
@@ -1714,14 +1738,20 @@ async function runTUIMode(options: CLIOptions, trace?: (phase: string) => void)
     ? createLocalRegistry()
     : createToolRegistry();
 
-  let agent = createAgent({
+  // Gated tool calls wait for the TUI; every call's result is sent as it
+  // finishes, so the transcript shows what ran.
+  const agentConfig = () => ({
     name: "DAX",
     mode: options.mode,
     provider,
     tools,
     workDir: options.workDir,
     llmConfig: policyConfig(policy, options.model),
+    requireApproval: true,
+    onToolResult: (call: ToolCall, result: ToolResult, elapsedMs: number) =>
+      tui.dispatch(toolResultEvent(call, result, elapsedMs)),
   });
+  let agent = createAgent(agentConfig());
 
   tui.dispatch({
     type: "meta",
@@ -1729,10 +1759,39 @@ async function runTUIMode(options: CLIOptions, trace?: (phase: string) => void)
     data: { provider: provider.name, model: options.model || activeModel(options, provider.name) },
   });
 
-  tui.setContext({
-    files: ["src/cli/main.ts", "src/agent/core.ts", "src/agent/gemini-cli.ts"],
-    scope: [options.workDir],
-  });
+  // The files the agent's work notes are scoped to; the sidebar shows them
+  // and can narrow them with remove_context_file.
+  const publishContext = () => {
+    tui.setContext({
+      files: agent.getWorkNotes()?.scope.files ?? [],
+      scope: [options.workDir],
+    });
+  };
+  publishContext();
+
+  // The scope tracker exists once the agent has set up its tools; until then
+  // there is nothing to show.
+  const publishScope = () => {
+    const status = getScopeTracker()?.getStatus();
+    if (status) tui.setScopeStatus?.(status);
+  };
+
+  // Policy and RAO history for the TUI's panels; sent again whenever a
+  // turn, gate or edit may have changed them.
+  const publishPM = async () => {
+    tui.setPMState?.(await agent.getPMState());
+    // getRaoHistory is newest first; the browser lists oldest first.
+    tui.setRaoHistory?.((await agent.getRaoHistory(MAX_RAO_HISTORY)).reverse());
+  };
+  void publishPM();
+
+  // The ratatui backend may have restored a transcript from its last run;
+  // this is the conversation the agent actually has.
+  tui.setSnapshot?.(
+    agent.getConversation().flatMap((m) =>
+      m.role === "user" || m.role === "assistant" ? [{ role: m.role, content: m.content }] : []
+    ),
+  );
 
   const timeoutFor = () => {
     if (provider.name === "gemini" || provider.name === "gemini-cli") {
@@ -1741,11 +1800,173 @@ async function runTUIMode(options: CLIOptions, trace?: (phase: string) => void)
     return { first: 8000, overall: 45000 };
   };
 
+  // A tool call that needs approval ends the turn with a gate; the TUI
+  // answers with gate_resolution or review_result for that gate id. The
+  // held calls go first, with their proposed diffs, so the gate can be
+  // reviewed; asking again for the same gate doesn't repeat them.
+  let gateId: string | undefined;
+  let announced: PendingGate | undefined;
+  const readWorkFile = (path: string) => {
+    try {
+      return readFileSync(resolve(options.workDir, path), "utf8");
+    } catch {
+      return undefined;
+    }
+  };
+  const raiseGate = () => {
+    const pending = agent.getPendingGate?.();
+    if (!pending) return;
+    gateId = `gate-${Date.now()}`;
+    setGate(ui, pending);
+    tui.dispatch(createStepEvent("waiting for approval", "verification"));
+    for (const event of gateEvents(gateId, pending, readWorkFile, pending !== announced)) tui.dispatch(event);
+    announced = pending;
+  };
+  const resolveGate = async (id: unknown, requested: string) => {
+    const pending = agent.getPendingGate?.();
+    if (!pending || id !== gateId) return;
+    let action: GateAction = "reject";
+    let done = true;
+    if (requested === "approve_once") {
+      action = requested;
+      done = (await agent.approvePendingOnce?.()) ?? false;
+    } else if (requested === "always_allow_tool") {
+      action = requested;
+      done = (await agent.alwaysAllowFromPending?.("tool")) ?? false;
+    } else if (requested === "always_allow_path") {
+      action = requested;
+      const pattern = inferPathPattern(pending.toolCalls);
+      done = pattern ? (await agent.alwaysAllowFromPending?.("path", pattern)) ?? false : false;
+    } else {
+      agent.rejectPendingGate?.();
+    }
+    if (!done) {
+      // Nothing ran; ask again rather than leave the turn stuck.
+      tui.addSystemMessage?.(`Couldn't ${action.replace(/_/g, " ")} for this gate.`, "warning");
+      raiseGate();
+      return;
+    }
+    if (action === "reject") {
+      for (const call of pending.toolCalls) {
+        tui.dispatch(createToolResultEvent(call.id, false, "rejected at the gate"));
+      }
+    }
+    gateId = undefined;
+    announced = undefined;
+    clearGate(ui);
+    publishPlanUpdates(action === "reject" ? planProgress.gateRejected() : planProgress.turnCompleted());
+    publishScope();
+    await publishPM();
+    tui.dispatch(createEvent("gate_resolved", { resolution: { action } }));
+    // Files the held calls and their results under a reply of their own.
+    tui.dispatch({ type: "complete", timestamp: Date.now(), data: {} });
+    const last = agent.getConversation().at(-1);
+    if (action !== "reject" && last?.role === "assistant" && last.content) {
+      tui.addSystemMessage?.(last.content, "notice");
+    }
+  };
+
+  const rebuild = async (history = agent.getConversation()) => {
+    const notes = agent.getWorkNotes();
+    agent = createAgent(agentConfig());
+    if (history) agent.resumeHistory(history);
+    if (notes && agent.setWorkNotes) agent.setWorkNotes(notes);
+    tui.dispatch({
+      type: "meta",
+      timestamp: Date.now(),
+      data: { provider: provider.name, model: options.model || activeModel(options, provider.name) },
+    });
+  };
+
+  // Moves to the next fallback model, then provider, as the interactive CLI
+  // does; returns what it switched to.
+  const switchToFallback = async (message: string) => {
+    const currentModel = options.model || activeModel(options, provider.name);
+    const nextModel = resolveFallbackModel(provider.name, currentModel);
+    if (nextModel && nextModel !== currentModel) {
+      options.model = nextModel;
+      if (provider.name === "gemini-cli") {
+        process.env.GEMINI_CLI_MODEL = nextModel;
+      } else if (provider.name === "claude-cli") {
+        process.env.CLAUDE_CLI_MODEL = nextModel;
+      }
+      provider = await getProvider(options);
+      return nextModel;
+    }
+    const result = resolveFallbackProvider(provider.name, message, {
+      codex: hasCodex() && (await codexLoginStatus()),
+      gemini_cli: (await geminiCliStatus()).ready,
+      claude_cli: (await claudeCliStatus()).ready,
+    });
+    if (!result?.value) return null;
+    options.local = false;
+    options.provider = result.value;
+    provider = await getProvider(options);
+    return provider.name;
+  };
+
+  // Token counts for the status bar, with a cost estimate and the context
+  // window for the model in use when they're known.
+  const reportUsage = () => {
+    const usage = agent.takeUsage();
+    if (!usage) return;
+    const model = options.model || activeModel(options, provider.name);
+    tui.dispatch(createUsageEvent({
+      ...usage,
+      cost_usd: estimateCost(model, usage),
+      context_window: contextWindow(model),
+    }));
+  };
+
+  // The last message sent, for the error banner's retry and fallback.
+  let lastInput: string | undefined;
+
+  // Edit-and-resend branches. Each user message gets an id and the history
+  // length before it; `heads` maps those positions to the message that
+  // starts the live branch there. A branch that's left keeps its history so
+  // select_branch can return to it.
+  type History = ReturnType<typeof agent.getConversation>;
+  let messageSeq = 0;
+  const turnStarts = new Map<string, number>();
+  let heads = new Map<number, string>();
+  const branches = new Map<string, { history: History; heads: Map<number, string> }>();
+  const addUserMessage = (content: string) => {
+    const id = `host-${++messageSeq}`;
+    const start = agent.getConversation().length;
+    turnStarts.set(id, start);
+    heads.set(start, id);
+    tui.addUserMessage(content, id);
+  };
+  const leaveBranch = (start: number) => {
+    const head = heads.get(start);
+    if (head) branches.set(head, { history: agent.getConversation().slice(), heads: new Map(heads) });
+  };
+
+  // The work notes' plan for the TUI's checklist, moved along as tools run,
+  // turns complete and gates are rejected.
+  const planProgress = new PlanProgress();
+  const publishPlan = () => {
+    const steps = agent.getWorkNotes()?.plan.steps ?? [];
+    if (steps.length === 0) return;
+    tui.dispatch(createPlanEvent(planProgress.start(steps)));
+  };
+  const publishPlanUpdates = (updates: PlanUpdate[]) => {
+    for (const { id, status } of updates) tui.dispatch(createPlanUpdateEvent(id, status));
+  };
+  const planFeedback: string[] = [];
+  // Review comments on a change that went ahead, for the next message.
+  const reviewComments: string[] = [];
+
   tui.setSendHandler(async (message: string) => {
     const input = message.trim();
     if (!input) return;
 
-    tui.addUserMessage(input);
+    addUserMessage(input);
+    lastInput = input;
+    await runTurn(input);
+  });
+
+  async function runTurn(input: string) {
     tui.dispatch({ type: "state", timestamp: Date.now(), data: { state: "request_sent" } });
     tui.dispatch({ type: "state", timestamp: Date.now(), data: { state: "awaiting_first_token" } });
 
@@ -1762,37 +1983,93 @@ async function runTUIMode(options: CLIOptions, trace?: (phase: string) => void)
 
     let gotFirst = false;
     const timeout = timeoutFor();
+    // Reasoning models put their thinking inline; it goes to the TUI's
+    // collapsible block instead of the reply.
+    const split = createThinkSplitter();
+    const forward = (part: { text: string; thinking: string }) => {
+      if (part.thinking) tui.dispatch(createThinkingDeltaEvent(part.thinking));
+      if (part.text) tui.dispatch({ type: "text_delta", timestamp: Date.now(), data: { text: part.text } });
+    };
+    // Plan step comments ride along with the next message; they're put back
+    // if the turn fails so a retry still carries them.
+    const feedback = planFeedback.splice(0);
+    const comments = reviewComments.splice(0);
+    const prompt = [
+      input,
+      feedback.length > 0 ? `Feedback on the plan:\n${feedback.join("\n")}` : "",
+      comments.length > 0 ? `Review comments on your last change:\n${comments.join("\n")}` : "",
+    ]
+      .filter(Boolean)
+      .join("\n\n");
 
     try {
-      if (agent.canStream()) {
-        await agent.chatStream(input, (chunk) => {
+      const planFirst =
+        !agent.getWorkNotes() && looksTaskLike(input) && (await agent.getPMState()).preferences.plan_before_tools;
+      // Phases and steps for the TUI's timeline, from what the turn is doing.
+      tui.dispatch(createPhaseEvent("understanding"));
+      tui.dispatch(createStepEvent(`request sent to ${provider.name}`));
+      if (planFirst) {
+        tui.dispatch(createPhaseEvent("planning"));
+        tui.dispatch(createStepEvent("drafting work notes"));
+        await agent.startTask(prompt);
+        publishPlan();
+        const last = agent.getConversation().at(-1);
+        if (last?.role === "assistant" && last.content) {
+          tui.dispatch({ type: "state", timestamp: Date.now(), data: { state: "streaming" } });
+          tui.dispatch({ type: "text_delta", timestamp: Date.now(), data: { text: last.content } });
+        }
+      } else if (agent.canStream()) {
+        await agent.chatStream(prompt, (chunk) => {
           if (!gotFirst) {
             gotFirst = true;
             tui.dispatch({ type: "state", timestamp: Date.now(), data: { state: "streaming" } });
+            tui.dispatch(createStepEvent("streaming reply", "execution"));
           }
-          tui.dispatch({ type: "text_delta", timestamp: Date.now(), data: { text: chunk } });
+          forward(split.push(chunk));
         }, {
           firstTokenTimeoutMs: timeout.first,
           overallTimeoutMs: timeout.overall,
           onFallback: () => {
+            tui.dispatch(createStepEvent("stream failed, retrying without streaming", "execution"));
             if (!gotFirst) {
               tui.dispatch({ type: "state", timestamp: Date.now(), data: { state: "streaming" } });
             }
           },
+          onStateChange: (state, data) => {
+            if (state !== "tool_executing" || !data) return;
+            tui.dispatch(createStepEvent(`running ${data.name}`, "execution"));
+            if (data.id) tui.dispatch(createToolCallEvent(data.name, data.id, data.arguments));
+            publishPlanUpdates(planProgress.toolStarted());
+          },
         });
       } else {
-        await agent.chat(input);
+        tui.dispatch(createStepEvent("waiting for the full reply", "execution"));
+        const before = agent.getConversation().length;
+        await agent.chat(prompt);
         const conversation = agent.getConversation();
+        if (conversation.slice(before).some((m) => m.toolCalls?.length)) {
+          publishPlanUpdates(planProgress.toolStarted());
+        }
         const last = conversation[conversation.length - 1];
         if (last?.role === "assistant" && last.content) {
           tui.dispatch({ type: "state", timestamp: Date.now(), data: { state: "streaming" } });
-          tui.dispatch({ type: "text_delta", timestamp: Date.now(), data: { text: last.content } });
+          forward(split.push(last.content));
         }
       }
+      forward(split.flush());
 
+      reportUsage();
+      // A step waiting on its gate finishes when the gate is answered.
+      if (!agent.getPendingGate?.()) publishPlanUpdates(planProgress.turnCompleted());
       tui.dispatch({ type: "complete", timestamp: Date.now(), data: {} });
       tui.dispatch({ type: "state", timestamp: Date.now(), data: { state: "done" } });
+      raiseGate();
+      publishContext();
+      publishScope();
+      await publishPM();
     } catch (error) {
+      planFeedback.unshift(...feedback);
+      reviewComments.unshift(...comments);
       const message = error instanceof Error ? error.message : String(error);
       tui.dispatch({
         type: "error",
@@ -1801,7 +2078,7 @@ async function runTUIMode(options: CLIOptions, trace?: (phase: string) => void)
       });
       tui.dispatch({ type: "state", timestamp: Date.now(), data: { state: "error" } });
     }
-  });
+  }
 
   tui.setCommandHandler(async (cmd) => {
     const input = cmd.trim();
@@ -1814,26 +2091,6 @@ async function runTUIMode(options: CLIOptions, trace?: (phase: string) => void)
       tui.dispatch({ type: "state", timestamp: Date.now(), data: { state: "done" } });
     };
 
-    const rebuild = async () => {
-      const history = agent.getConversation();
-      const notes = agent.getWorkNotes();
-      agent = createAgent({
-        name: "DAX",
-        mode: options.mode,
-        provider,
-        tools,
-        workDir: options.workDir,
-        llmConfig: policyConfig(policy, options.model),
-      });
-      if (history && agent.loadHistory) agent.loadHistory(history);
-      if (notes && agent.setWorkNotes) agent.setWorkNotes(notes);
-      tui.dispatch({
-        type: "meta",
-        timestamp: Date.now(),
-        data: { provider: provider.name, model: options.model || activeModel(options, provider.name) },
-      });
-    };
-
     tui.addUserMessage(input);
 
     if (input === "/quit" || input === "/exit") {
@@ -1929,8 +2186,13 @@ async function runTUIMode(options: CLIOptions, trace?: (phase: string) => void)
           tui.dispatch({ type: "text_delta", timestamp: Date.now(), data: { text: last.content } });
         }
       }
+      reportUsage();
       tui.dispatch({ type: "complete", timestamp: Date.now(), data: {} });
       tui.dispatch({ type: "state", timestamp: Date.now(), data: { state: "done" } });
+      raiseGate();
+      publishContext();
+      publishScope();
+      await publishPM();
     } catch (error) {
       const message = error instanceof Error ? error.message : String(error);
       tui.dispatch({
@@ -1942,6 +2204,126 @@ async function runTUIMode(options: CLIOptions, trace?: (phase: string) => void)
     }
   });
 
+  tui.setActionHandler?.(async (action) => {
+    switch (action.type) {
+      case "gate_resolution":
+        await resolveGate(action.gate_id, String(action.action));
+        return;
+      case "review_result": {
+        // Pending tool calls can't be rewritten hunk by hunk. A fully
+        // accepted change goes ahead and its comments ride along with the
+        // next message; anything less is rejected and handed back to the
+        // agent with the verdicts, so it re-proposes only the accepted hunks.
+        if (!agent.getPendingGate?.() || action.gate_id !== gateId) return;
+        const review = parseReview(action);
+        if (review.hunks.length === 0) {
+          // Nothing was reviewed, so there is nothing to approve or re-propose.
+          await resolveGate(action.gate_id, "reject");
+          if (review.comments.length > 0) reviewComments.push(formatComments(review.comments));
+          tui.addSystemMessage?.("Empty review: the change was rejected.", "notice");
+          return;
+        }
+        if (acceptsAll(review)) {
+          await resolveGate(action.gate_id, "approve_once");
+          if (review.comments.length > 0) {
+            reviewComments.push(formatComments(review.comments));
+            tui.addSystemMessage?.("Review comments will be sent with your next message.", "notice");
+          }
+          return;
+        }
+        await resolveGate(action.gate_id, "reject");
+        tui.addSystemMessage?.(
+          "Partial review: nothing was applied. The agent is re-proposing the change with only the accepted hunks.",
+          "notice",
+        );
+        const prompt = replanPrompt(review);
+        addUserMessage(prompt);
+        lastInput = prompt;
+        await runTurn(prompt);
+        return;
+      }
+      case "pm_command": {
+        const edit = parsePMEdit(action);
+        // The TUI notes what changed when the new state arrives.
+        const done = edit ? await agent.editPM(edit) : null;
+        if (!done) tui.addSystemMessage?.("Policy unchanged: the edit didn't apply.", "warning");
+        await publishPM();
+        return;
+      }
+      case "plan_feedback": {
+        const steps = agent.getWorkNotes()?.plan.steps ?? [];
+        const title = steps[Number(action.step_id) - 1] ?? String(action.step_id);
+        planFeedback.push(`- ${title}: ${String(action.comment ?? "")}`);
+        tui.addSystemMessage?.("Plan feedback will be sent with your next message.", "notice");
+        return;
+      }
+      case "fork_from": {
+        const start = turnStarts.get(String(action.message_id));
+        const content = String(action.content ?? "").trim();
+        if (start === undefined || !content) {
+          tui.addSystemMessage?.("That message can't be resent: the host has no record of it.", "warning");
+          return;
+        }
+        leaveBranch(start);
+        heads = new Map([...heads].filter(([position]) => position < start));
+        await rebuild(agent.getConversation().slice(0, start));
+        addUserMessage(content);
+        lastInput = content;
+        await runTurn(content);
+        return;
+      }
+      case "select_branch": {
+        const id = String(action.message_id);
+        const start = turnStarts.get(id);
+        const branch = branches.get(id);
+        if (start === undefined || !branch) {
+          tui.addSystemMessage?.("The host has no record of that branch, so replies won't follow it.", "warning");
+          return;
+        }
+        leaveBranch(start);
+        heads = new Map(branch.heads);
+        await rebuild(branch.history);
+        publishContext();
+        return;
+      }
+      case "remove_context_file": {
+        const scope = agent.getWorkNotes()?.scope;
+        if (!scope) return;
+        agent.updateScope({
+          files: scope.files.filter((file) => file !== action.path),
+          maxFiles: scope.max_files,
+          maxLoc: scope.max_loc,
+        });
+        publishContext();
+        return;
+      }
+      case "error_action": {
+        const input = lastInput;
+        if (action.action === "dismiss" || !input) return;
+        if (action.action === "fallback") {
+          const next = await switchToFallback(String(action.message ?? ""));
+          if (!next) {
+            tui.addSystemMessage?.("No fallback model or provider is available.", "warning");
+            return;
+          }
+          tui.addSystemMessage?.("Retrying with " + next, "notice");
+        }
+        // The failed message is already in the agent's history; drop it so
+        // the retry doesn't send it twice.
+        const history = agent.getConversation();
+        const last = history.at(-1);
+        await rebuild(last?.role === "user" && last.content.startsWith(input) ? history.slice(0, -1) : history);
+        await runTurn(input);
+        return;
+      }
+      case "thinking_mode": {
+        const mode = String(action.mode);
+        if (isThinkingMode(mode)) setThinkingMode(ui, mode);
+        return;
+      }
+    }
+  });
+
   tui.focusInput();
   trace?.("tui ready");
 
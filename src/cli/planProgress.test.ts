@@ -0,0 +1,42 @@
+import { describe, expect, test } from "bun:test"
+import { PlanProgress } from "./planProgress.js"
+
+describe("PlanProgress", () => {
+  test("numbers a new plan and sends every step as pending", () => {
+    const progress = new PlanProgress()
+    expect(progress.start(["read", "edit"])).toEqual([
+      { id: "1", title: "read", status: "pending" },
+      { id: "2", title: "edit", status: "pending" },
+    ])
+  })
+
+  test("a tool starts the next step and the turn finishes it", () => {
+    const progress = new PlanProgress()
+    progress.start(["read", "edit"])
+    expect(progress.turnCompleted()).toEqual([])
+    expect(progress.toolStarted()).toEqual([{ id: "1", status: "in_progress" }])
+    expect(progress.toolStarted()).toEqual([])
+    expect(progress.turnCompleted()).toEqual([{ id: "1", status: "done" }])
+    expect(progress.toolStarted()).toEqual([{ id: "2", status: "in_progress" }])
+    expect(progress.turnCompleted()).toEqual([{ id: "2", status: "done" }])
+    expect(progress.toolStarted()).toEqual([])
+  })
+
+  test("a rejected gate skips the step in progress", () => {
+    const progress = new PlanProgress()
+    progress.start(["read", "edit"])
+    expect(progress.gateRejected()).toEqual([])
+    progress.toolStarted()
+    expect(progress.gateRejected()).toEqual([{ id: "1", status: "skipped" }])
+    expect(progress.toolStarted()).toEqual([{ id: "2", status: "in_progress" }])
+  })
+
+  test("a new plan starts from the first step", () => {
+    const progress = new PlanProgress()
+    progress.start(["a", "b"])
+    progress.toolStarted()
+    progress.turnCompleted()
+    progress.start(["c"])
+    expect(progress.toolStarted()).toEqual([{ id: "1", status: "in_progress" }])
+  })
+})
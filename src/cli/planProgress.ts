@@ -0,0 +1,49 @@
+import type { PlanStep, PlanStepStatus } from "./tui/types/stream.js"
+
+export type PlanUpdate = { id: string; status: PlanStepStatus }
+
+/**
+ * Walks the work notes' plan as the agent works. The next step goes in
+ * progress when a tool runs, is done when that turn completes and is
+ * skipped when its gate is rejected. Ids are 1-based positions, as the TUI
+ * numbers steps without one.
+ */
+export class PlanProgress {
+  private status: PlanStepStatus[] = []
+  private cursor = 0
+
+  /** Starts over with a new plan and returns its checklist. */
+  start(steps: string[]): PlanStep[] {
+    this.status = steps.map((): PlanStepStatus => "pending")
+    this.cursor = 0
+    return steps.map((title, i) => ({ id: String(i + 1), title, status: "pending" }))
+  }
+
+  toolStarted(): PlanUpdate[] {
+    if (this.cursor >= this.status.length || this.current() === "in_progress") return []
+    return [this.set("in_progress")]
+  }
+
+  turnCompleted(): PlanUpdate[] {
+    return this.current() === "in_progress" ? [this.advance("done")] : []
+  }
+
+  gateRejected(): PlanUpdate[] {
+    return this.current() === "in_progress" ? [this.advance("skipped")] : []
+  }
+
+  private current() {
+    return this.status[this.cursor]
+  }
+
+  private set(status: PlanStepStatus): PlanUpdate {
+    this.status[this.cursor] = status
+    return { id: String(this.cursor + 1), status }
+  }
+
+  private advance(status: PlanStepStatus) {
+    const update = this.set(status)
+    this.cursor++
+    return update
+  }
+}
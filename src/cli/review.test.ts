@@ -0,0 +1,48 @@
+import { describe, expect, test } from "bun:test"
+import { acceptsAll, parseReview, replanPrompt } from "./review.js"
+
+const result = {
+  type: "review_result",
+  gate_id: "g1",
+  accepted: ["c1:0:0"],
+  rejected: ["c1:0:1"],
+  pending: [],
+  comments: [{ hunk_id: "c1:0:1", path: "src/a.ts", header: "@@ -9 +9 @@", comment: " not this one " }],
+  hunks: [
+    { id: "c1:0:0", path: "src/a.ts", header: "@@ -1 +1 @@", verdict: "accepted" },
+    { id: "c1:0:1", path: "src/a.ts", header: "@@ -9 +9 @@", verdict: "rejected" },
+  ],
+}
+
+describe("review results", () => {
+  test("reads hunks and trims comments", () => {
+    const review = parseReview(result)
+    expect(review.hunks.map((h) => h.verdict)).toEqual(["accepted", "rejected"])
+    expect(review.comments).toEqual([{ path: "src/a.ts", header: "@@ -9 +9 @@", comment: "not this one" }])
+    expect(acceptsAll(review)).toBe(false)
+  })
+
+  test("falls back to the id lists", () => {
+    const review = parseReview({ accepted: ["x"], rejected: [], pending: ["y"] })
+    expect(review.hunks).toEqual([
+      { id: "x", path: "", header: "", verdict: "accepted" },
+      { id: "y", path: "", header: "", verdict: "pending" },
+    ])
+    expect(acceptsAll(review)).toBe(false)
+    expect(acceptsAll(parseReview({ accepted: ["x"] }))).toBe(true)
+  })
+
+  test("accepts nothing from an empty review", () => {
+    expect(acceptsAll(parseReview({}))).toBe(false)
+    expect(acceptsAll(parseReview({ accepted: [], hunks: [], comments: [{ comment: "looks odd" }] }))).toBe(false)
+  })
+
+  test("asks the agent to keep only the accepted hunks", () => {
+    const prompt = replanPrompt(parseReview(result))
+    expect(prompt).toContain("It was not applied.")
+    expect(prompt).toContain("Keep these hunks:\n- src/a.ts @@ -1 +1 @@")
+    expect(prompt).toContain("Drop these hunks:\n- src/a.ts @@ -9 +9 @@")
+    expect(prompt).not.toContain("Not reviewed")
+    expect(prompt).toContain("Comments:\n- src/a.ts @@ -9 +9 @@: not this one")
+  })
+})
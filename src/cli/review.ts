@@ -0,0 +1,88 @@
+export type Verdict = "accepted" | "rejected" | "pending"
+
+export interface ReviewedHunk {
+  id: string
+  path: string
+  header: string
+  verdict: Verdict
+}
+
+export interface ReviewComment {
+  path: string
+  header: string
+  comment: string
+}
+
+export interface Review {
+  hunks: ReviewedHunk[]
+  comments: ReviewComment[]
+}
+
+const VERDICTS: Verdict[] = ["accepted", "rejected", "pending"]
+
+function text(value: unknown) {
+  return typeof value === "string" ? value : ""
+}
+
+function list(value: unknown): Record<string, unknown>[] {
+  return Array.isArray(value) ? value.filter((item) => item && typeof item === "object") : []
+}
+
+/**
+ * Reads a `review_result` from the TUI. Older TUIs send only the id lists,
+ * so hunks missing from `hunks` are filled in from those.
+ */
+export function parseReview(action: Record<string, unknown>): Review {
+  const hunks: ReviewedHunk[] = list(action.hunks).flatMap((hunk) => {
+    const verdict = VERDICTS.find((v) => v === hunk.verdict)
+    return verdict ? [{ id: text(hunk.id), path: text(hunk.path), header: text(hunk.header), verdict }] : []
+  })
+  for (const verdict of VERDICTS) {
+    const ids = Array.isArray(action[verdict]) ? (action[verdict] as unknown[]).map(text) : []
+    for (const id of ids) {
+      if (!hunks.some((hunk) => hunk.id === id)) hunks.push({ id, path: "", header: "", verdict })
+    }
+  }
+  const comments = list(action.comments)
+    .map((c) => ({ path: text(c.path), header: text(c.header), comment: text(c.comment).trim() }))
+    .filter((c) => c.comment)
+  return { hunks, comments }
+}
+
+/**
+ * True when every hunk was accepted, so the change can go ahead as is. A
+ * review with no hunks accepts nothing.
+ */
+export function acceptsAll(review: Review) {
+  return review.hunks.length > 0 && review.hunks.every((hunk) => hunk.verdict === "accepted")
+}
+
+function where(hunk: { path: string; header: string; id?: string }) {
+  return [hunk.path, hunk.header].filter(Boolean).join(" ") || hunk.id || "?"
+}
+
+export function formatComments(comments: ReviewComment[]) {
+  return comments.map((c) => `- ${where(c)}: ${c.comment}`).join("\n")
+}
+
+/**
+ * The message that hands a partly accepted change back to the agent. The
+ * gate has been rejected, so nothing was applied; the agent is asked to
+ * propose the change again with only the accepted hunks.
+ */
+export function replanPrompt(review: Review) {
+  const section = (title: string, verdict: Verdict) => {
+    const hunks = review.hunks.filter((hunk) => hunk.verdict === verdict)
+    return hunks.length > 0 ? `${title}\n${hunks.map((hunk) => `- ${where(hunk)}`).join("\n")}` : ""
+  }
+  return [
+    "I reviewed your proposed change hunk by hunk. It was not applied.",
+    section("Keep these hunks:", "accepted"),
+    section("Drop these hunks:", "rejected"),
+    section("Not reviewed, leave these out for now:", "pending"),
+    review.comments.length > 0 ? `Comments:\n${formatComments(review.comments)}` : "",
+    "Propose the change again with only the hunks to keep, taking the comments into account.",
+  ]
+    .filter(Boolean)
+    .join("\n\n")
+}
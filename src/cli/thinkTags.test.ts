@@ -0,0 +1,34 @@
+import { describe, expect, test } from "bun:test"
+import { createThinkSplitter } from "./thinkTags.js"
+
+function run(chunks: string[]) {
+  const splitter = createThinkSplitter()
+  let text = ""
+  let thinking = ""
+  for (const part of [...chunks.map((chunk) => splitter.push(chunk)), splitter.flush()]) {
+    text += part.text
+    thinking += part.thinking
+  }
+  return { text, thinking }
+}
+
+describe("createThinkSplitter", () => {
+  test("passes plain text through", () => {
+    expect(run(["hello ", "world"])).toEqual({ text: "hello world", thinking: "" })
+  })
+
+  test("separates inline reasoning", () => {
+    expect(run(["<think>weigh a vs b</think>Use a."])).toEqual({ text: "Use a.", thinking: "weigh a vs b" })
+  })
+
+  test("handles tags split across chunks", () => {
+    expect(run(["<thi", "nk>step one", " and two</th", "ink>", "Done"])).toEqual({
+      text: "Done",
+      thinking: "step one and two",
+    })
+  })
+
+  test("keeps a lone angle bracket that isn't a tag", () => {
+    expect(run(["a <", "b"])).toEqual({ text: "a <b", thinking: "" })
+  })
+})
@@ -0,0 +1,54 @@
+const OPEN = "<think>"
+const CLOSE = "</think>"
+
+export interface SplitChunk {
+  text: string
+  thinking: string
+}
+
+/**
+ * Splits streamed content into reply text and the `<think>…</think>`
+ * reasoning that local reasoning models put inline. Tags may arrive split
+ * across chunks, so a trailing partial tag is held back until the next one.
+ */
+export function createThinkSplitter() {
+  let inside = false
+  let pending = ""
+
+  const partialTagAt = (text: string, tag: string) => {
+    for (let n = Math.min(tag.length - 1, text.length); n > 0; n--) {
+      if (text.endsWith(tag.slice(0, n))) return text.length - n
+    }
+    return text.length
+  }
+
+  return {
+    push(chunk: string): SplitChunk {
+      let rest = pending + chunk
+      pending = ""
+      const out: SplitChunk = { text: "", thinking: "" }
+      while (rest) {
+        const tag = inside ? CLOSE : OPEN
+        const at = rest.indexOf(tag)
+        if (at < 0) {
+          const keep = partialTagAt(rest, tag)
+          pending = rest.slice(keep)
+          rest = rest.slice(0, keep)
+        }
+        const part = at < 0 ? rest : rest.slice(0, at)
+        if (inside) out.thinking += part
+        else out.text += part
+        if (at < 0) break
+        rest = rest.slice(at + tag.length)
+        inside = !inside
+      }
+      return out
+    },
+    /** Whatever was held back, once the stream has ended. */
+    flush(): SplitChunk {
+      const rest = pending
+      pending = ""
+      return inside ? { text: "", thinking: rest } : { text: rest, thinking: "" }
+    },
+  }
+}
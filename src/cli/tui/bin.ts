@@ -39,6 +39,11 @@ tui.setCommandHandler((cmd) => {
   console.log("Command:", cmd);
 });
 
+tui.setCommands?.([
+  { name: "/help", description: "Show help" },
+  { name: "/clear", description: "Clear the conversation" },
+]);
+
 tui.setContext({
   files: ["src/main.ts", "src/utils.ts"],
   scope: ["src/", "package.json"],
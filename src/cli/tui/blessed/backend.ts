@@ -427,6 +427,16 @@ export class BlessedBackend implements TUIBackend {
         }
         break;
 
+      case "phase":
+        this.phase = event.data.phase || "";
+        this.step = "";
+        break;
+
+      case "step":
+        if (event.data.phase) this.phase = event.data.phase;
+        this.step = event.data.step || "";
+        break;
+
       case "error":
         this.messages.push({
           role: "system",
@@ -451,6 +461,15 @@ export class BlessedBackend implements TUIBackend {
     this.render();
   }
 
+  addSystemMessage(content: string) {
+    this.messages.push({
+      role: "system",
+      content,
+      timestamp: Date.now(),
+    });
+    this.render();
+  }
+
   setContext(context: ContextState) {
     this.context = context;
     this.render();
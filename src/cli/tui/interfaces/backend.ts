@@ -1,6 +1,8 @@
 import type { DaxStreamEvent, StreamState } from "../types/stream.js";
+import type { ScopeStatus } from "../../../scope/tracker.js";
+import type { PMState, RaoSnapshot } from "../../../pm/types.js";
 
-export type { DaxStreamEvent, StreamState };
+export type { DaxStreamEvent, StreamState, ScopeStatus, PMState, RaoSnapshot };
 
 export interface ChatMessage {
   role: "user" | "assistant" | "system";
@@ -9,6 +11,9 @@ export interface ChatMessage {
   toolCalls?: Array<{ name: string; id: string; status: string }>;
 }
 
+/** Transcript notes that come from the host rather than the model. */
+export type SystemRole = "system" | "notice" | "warning" | "error";
+
 export interface ToolState {
   name: string;
   id: string;
@@ -24,6 +29,23 @@ export interface GateState {
   warnings: Array<{ code: string; subject: string }>;
 }
 
+export interface TuiAction {
+  type: string;
+  [key: string]: unknown;
+}
+
+export interface SlashCommand {
+  name: string;
+  description?: string;
+}
+
+/** A conversation turn as the host has it, for `setSnapshot`. */
+export interface SnapshotMessage {
+  id?: string;
+  role: "user" | "assistant";
+  content: string;
+}
+
 export interface ContextState {
   files: string[];
   scope: string[];
@@ -34,15 +56,49 @@ export interface TUIBackend {
   
   dispatch(event: DaxStreamEvent): void;
   
-  addUserMessage(content: string): void;
+  /**
+   * `id` is a stable message id; the ratatui backend sends it back in
+   * `fork_from` when the user edits and resends the message.
+   */
+  addUserMessage(content: string, id?: string): void;
+
+  addSystemMessage?(content: string, role?: SystemRole): void;
   
   setContext(context: ContextState): void;
+
+  setScopeStatus?(status: ScopeStatus): void;
+
+  /** Constraints and preferences for the policy panel; edits come back as `pm_command` actions. */
+  setPMState?(pm: PMState): void;
+
+  /** RAO snapshots, oldest first, for the history browser. */
+  setRaoHistory?(history: RaoSnapshot[]): void;
   
   updateState(state: StreamState): void;
+
+  /**
+   * The host's transcript for the current session, sent on attach. The
+   * ratatui backend restores its own saved copy at startup and reconciles
+   * it with this.
+   */
+  setSnapshot?(messages: SnapshotMessage[], state?: StreamState): void;
+
+  /**
+   * Session the following calls belong to; the ratatui backend shows each
+   * session in its own tab. Dispatched events use `data.session` if set.
+   * A host that never names a session runs a single one, and the TUI turns
+   * its new-session key off; `runTUIMode` is such a host today.
+   */
+  setSession?(session: string | undefined): void;
   
-  setSendHandler(fn: (message: string) => void | Promise<void>): void;
+  /** `session` is the tab the input was typed in, when the host named one. */
+  setSendHandler(fn: (message: string, session?: string) => void | Promise<void>): void;
   
-  setCommandHandler(fn: (command: string) => void | Promise<void>): void;
+  setCommandHandler(fn: (command: string, session?: string) => void | Promise<void>): void;
+
+  setActionHandler?(fn: (action: TuiAction) => void | Promise<void>): void;
+
+  setCommands?(commands: SlashCommand[]): void;
   
   focusInput(): void;
   
@@ -1,14 +1,28 @@
 import { spawn } from "node:child_process";
 import type { ChildProcess } from "node:child_process";
 import { existsSync } from "node:fs";
-import type { TUIBackend, ContextState, DaxStreamEvent, StreamState } from "../interfaces/backend.js";
+import type {
+  TUIBackend,
+  ContextState,
+  DaxStreamEvent,
+  PMState,
+  RaoSnapshot,
+  ScopeStatus,
+  SlashCommand,
+  SnapshotMessage,
+  StreamState,
+  SystemRole,
+  TuiAction,
+} from "../interfaces/backend.js";
 
 export class RatatuiBackend implements TUIBackend {
   name: "ratatui" = "ratatui";
   private process: ChildProcess | null = null;
-  private onSend?: (message: string) => void | Promise<void>;
-  private onCommand?: (command: string) => void | Promise<void>;
+  private onSend?: (message: string, session?: string) => void | Promise<void>;
+  private onCommand?: (command: string, session?: string) => void | Promise<void>;
+  private onAction?: (action: TuiAction) => void | Promise<void>;
   private ready = false;
+  private session?: string;
 
   constructor() {
     // Check if we have a real TTY, but don't fail - just warn
@@ -41,10 +55,12 @@ export class RatatuiBackend implements TUIBackend {
           if (msg.type === "input") {
             const content = msg.content;
             if (content.startsWith("/")) {
-              void this.onCommand?.(content);
+              void this.onCommand?.(content, msg.session);
             } else {
-              void this.onSend?.(content);
+              void this.onSend?.(content, msg.session);
             }
+          } else if (typeof msg.type === "string") {
+            void this.onAction?.(msg as TuiAction);
           }
         } catch {
           // Non-JSON output (logs, etc.)
@@ -85,38 +101,54 @@ export class RatatuiBackend implements TUIBackend {
     return null;
   }
 
-  private send(message: object) {
+  private send(message: object, session = this.session) {
     if (this.process?.stdin && this.ready) {
-      this.process.stdin.write(JSON.stringify(message) + "\n");
+      this.process.stdin.write(JSON.stringify(session ? { ...message, session } : message) + "\n");
     }
   }
 
   dispatch(event: DaxStreamEvent) {
+    const send = (message: object) => this.send(message, event.data.session ?? this.session);
     switch (event.type) {
       case "meta":
-        this.send({
+        send({
           type: "dispatch",
+          timestamp: event.timestamp,
           event: { type: "meta", data: { provider: event.data.provider, model: event.data.model } },
         });
         break;
       case "state":
-        this.send({ type: "dispatch", event: { type: "state", data: { state: event.data.state } } });
+        send({ type: "dispatch", timestamp: event.timestamp, event: { type: "state", data: { state: event.data.state } } });
         break;
       case "text_delta":
-        this.send({ type: "dispatch", event: { type: "text_delta", data: { text: event.data.text } } });
+        send({ type: "dispatch", timestamp: event.timestamp, event: { type: "text_delta", data: { text: event.data.text } } });
+        break;
+      case "thinking_delta":
+        send({
+          type: "dispatch",
+          timestamp: event.timestamp,
+          event: { type: "thinking_delta", data: { text: event.data.text } },
+        });
         break;
       case "tool_call":
-        this.send({
+        send({
           type: "dispatch",
+          timestamp: event.timestamp,
           event: {
             type: "tool_call",
-            data: { name: event.data.tool?.name, id: event.data.tool?.id },
+            data: {
+              name: event.data.tool?.name,
+              id: event.data.tool?.id,
+              arguments: event.data.tool?.arguments,
+              diff: event.data.tool?.diff,
+            },
           },
         });
         break;
       case "tool_result":
-        this.send({
+        send({
           type: "dispatch",
+          timestamp: event.timestamp,
           event: {
             type: "tool_result",
             data: {
@@ -124,19 +156,60 @@ export class RatatuiBackend implements TUIBackend {
               success: event.data.result?.success,
               output: event.data.result?.output,
               elapsed_ms: event.data.result?.elapsed_ms,
+              diff: event.data.result?.diff,
             },
           },
         });
         break;
+      case "phase":
+        send({ type: "dispatch", timestamp: event.timestamp, event: { type: "phase", data: { phase: event.data.phase } } });
+        break;
+      case "step":
+        send({
+          type: "dispatch",
+          timestamp: event.timestamp,
+          event: { type: "step", data: { phase: event.data.phase, step: event.data.step } },
+        });
+        break;
+      case "plan":
+        send({
+          type: "dispatch",
+          timestamp: event.timestamp,
+          event: { type: "plan", data: { title: event.data.plan?.title, steps: event.data.plan?.steps ?? [] } },
+        });
+        break;
+      case "plan_update":
+        if (!event.data.plan_update) break;
+        send({
+          type: "dispatch",
+          timestamp: event.timestamp,
+          event: { type: "plan_update", data: event.data.plan_update },
+        });
+        break;
+      case "usage":
+        send({ type: "dispatch", timestamp: event.timestamp, event: { type: "usage", data: event.data.usage ?? {} } });
+        break;
       case "complete":
-        this.send({ type: "dispatch", event: { type: "complete", data: {} } });
+        send({ type: "dispatch", timestamp: event.timestamp, event: { type: "complete", data: {} } });
         break;
       case "error":
-        this.send({ type: "dispatch", event: { type: "error", data: { message: event.data.error?.message } } });
+        send({
+          type: "dispatch",
+          timestamp: event.timestamp,
+          event: {
+            type: "error",
+            data: {
+              message: event.data.error?.message,
+              code: event.data.error?.code,
+              recoverable: event.data.error?.recoverable,
+            },
+          },
+        });
         break;
       case "gate":
-        this.send({
+        send({
           type: "dispatch",
+          timestamp: event.timestamp,
           event: {
             type: "gate",
             data: { id: event.data.gate?.id, blocked: event.data.gate?.blocked, warnings: event.data.gate?.warnings },
@@ -144,9 +217,10 @@ export class RatatuiBackend implements TUIBackend {
         });
         break;
       case "gate_resolved":
-        this.send({
+        send({
           type: "dispatch",
-          event: { type: "gate", data: { id: event.data.resolution?.action } },
+          timestamp: event.timestamp,
+          event: { type: "gate_resolved", data: { action: event.data.resolution?.action } },
         });
         break;
       case "tool_start":
@@ -156,26 +230,58 @@ export class RatatuiBackend implements TUIBackend {
     }
   }
 
-  addUserMessage(content: string) {
-    this.send({ type: "addUserMessage", content });
+  addUserMessage(content: string, id?: string) {
+    this.send({ type: "addUserMessage", content, id });
+  }
+
+  addSystemMessage(content: string, role: SystemRole = "notice") {
+    this.send({ type: "addSystemMessage", content, role });
   }
 
   setContext(context: ContextState) {
     this.send({ type: "setContext", files: context.files, scope: context.scope });
   }
 
+  setScopeStatus(status: ScopeStatus) {
+    this.send({ type: "scopeStatus", ...status });
+  }
+
+  setPMState(pm: PMState) {
+    this.send({ type: "pmState", ...pm });
+  }
+
+  setRaoHistory(history: RaoSnapshot[]) {
+    this.send({ type: "raoHistory", history });
+  }
+
   updateState(state: StreamState) {
     this.send({ type: "updateState", state });
   }
 
-  setSendHandler(fn: (message: string) => void | Promise<void>) {
+  setSnapshot(messages: SnapshotMessage[], state?: StreamState) {
+    this.send({ type: "snapshot", messages, state });
+  }
+
+  setCommands(commands: SlashCommand[]) {
+    this.send({ type: "setCommands", commands });
+  }
+
+  setSession(session: string | undefined) {
+    this.session = session;
+  }
+
+  setSendHandler(fn: (message: string, session?: string) => void | Promise<void>) {
     this.onSend = fn;
   }
 
-  setCommandHandler(fn: (command: string) => void | Promise<void>) {
+  setCommandHandler(fn: (command: string, session?: string) => void | Promise<void>) {
     this.onCommand = fn;
   }
 
+  setActionHandler(fn: (action: TuiAction) => void | Promise<void>) {
+    this.onAction = fn;
+  }
+
   focusInput() {
     // Ratatui handles this automatically
   }
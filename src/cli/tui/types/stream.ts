@@ -11,6 +11,7 @@ export interface DaxStreamEvent {
     | "meta"
     | "state"
     | "text_delta"
+    | "thinking_delta"
     | "tool_call"
     | "tool_result"
     | "tool_start"
@@ -19,11 +20,25 @@ export interface DaxStreamEvent {
     | "gate"
     | "gate_resolved"
     | "error"
+    | "usage"
+    | "phase"
+    | "step"
+    | "plan"
+    | "plan_update"
     | "complete";
   timestamp: number;
   data: DaxStreamData;
 }
 
+export type PlanStepStatus = "pending" | "in_progress" | "done" | "skipped";
+
+export interface PlanStep {
+  id?: string;
+  title: string;
+  status?: PlanStepStatus;
+  substeps?: PlanStep[];
+}
+
 export interface DaxStreamData {
   provider?: string;
   model?: string;
@@ -34,6 +49,7 @@ export interface DaxStreamData {
     name: string;
     id: string;
     arguments?: string;
+    diff?: string;
   };
   result?: {
     tool_id: string;
@@ -41,6 +57,7 @@ export interface DaxStreamData {
     output?: string;
     error?: string;
     elapsed_ms?: number;
+    diff?: string;
   };
   gate?: {
     id: string;
@@ -57,8 +74,25 @@ export interface DaxStreamData {
     message: string;
     recoverable: boolean;
   };
+  usage?: {
+    input_tokens?: number;
+    output_tokens?: number;
+    cached_tokens?: number;
+    cost_usd?: number;
+    context_tokens?: number;
+    context_window?: number;
+  };
   phase?: string;
   step?: string;
+  plan?: {
+    title?: string;
+    steps: PlanStep[];
+  };
+  plan_update?: {
+    id: string;
+    status?: PlanStepStatus;
+    title?: string;
+  };
 }
 
 export function createEvent(
@@ -84,13 +118,18 @@ export function createTextDeltaEvent(text: string): DaxStreamEvent {
   return createEvent("text_delta", { text });
 }
 
+export function createThinkingDeltaEvent(text: string): DaxStreamEvent {
+  return createEvent("thinking_delta", { text });
+}
+
 export function createToolCallEvent(
   name: string,
   id: string,
-  arguments_?: string
+  arguments_?: string,
+  diff?: string
 ): DaxStreamEvent {
   return createEvent("tool_call", {
-    tool: { name, id, arguments: arguments_ },
+    tool: { name, id, arguments: arguments_, diff },
   });
 }
 
@@ -126,6 +165,30 @@ export function createErrorEvent(
   });
 }
 
+export function createPhaseEvent(phase: string): DaxStreamEvent {
+  return createEvent("phase", { phase });
+}
+
+export function createStepEvent(step: string, phase?: string): DaxStreamEvent {
+  return createEvent("step", { step, phase });
+}
+
+export function createPlanEvent(steps: PlanStep[], title?: string): DaxStreamEvent {
+  return createEvent("plan", { plan: { title, steps } });
+}
+
+export function createPlanUpdateEvent(
+  id: string,
+  status?: PlanStepStatus,
+  title?: string
+): DaxStreamEvent {
+  return createEvent("plan_update", { plan_update: { id, status, title } });
+}
+
+export function createUsageEvent(usage: NonNullable<DaxStreamData["usage"]>): DaxStreamEvent {
+  return createEvent("usage", { usage });
+}
+
 export function createCompleteEvent(): DaxStreamEvent {
   return createEvent("complete", {});
 }
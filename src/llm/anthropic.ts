@@ -89,10 +89,13 @@ export class AnthropicProvider implements LLMProvider {
         content: content?.type === "text" ? content.text : "",
         tool_calls: toolCalls.length > 0 ? toolCalls : undefined,
         usage: {
-          prompt_tokens: data.usage?.input_tokens || 0,
+          prompt_tokens: (data.usage?.input_tokens || 0) + (data.usage?.cache_read_input_tokens || 0),
           completion_tokens: data.usage?.output_tokens || 0,
           total_tokens:
-            (data.usage?.input_tokens || 0) + (data.usage?.output_tokens || 0),
+            (data.usage?.input_tokens || 0) +
+            (data.usage?.cache_read_input_tokens || 0) +
+            (data.usage?.output_tokens || 0),
+          cached_tokens: data.usage?.cache_read_input_tokens || 0,
         },
       }
     } catch (error) {
@@ -67,7 +67,10 @@ export class OpenAIProvider implements LLMProvider {
       return {
         content: choice.message.content || "",
         tool_calls: choice.message.tool_calls,
-        usage: data.usage,
+        usage: data.usage && {
+          ...data.usage,
+          cached_tokens: data.usage.prompt_tokens_details?.cached_tokens || 0,
+        },
       }
     } catch (error) {
       if (error instanceof LLMError) throw error
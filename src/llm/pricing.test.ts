@@ -0,0 +1,29 @@
+import { describe, expect, test } from "bun:test"
+import { contextWindow, estimateCost } from "./pricing"
+
+describe("estimateCost", () => {
+  test("bills cached prompt tokens at the cached rate", () => {
+    const cost = estimateCost("gpt-4o", { input_tokens: 1_000_000, output_tokens: 100_000, cached_tokens: 400_000 })
+    expect(cost).toBeCloseTo(0.6 * 2.5 + 0.4 * 1.25 + 0.1 * 10)
+  })
+
+  test("uses the longest matching model prefix", () => {
+    expect(estimateCost("gpt-4o-mini-2024-07-18", { input_tokens: 1_000_000, output_tokens: 0 })).toBeCloseTo(0.15)
+    expect(estimateCost("claude-sonnet-4-20250514", { input_tokens: 0, output_tokens: 1_000_000 })).toBeCloseTo(15)
+  })
+
+  test("has no estimate for unknown or local models", () => {
+    expect(estimateCost("phi3:mini-128k", { input_tokens: 10, output_tokens: 10 })).toBeUndefined()
+    expect(estimateCost("default", { input_tokens: 10, output_tokens: 10 })).toBeUndefined()
+  })
+})
+
+describe("contextWindow", () => {
+  test("knows hosted and local models", () => {
+    expect(contextWindow("gpt-4o")).toBe(128_000)
+    expect(contextWindow("claude-sonnet-4-5")).toBe(200_000)
+    expect(contextWindow("phi3:mini-128k")).toBe(128_000)
+    expect(contextWindow("phi3:latest")).toBe(4_096)
+    expect(contextWindow("default")).toBeUndefined()
+  })
+})
@@ -0,0 +1,59 @@
+/** USD per million tokens. Cached prompt tokens are billed at `cached`. */
+interface Rate {
+  input: number
+  cached: number
+  output: number
+}
+
+// List prices; the longest prefix that matches the model wins. Local and
+// subscription-only models are left out, so they get no estimate.
+const RATES: Record<string, Rate> = {
+  "gpt-4o": { input: 2.5, cached: 1.25, output: 10 },
+  "gpt-4o-mini": { input: 0.15, cached: 0.075, output: 0.6 },
+  "gpt-4.1": { input: 2, cached: 0.5, output: 8 },
+  "gpt-4.1-mini": { input: 0.4, cached: 0.1, output: 1.6 },
+  "gpt-5": { input: 1.25, cached: 0.125, output: 10 },
+  "gpt-5-mini": { input: 0.25, cached: 0.025, output: 2 },
+  "claude-opus-4": { input: 15, cached: 1.5, output: 75 },
+  "claude-sonnet-4": { input: 3, cached: 0.3, output: 15 },
+  "claude-3-5-haiku": { input: 0.8, cached: 0.08, output: 4 },
+  "gemini-2.5-pro": { input: 1.25, cached: 0.31, output: 10 },
+  "gemini-2.5-flash": { input: 0.3, cached: 0.075, output: 2.5 },
+  "gemini-2.0-flash": { input: 0.1, cached: 0.025, output: 0.4 },
+}
+
+const WINDOWS: Record<string, number> = {
+  "gpt-4o": 128_000,
+  "gpt-4.1": 1_047_576,
+  "gpt-5": 400_000,
+  "claude-": 200_000,
+  "gemini-": 1_048_576,
+  "phi3": 4_096,
+  "phi3:mini-128k": 128_000,
+  "llama3.2": 128_000,
+  "codellama": 16_000,
+}
+
+function lookup<T>(table: Record<string, T>, model: string): T | undefined {
+  const key = Object.keys(table)
+    .filter((prefix) => model.startsWith(prefix))
+    .sort((a, b) => b.length - a.length)[0]
+  return key === undefined ? undefined : table[key]
+}
+
+/** Estimated USD for one report's tokens, if the model's price is known. */
+export function estimateCost(
+  model: string,
+  usage: { input_tokens: number; output_tokens: number; cached_tokens?: number },
+): number | undefined {
+  const rate = lookup(RATES, model)
+  if (!rate) return undefined
+  const cached = Math.min(usage.cached_tokens ?? 0, usage.input_tokens)
+  const fresh = usage.input_tokens - cached
+  return (fresh * rate.input + cached * rate.cached + usage.output_tokens * rate.output) / 1_000_000
+}
+
+/** The model's context window in tokens, if known. */
+export function contextWindow(model: string): number | undefined {
+  return lookup(WINDOWS, model)
+}
@@ -34,6 +34,8 @@ export interface LLMResponse {
     prompt_tokens: number
     completion_tokens: number
     total_tokens: number
+    /** Prompt tokens served from the provider's cache, included in `prompt_tokens`. */
+    cached_tokens?: number
   }
 }
 
@@ -0,0 +1,51 @@
+import { describe, expect, test } from "bun:test"
+import { applyPMEdit, parsePMEdit } from "./commands.js"
+import { defaultPMState } from "./types.js"
+
+function basePm() {
+  const pm = defaultPMState("p1")
+  pm.constraints = {
+    never_touch: ["src/auth/**"],
+    always_allow: [{ kind: "tool", pattern: "read_file" }],
+  }
+  return pm
+}
+
+describe("parsePMEdit", () => {
+  test("reads the panel's pm_command payloads", () => {
+    expect(parsePMEdit({ type: "pm_command", op: "add_never_touch", pattern: " dist/** " })).toEqual({
+      op: "add_never_touch",
+      pattern: "dist/**",
+    })
+    expect(
+      parsePMEdit({ type: "pm_command", op: "revoke_always_allow", rule: { kind: "tool", pattern: "read_file" } }),
+    ).toEqual({ op: "revoke_always_allow", rule: { kind: "tool", pattern: "read_file" } })
+  })
+
+  test("rejects unknown ops and incomplete fields", () => {
+    expect(parsePMEdit({ op: "drop_table" })).toBeNull()
+    expect(parsePMEdit({ op: "add_never_touch", pattern: "  " })).toBeNull()
+    expect(parsePMEdit({ op: "add_always_allow", rule: { kind: "host", pattern: "x" } })).toBeNull()
+  })
+})
+
+describe("applyPMEdit", () => {
+  test("edits never_touch globs", () => {
+    const pm = basePm()
+    const added = applyPMEdit({ op: "add_never_touch", pattern: "dist/**" }, pm)
+    expect(added.updates.constraints?.never_touch).toEqual(["src/auth/**", "dist/**"])
+    const replaced = applyPMEdit({ op: "replace_never_touch", from: "src/auth/**", to: "src/secrets/**" }, pm)
+    expect(replaced.updates.constraints?.never_touch).toEqual(["src/secrets/**"])
+    expect(applyPMEdit({ op: "remove_never_touch", pattern: "missing" }, pm).applied).toBe(false)
+  })
+
+  test("adds and revokes always_allow rules without touching the rest", () => {
+    const pm = basePm()
+    const added = applyPMEdit({ op: "add_always_allow", rule: { kind: "path", pattern: "docs/**" } }, pm)
+    expect(added.updates.constraints?.always_allow).toHaveLength(2)
+    expect(added.updates.constraints?.never_touch).toEqual(["src/auth/**"])
+    const revoked = applyPMEdit({ op: "revoke_always_allow", rule: { kind: "tool", pattern: "read_file" } }, pm)
+    expect(revoked.updates.constraints?.always_allow).toEqual([])
+    expect(applyPMEdit({ op: "add_always_allow", rule: { kind: "tool", pattern: "read_file" } }, pm).applied).toBe(false)
+  })
+})
@@ -1,4 +1,4 @@
-import type { PMState, RiskPosture, Verbosity } from "./types.js"
+import type { AllowRule, PMState, RiskPosture, Verbosity } from "./types.js"
 
 export interface PMCommandResult {
   applied: boolean
@@ -72,3 +72,77 @@ export function applyPMCommands(text: string, pm: PMState): PMCommandResult {
 
   return { applied: false, updates: {} }
 }
+
+/** A structured constraints edit, as sent by the TUI policy panel (`pm_command`). */
+export type PMEdit =
+  | { op: "add_never_touch"; pattern: string }
+  | { op: "remove_never_touch"; pattern: string }
+  | { op: "replace_never_touch"; from: string; to: string }
+  | { op: "add_always_allow"; rule: AllowRule }
+  | { op: "revoke_always_allow"; rule: AllowRule }
+
+export function parsePMEdit(value: Record<string, unknown>): PMEdit | null {
+  const text = (key: string) => (typeof value[key] === "string" ? (value[key] as string).trim() : "")
+  const rule = (): AllowRule | null => {
+    const raw = value.rule as { kind?: unknown; pattern?: unknown } | undefined
+    if (!raw || typeof raw.pattern !== "string" || !raw.pattern.trim()) return null
+    if (raw.kind !== "tool" && raw.kind !== "path") return null
+    return { kind: raw.kind, pattern: raw.pattern.trim() }
+  }
+  switch (value.op) {
+    case "add_never_touch":
+    case "remove_never_touch":
+      return text("pattern") ? { op: value.op, pattern: text("pattern") } : null
+    case "replace_never_touch":
+      return text("from") && text("to") ? { op: value.op, from: text("from"), to: text("to") } : null
+    case "add_always_allow":
+    case "revoke_always_allow": {
+      const parsed = rule()
+      return parsed ? { op: value.op, rule: parsed } : null
+    }
+    default:
+      return null
+  }
+}
+
+export function applyPMEdit(edit: PMEdit, pm: PMState): PMCommandResult {
+  const neverTouch = pm.constraints.never_touch || []
+  const allow = pm.constraints.always_allow || []
+  const same = (a: AllowRule, b: AllowRule) => a.kind === b.kind && a.pattern === b.pattern
+  const constraints = (next: Partial<PMState["constraints"]>, confirmation: string): PMCommandResult => ({
+    applied: true,
+    updates: { constraints: { ...pm.constraints, ...next } },
+    confirmation,
+  })
+  switch (edit.op) {
+    case "add_never_touch":
+      return constraints(
+        { never_touch: Array.from(new Set([...neverTouch, edit.pattern])) },
+        `Added never-touch constraint '${edit.pattern}'.`,
+      )
+    case "remove_never_touch":
+      if (!neverTouch.includes(edit.pattern)) return { applied: false, updates: {} }
+      return constraints(
+        { never_touch: neverTouch.filter((glob) => glob !== edit.pattern) },
+        `Removed never-touch constraint '${edit.pattern}'.`,
+      )
+    case "replace_never_touch":
+      if (!neverTouch.includes(edit.from)) return { applied: false, updates: {} }
+      return constraints(
+        { never_touch: Array.from(new Set(neverTouch.map((glob) => (glob === edit.from ? edit.to : glob)))) },
+        `Changed never-touch constraint '${edit.from}' to '${edit.to}'.`,
+      )
+    case "add_always_allow":
+      if (allow.some((row) => same(row, edit.rule))) return { applied: false, updates: {} }
+      return constraints(
+        { always_allow: [...allow, edit.rule] },
+        `Always allowing ${edit.rule.kind} '${edit.rule.pattern}'.`,
+      )
+    case "revoke_always_allow":
+      if (!allow.some((row) => same(row, edit.rule))) return { applied: false, updates: {} }
+      return constraints(
+        { always_allow: allow.filter((row) => !same(row, edit.rule)) },
+        `Revoked always allow ${edit.rule.kind} '${edit.rule.pattern}'.`,
+      )
+  }
+}